@@ -1,2 +1,3 @@
 pub mod pipeline;
+pub mod policy_lint;
 pub mod reporting;
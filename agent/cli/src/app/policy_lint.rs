@@ -0,0 +1,68 @@
+use std::fs;
+use std::path::Path;
+
+use rust_core::adapters::serialization::{
+    LintSeverityDto, PolicyDto, PolicyLintDto, PolicyUpsertDto, validate_policy,
+};
+use rust_core::domain::policy::PolicySet;
+use rust_core::domain::policy_lint::lint_policy;
+
+#[derive(Debug)]
+pub struct PolicyLintError {
+    pub message: String,
+}
+
+impl PolicyLintError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+/// Lints a policy file before it is published. The file holds either a
+/// policy or a `POST /v1/policies` body (`device_platform` + `policy`).
+pub fn lint_policy_file(
+    path: &Path,
+    known_actions: &[String],
+) -> Result<Vec<PolicyLintDto>, PolicyLintError> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| PolicyLintError::new(format!("{}: {}", path.display(), err)))?;
+    let policy = parse_policy(&contents)?;
+    validate_policy(&policy).map_err(|err| PolicyLintError::new(err.message))?;
+
+    let known_actions = known_actions.iter().map(String::as_str).collect::<Vec<_>>();
+    Ok(lint_policy(&PolicySet::from(policy), &known_actions)
+        .into_iter()
+        .map(PolicyLintDto::from)
+        .collect())
+}
+
+pub fn has_errors(lints: &[PolicyLintDto]) -> bool {
+    lints
+        .iter()
+        .any(|lint| lint.severity == LintSeverityDto::Error)
+}
+
+/// One line per finding, e.g. `error: rule 1 (transfer): never matches: ...`.
+pub fn format_lint(lint: &PolicyLintDto) -> String {
+    let severity = match lint.severity {
+        LintSeverityDto::Error => "error",
+        LintSeverityDto::Warning => "warning",
+    };
+    match lint.rule_index {
+        Some(index) => format!(
+            "{}: rule {} ({}): {}",
+            severity, index, lint.action, lint.message
+        ),
+        None => format!("{}: {}: {}", severity, lint.action, lint.message),
+    }
+}
+
+fn parse_policy(contents: &str) -> Result<PolicyDto, PolicyLintError> {
+    if let Ok(upsert) = serde_json::from_str::<PolicyUpsertDto>(contents) {
+        return Ok(upsert.policy);
+    }
+    serde_json::from_str::<PolicyDto>(contents)
+        .map_err(|err| PolicyLintError::new(format!("invalid policy: {}", err)))
+}
@@ -4,7 +4,7 @@ use reqwest::blocking::Client;
 use serde::Serialize;
 use uuid::Uuid;
 
-use crate::domain::report::{Finding, Report, Severity};
+use crate::domain::report::{Report, Severity};
 
 #[derive(Debug)]
 pub struct ReportError {
@@ -95,7 +95,7 @@ pub fn build_payload(
         payload: STANDARD.encode(report_json.as_bytes()),
     };
 
-    let pipeline = match (options.pipeline_provider, options.pipeline_run_id) {
+    let pipeline = match (options.pipeline_provider.clone(), options.pipeline_run_id.clone()) {
         (Some(provider), Some(run_id)) => Some(PipelineInfoDto { provider, run_id }),
         _ => None,
     };
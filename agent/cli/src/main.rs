@@ -1,4 +1,8 @@
+use std::path::{Path, PathBuf};
+use std::process;
+
 use agent_cli::app::pipeline::Pipeline;
+use agent_cli::app::policy_lint::{format_lint, has_errors, lint_policy_file};
 use agent_cli::app::reporting::{build_payload, submit_report, ReportOptions};
 use agent_cli::plugins::registry::{builtin_plugins, plugin_by_name};
 use clap::{Parser, Subcommand};
@@ -34,6 +38,16 @@ enum Commands {
         #[arg(long, default_value_t = false)]
         dry_run: bool,
     },
+    /// Checks a policy for shadowed, duplicate and unreachable rules; exits 1 on errors.
+    LintPolicy {
+        /// Policy JSON, or a `POST /v1/policies` body.
+        file: PathBuf,
+        /// Actions to accept besides the built-in ones (comma-separated).
+        #[arg(long, value_delimiter = ',')]
+        known_actions: Vec<String>,
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -113,5 +127,123 @@ fn main() {
 
             println!("report uploaded. findings: {}", report.findings.len());
         }
+        Commands::LintPolicy {
+            file,
+            known_actions,
+            json,
+        } => {
+            let code = lint_policy(&file, &known_actions, json);
+            if code != 0 {
+                process::exit(code);
+            }
+        }
+    }
+}
+
+/// Runs `agent lint-policy` and returns its exit status: 0 when clean or only
+/// warnings, 1 on error-level findings, 2 when the file cannot be linted.
+fn lint_policy(file: &Path, known_actions: &[String], json: bool) -> i32 {
+    let lints = match lint_policy_file(file, known_actions) {
+        Ok(lints) => lints,
+        Err(err) => {
+            eprintln!("policy lint failed: {}", err.message);
+            return 2;
+        }
+    };
+
+    if json {
+        let output = serde_json::to_string_pretty(&lints).unwrap_or_else(|_| "[]".to_string());
+        println!("{}", output);
+    } else {
+        for lint in &lints {
+            println!("{}", format_lint(lint));
+        }
+        println!(
+            "lint {} completed. findings: {}",
+            file.display(),
+            lints.len()
+        );
+    }
+
+    if has_errors(&lints) { 1 } else { 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn write_policy(contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("lint-policy-{}.json", uuid::Uuid::new_v4()));
+        fs::write(&path, contents).expect("write policy");
+        path
+    }
+
+    fn policy(rules: &str) -> String {
+        format!(
+            r#"{{"policy_id":"pol_1","app_id":"fintech.mobile","app_version":"1.0.0","env":"prod","rules":{},"signature":"sig","issued_at":"2026-10-18T10:00:00Z"}}"#,
+            rules
+        )
+    }
+
+    #[test]
+    fn clean_policy_exits_zero() {
+        let path = write_policy(&policy(
+            r#"[{"action":"transfer","decision":"DENY","conditions":{"debugger":true}},{"action":"login","decision":"STEP_UP","conditions":{"attestation":"fail"}}]"#,
+        ));
+        assert!(lint_policy_file(&path, &[]).expect("lint").is_empty());
+        assert_eq!(lint_policy(&path, &[], false), 0);
+
+        let upsert = write_policy(&format!(
+            r#"{{"device_platform":"ios","policy":{}}}"#,
+            policy(r#"[{"action":"transfer","decision":"DENY","conditions":{"debugger":true}}]"#)
+        ));
+        assert_eq!(lint_policy(&upsert, &[], true), 0);
+        fs::remove_file(path).ok();
+        fs::remove_file(upsert).ok();
+    }
+
+    #[test]
+    fn error_findings_exit_non_zero() {
+        let path = write_policy(&policy(
+            r#"[{"action":"transfer","decision":"DENY","conditions":{"risk_score_gte":150}}]"#,
+        ));
+        let lints = lint_policy_file(&path, &[]).expect("lint");
+        assert!(has_errors(&lints));
+        assert_eq!(lint_policy(&path, &[], false), 1);
+        assert_eq!(lint_policy(&path, &[], true), 1);
+
+        // Warnings alone keep the exit status at 0.
+        let unknown = write_policy(&policy(
+            r#"[{"action":"export_data","decision":"DENY","conditions":{"debugger":true}}]"#,
+        ));
+        assert!(!lint_policy_file(&unknown, &[]).expect("lint").is_empty());
+        assert_eq!(lint_policy(&unknown, &[], false), 0);
+        assert!(
+            lint_policy_file(&unknown, &["export_data".to_string()])
+                .expect("lint")
+                .is_empty()
+        );
+        fs::remove_file(path).ok();
+        fs::remove_file(unknown).ok();
+    }
+
+    #[test]
+    fn unreadable_or_invalid_files_exit_two() {
+        let missing =
+            std::env::temp_dir().join(format!("lint-policy-{}.json", uuid::Uuid::new_v4()));
+        assert!(lint_policy_file(&missing, &[]).is_err());
+        assert_eq!(lint_policy(&missing, &[], false), 2);
+
+        let malformed = write_policy("{not json");
+        assert_eq!(lint_policy(&malformed, &[], false), 2);
+
+        let no_rules = write_policy(&policy("[]"));
+        let err = lint_policy_file(&no_rules, &[]).expect_err("empty rules");
+        assert!(err.message.contains("rules"));
+        assert_eq!(lint_policy(&no_rules, &[], false), 2);
+        fs::remove_file(malformed).ok();
+        fs::remove_file(no_rules).ok();
     }
 }
@@ -6,22 +6,91 @@ use axum::{
     http::StatusCode,
     http::{header::AUTHORIZATION, HeaderMap},
     response::Html,
-    routing::{get, post},
+    routing::{delete, get, post},
     Json, Router,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection};
+use rust_core::adapters::crypto::Ed25519Signer;
 use rust_core::adapters::serialization::{
-    validate_policy, validate_report_upload, AttestationStatusDto, DecisionDto, PolicyConditionsDto,
-    PolicyDto, PolicyRuleDto, PolicyUpsertDto, PolicyUpsertResponse, ReportUploadDto,
+    compute_risk, validate_policy, validate_policy_override_request, validate_policy_rollout,
+    validate_policy_rollback, validate_policy_rollout_target, validate_report_upload, AttestationStatusDto, DecisionDto,
+    PolicyConditionsDto, PolicyDto, PolicyOverrideDto, PolicyOverrideRequestDto, PolicyRollbackDto, PolicyRolloutDto,
+    PolicyLintDto, PolicyRolloutTargetDto, PolicyRuleDto, PolicyUpsertDto, PolicyUpsertResponse, ReportUploadDto,
+    RuleChangeDto,
 };
+use rust_core::domain::policy::{PolicyRule, PolicySet};
+use rust_core::domain::policy_diff::diff_rules;
+use rust_core::domain::policy_lint::{lint_policy, LintSeverity};
+use rust_core::domain::version::{Version, VersionRange};
+use rust_core::ports::CryptoSigner;
 use serde::Deserialize;
-use tracing::info;
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
 
 #[derive(Clone)]
 struct AppState {
     db: Arc<Mutex<Connection>>,
     api_token: Option<String>,
+    /// Bearer token for `/v1/admin/*`; falls back to `api_token`.
+    admin_token: Option<String>,
+    signer: Ed25519Signer,
+    lint_mode: LintMode,
+    /// Actions the policy linter accepts on top of `KNOWN_ACTIONS`.
+    known_actions: Vec<String>,
+}
+
+/// What policy upserts do with error-level lint findings (`POLICY_LINT_MODE`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LintMode {
+    Reject,
+    Warn,
+}
+
+/// `device_platform` value for policies that apply to every platform of an app/env.
+const ANY_PLATFORM: &str = "any";
+
+/// Response header naming the tier `GET /v1/policies/current` resolved from.
+const POLICY_RESOLUTION_HEADER: &str = "x-policy-resolution";
+
+/// Response header telling whether `GET /v1/policies/current` served the
+/// current policy or a staged rollout candidate.
+const POLICY_ROLLOUT_HEADER: &str = "x-policy-rollout";
+
+/// Lookup tiers of `GET /v1/policies/current`, tried in declaration order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PolicyResolution {
+    Exact,
+    VersionRange,
+    PlatformAny,
+    OrgDefault,
+}
+
+impl PolicyResolution {
+    fn as_str(&self) -> &'static str {
+        match self {
+            PolicyResolution::Exact => "exact",
+            PolicyResolution::VersionRange => "version_range",
+            PolicyResolution::PlatformAny => "platform_any",
+            PolicyResolution::OrgDefault => "org_default",
+        }
+    }
+}
+
+/// Which version of a slot with a staged rollout a device was served.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RolloutVariant {
+    Current,
+    Candidate,
+}
+
+impl RolloutVariant {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RolloutVariant::Current => "current",
+            RolloutVariant::Candidate => "candidate",
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -30,6 +99,9 @@ struct PolicyQuery {
     app_version: String,
     env: String,
     device_platform: String,
+    /// Rollout bucketing key; preferred over `session_id`.
+    device_id: Option<String>,
+    session_id: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -40,16 +112,55 @@ struct PolicyListQuery {
     device_platform: Option<String>,
 }
 
+/// Versions are named `policy_id@issued_at`, as in `rolled_back_from`.
+#[derive(Deserialize)]
+struct PolicyDiffQuery {
+    from: String,
+    to: String,
+    device_platform: String,
+}
+
+#[derive(Deserialize)]
+struct OverrideListQuery {
+    app_id: String,
+    env: String,
+}
+
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt::init();
 
     let db_path = std::env::var("POLICY_DB_PATH").unwrap_or_else(|_| "data/policy.db".to_string());
     let api_token = std::env::var("API_TOKEN").ok();
+    let admin_token = std::env::var("ADMIN_API_TOKEN").ok().or_else(|| api_token.clone());
+    let signer = load_signer().expect("failed to load policy signing key");
+    let lint_mode = match std::env::var("POLICY_LINT_MODE").as_deref() {
+        Ok("warn") => LintMode::Warn,
+        _ => LintMode::Reject,
+    };
+    let known_actions = std::env::var("POLICY_KNOWN_ACTIONS")
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|action| !action.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    info!(
+        "policy signing key {} public key {}",
+        signer.key_id(),
+        signer.public_key().to_base64()
+    );
     let conn = init_db(&db_path).expect("failed to init policy db");
     let state = AppState {
         db: Arc::new(Mutex::new(conn)),
         api_token,
+        admin_token,
+        signer,
+        lint_mode,
+        known_actions,
     };
     seed_default_policy(&state);
 
@@ -58,6 +169,20 @@ async fn main() {
         .route("/v1/policies/current", get(get_policy))
         .route("/v1/policies", get(list_policies).post(upsert_policy))
         .route("/v1/policies/versions", get(list_policy_versions))
+        .route("/v1/policies/rollback", post(rollback_policy))
+        .route("/v1/policies/diff", get(diff_policy_versions))
+        .route("/v1/policies/rollouts", get(list_policy_rollouts).post(start_policy_rollout))
+        .route("/v1/policies/rollouts/promote", post(promote_policy_rollout))
+        .route("/v1/policies/rollouts/rollback", post(rollback_policy_rollout))
+        .route(
+            "/v1/policies/org-default",
+            get(get_org_default_policy).put(put_org_default_policy),
+        )
+        .route(
+            "/v1/admin/overrides",
+            get(list_policy_overrides).post(create_policy_override),
+        )
+        .route("/v1/admin/overrides/:override_id", delete(delete_policy_override))
         .route("/v1/reports/upload", post(upload_report))
         .with_state(state);
 
@@ -72,22 +197,377 @@ async fn get_policy(
     State(state): State<AppState>,
     headers: HeaderMap,
     Query(query): Query<PolicyQuery>,
-) -> Result<Json<PolicyDto>, (StatusCode, String)> {
+) -> Result<([(&'static str, &'static str); 2], Json<PolicyDto>), (StatusCode, String)> {
     require_auth(&headers, &state.api_token)?;
     let mut conn = state.db.lock().unwrap();
 
-    if let Ok(Some(policy)) = fetch_policy(
+    let rollout_key = query.device_id.as_deref().or(query.session_id.as_deref());
+    let (mut policy, resolution, variant) = resolve_policy(
         &mut conn,
         &query.app_id,
         &query.app_version,
         &query.env,
         &query.device_platform,
-    ) {
-        return Ok(Json(policy));
+        rollout_key,
+    )
+    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+    .ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!(
+                "no policy for app_id={} app_version={} env={} device_platform={}",
+                query.app_id, query.app_version, query.env, query.device_platform
+            ),
+        )
+    })?;
+    info!(
+        "resolved policy {} for {} {} {} {} via {} ({})",
+        policy.policy_id,
+        query.app_id,
+        query.app_version,
+        query.env,
+        query.device_platform,
+        resolution.as_str(),
+        variant.as_str()
+    );
+
+    // Range, platform-agnostic and org default policies are served for the
+    // concrete version so `app_version` conditions evaluate against it.
+    policy.app_id = query.app_id;
+    policy.app_version = query.app_version;
+    policy.env = query.env;
+    apply_overrides(&mut conn, &mut policy, Utc::now())
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    // Re-issue on every serve so clients can enforce a max age on `issued_at`.
+    policy.issued_at = Utc::now().to_rfc3339();
+    sign_policy(&mut policy, &state.signer)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err))?;
+    Ok((
+        [
+            (POLICY_RESOLUTION_HEADER, resolution.as_str()),
+            (POLICY_ROLLOUT_HEADER, variant.as_str()),
+        ],
+        Json(policy),
+    ))
+}
+
+async fn get_org_default_policy(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<PolicyDto>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+    let mut conn = state.db.lock().unwrap();
+
+    fetch_org_default(&mut conn)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "no org default policy configured".to_string()))
+}
+
+async fn put_org_default_policy(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<PolicyDto>,
+) -> Result<Json<PolicyUpsertResponse>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+    validate_policy(&payload).map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
+    let warnings = lint_upsert(&payload, state.lint_mode, &state.known_actions)?;
+
+    let mut conn = state.db.lock().unwrap();
+    let stored_at = store_org_default(&mut conn, &payload)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    Ok(Json(PolicyUpsertResponse {
+        status: "ok".to_string(),
+        stored_at,
+        warnings: (!warnings.is_empty()).then_some(warnings),
+    }))
+}
+
+async fn diff_policy_versions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<PolicyDiffQuery>,
+) -> Result<Json<PolicyDiffResponse>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+
+    let mut conn = state.db.lock().unwrap();
+    let mut load = |version: &str| {
+        let (policy_id, issued_at) = version.split_once('@').ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("version {} must be policy_id@issued_at", version),
+            )
+        })?;
+        fetch_policy_version(&mut conn, policy_id, issued_at, &query.device_platform)
+            .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("no policy version {} for device_platform={}", version, query.device_platform),
+                )
+            })
+    };
+    let from = load(&query.from)?;
+    let to = load(&query.to)?;
+
+    Ok(Json(PolicyDiffResponse {
+        changes: diff_policy_rules(from.rules, to.rules),
+        from: query.from,
+        to: query.to,
+        device_platform: query.device_platform,
+    }))
+}
+
+fn diff_policy_rules(from: Vec<PolicyRuleDto>, to: Vec<PolicyRuleDto>) -> Vec<RuleChangeDto> {
+    let from = from.into_iter().map(PolicyRule::from).collect::<Vec<_>>();
+    let to = to.into_iter().map(PolicyRule::from).collect::<Vec<_>>();
+    diff_rules(&from, &to).into_iter().map(Into::into).collect()
+}
+
+/// Makes a historical version current again. The restored policy is re-issued
+/// and re-signed, and recorded as a new version naming the actor, the reason
+/// and the version it was restored from.
+async fn rollback_policy(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<PolicyRollbackDto>,
+) -> Result<Json<PolicyVersionRecord>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+    validate_policy_rollback(&payload).map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
+
+    let mut conn = state.db.lock().unwrap();
+    let mut policy = fetch_policy_version(
+        &mut conn,
+        &payload.policy_id,
+        &payload.issued_at,
+        &payload.device_platform,
+    )
+    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+    .ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!(
+                "no policy version policy_id={} issued_at={} device_platform={}",
+                payload.policy_id, payload.issued_at, payload.device_platform
+            ),
+        )
+    })?;
+
+    policy.issued_at = Utc::now().to_rfc3339();
+    sign_policy(&mut policy, &state.signer)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err))?;
+    let audit = VersionAudit {
+        actor: payload.actor,
+        reason: payload.reason,
+        rolled_back_from: Some(format!("{}@{}", payload.policy_id, payload.issued_at)),
+    };
+    let record = restore_policy(&mut conn, &policy, &payload.device_platform, audit)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    warn!(
+        "policy {} {} {} {} rolled back to {}@{} by {}",
+        policy.app_id,
+        policy.app_version,
+        policy.env,
+        payload.device_platform,
+        payload.policy_id,
+        payload.issued_at,
+        record.actor.as_deref().unwrap_or_default()
+    );
+
+    Ok(Json(record))
+}
+
+async fn start_policy_rollout(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<PolicyRolloutDto>,
+) -> Result<Json<PolicyRolloutRecord>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+    validate_policy_rollout(&payload).map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
+    lint_upsert(&payload.policy, state.lint_mode, &state.known_actions)?;
+
+    let mut policy = payload.policy;
+    sign_policy(&mut policy, &state.signer)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err))?;
+
+    let mut conn = state.db.lock().unwrap();
+    let current = fetch_policy(
+        &mut conn,
+        &policy.app_id,
+        &policy.app_version,
+        &policy.env,
+        &payload.device_platform,
+    )
+    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    if current.is_none() {
+        return Err((
+            StatusCode::CONFLICT,
+            "no current policy to roll out against; publish one with POST /v1/policies".to_string(),
+        ));
     }
 
-    let policy = default_policy(&query.app_id, &query.app_version, &query.env);
-    Ok(Json(policy))
+    let record = store_rollout(&mut conn, &policy, &payload.device_platform, payload.percentage)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    info!(
+        "policy {} rolling out to {}% of {} {} {} {}",
+        policy.policy_id,
+        record.percentage,
+        policy.app_id,
+        policy.app_version,
+        policy.env,
+        record.device_platform
+    );
+
+    Ok(Json(record))
+}
+
+async fn list_policy_rollouts(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<PolicyListQuery>,
+) -> Result<Json<Vec<PolicyRolloutRecord>>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+
+    let mut conn = state.db.lock().unwrap();
+    let records = fetch_rollouts(&mut conn)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let filtered = records
+        .into_iter()
+        .filter(|record| match &query.app_id {
+            Some(app_id) => &record.policy.app_id == app_id,
+            None => true,
+        })
+        .filter(|record| match &query.app_version {
+            Some(app_version) => &record.policy.app_version == app_version,
+            None => true,
+        })
+        .filter(|record| match &query.env {
+            Some(env) => &record.policy.env == env,
+            None => true,
+        })
+        .filter(|record| match &query.device_platform {
+            Some(platform) => &record.device_platform == platform,
+            None => true,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(filtered))
+}
+
+/// Makes the candidate the current policy for every device and ends the rollout.
+async fn promote_policy_rollout(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<PolicyRolloutTargetDto>,
+) -> Result<Json<PolicyUpsertResponse>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+    validate_policy_rollout_target(&payload)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
+
+    let mut conn = state.db.lock().unwrap();
+    let stored_at = promote_rollout(&mut conn, &payload)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "no rollout for this policy slot".to_string()))?;
+    info!(
+        "promoted rollout for {} {} {} {}",
+        payload.app_id, payload.app_version, payload.env, payload.device_platform
+    );
+
+    Ok(Json(PolicyUpsertResponse {
+        status: "promoted".to_string(),
+        stored_at,
+        warnings: None,
+    }))
+}
+
+/// Ends the rollout; every device is served the current policy again.
+async fn rollback_policy_rollout(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<PolicyRolloutTargetDto>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+    validate_policy_rollout_target(&payload)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
+
+    let mut conn = state.db.lock().unwrap();
+    let removed = delete_rollout(&mut conn, &payload)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    if !removed {
+        return Err((StatusCode::NOT_FOUND, "no rollout for this policy slot".to_string()));
+    }
+    warn!(
+        "rolled back rollout for {} {} {} {}",
+        payload.app_id, payload.app_version, payload.env, payload.device_platform
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn create_policy_override(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<PolicyOverrideRequestDto>,
+) -> Result<(StatusCode, Json<PolicyOverrideRecord>), (StatusCode, String)> {
+    require_auth(&headers, &state.admin_token)?;
+    validate_policy_override_request(&payload)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
+    let now = Utc::now();
+    if !override_is_active(&payload.expires_at, now) {
+        return Err((StatusCode::BAD_REQUEST, "expires_at must be in the future".to_string()));
+    }
+
+    let mut conn = state.db.lock().unwrap();
+    let record = store_override(&mut conn, payload, now)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    warn!(
+        "policy override {} set for {} {}: {:?} until {}",
+        record.override_id,
+        record.app_id,
+        record.env,
+        record.policy_override.decision,
+        record.policy_override.expires_at
+    );
+
+    Ok((StatusCode::CREATED, Json(record)))
+}
+
+async fn list_policy_overrides(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<OverrideListQuery>,
+) -> Result<Json<Vec<PolicyOverrideRecord>>, (StatusCode, String)> {
+    require_auth(&headers, &state.admin_token)?;
+
+    let mut conn = state.db.lock().unwrap();
+    fetch_active_overrides(&mut conn, &query.app_id, &query.env, Utc::now())
+        .map(Json)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))
+}
+
+async fn delete_policy_override(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    axum::extract::Path(override_id): axum::extract::Path<i64>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    require_auth(&headers, &state.admin_token)?;
+
+    let conn = state.db.lock().unwrap();
+    let deleted = conn
+        .execute(
+            "DELETE FROM policy_overrides WHERE override_id = ?1",
+            params![override_id],
+        )
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    if deleted == 0 {
+        return Err((StatusCode::NOT_FOUND, format!("no policy override {}", override_id)));
+    }
+    info!("policy override {} lifted", override_id);
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
 async fn root_status(State(state): State<AppState>) -> (StatusCode, Html<String>) {
@@ -111,14 +591,20 @@ async fn upsert_policy(
     require_auth(&headers, &state.api_token)?;
     validate_policy(&payload.policy)
         .map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
+    let warnings = lint_upsert(&payload.policy, state.lint_mode, &state.known_actions)?;
+
+    let mut policy = payload.policy;
+    sign_policy(&mut policy, &state.signer)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err))?;
 
     let mut conn = state.db.lock().unwrap();
-    let stored_at = store_policy(&mut conn, &payload.policy, &payload.device_platform)
+    let stored_at = store_policy(&mut conn, &policy, &payload.device_platform)
         .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
 
     Ok(Json(PolicyUpsertResponse {
         status: "ok".to_string(),
         stored_at,
+        warnings: (!warnings.is_empty()).then_some(warnings),
     }))
 }
 
@@ -194,10 +680,12 @@ async fn upload_report(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(payload): Json<ReportUploadDto>,
-) -> Result<Json<StatusAccepted>, (StatusCode, String)> {
+) -> Result<Json<ReportAccepted>, (StatusCode, String)> {
     require_auth(&headers, &state.api_token)?;
     validate_report_upload(&payload)
         .map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
+    let risk_score = compute_risk(payload.findings.as_deref().unwrap_or_default())
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
 
     let payload_json = serde_json::to_string(&payload)
         .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
@@ -210,13 +698,88 @@ async fn upload_report(
     )
     .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
 
-    Ok(Json(StatusAccepted {
+    Ok(Json(ReportAccepted {
         status: "accepted".to_string(),
+        risk_score: risk_score.value(),
     }))
 }
 
+/// Loads `POLICY_SIGNING_KEY`. Without it startup fails, unless
+/// `POLICY_ALLOW_EPHEMERAL_KEY=true` allows a per-process key for local development.
+fn load_signer() -> Result<Ed25519Signer, String> {
+    let key_id =
+        std::env::var("POLICY_SIGNING_KEY_ID").unwrap_or_else(|_| "policy-service".to_string());
+    let allow_ephemeral = std::env::var("POLICY_ALLOW_EPHEMERAL_KEY")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false);
+    match std::env::var("POLICY_SIGNING_KEY") {
+        Ok(seed) => Ed25519Signer::from_base64_seed(&key_id, &seed).map_err(|err| err.message),
+        Err(_) if allow_ephemeral => {
+            let signer = Ed25519Signer::generate(&key_id).map_err(|err| err.message)?;
+            warn!(
+                "POLICY_SIGNING_KEY not set; signing with an ephemeral key, public key {}",
+                signer.public_key().to_base64()
+            );
+            Ok(signer)
+        }
+        Err(_) => Err(
+            "POLICY_SIGNING_KEY is not set (set POLICY_ALLOW_EPHEMERAL_KEY=true for a development key)"
+                .to_string(),
+        ),
+    }
+}
+
+/// Replaces whatever signature the caller supplied with the server signature.
+fn sign_policy(policy: &mut PolicyDto, signer: &Ed25519Signer) -> Result<(), String> {
+    policy.signature = signer
+        .sign(&policy.signing_payload())
+        .map_err(|err| err.message)?;
+    Ok(())
+}
+
+/// Lints a policy before it is stored. Error-level findings reject it with 422
+/// unless the service runs with `POLICY_LINT_MODE=warn`; the remaining
+/// findings are logged and returned to the caller.
+fn lint_upsert(
+    policy: &PolicyDto,
+    mode: LintMode,
+    known_actions: &[String],
+) -> Result<Vec<PolicyLintDto>, (StatusCode, String)> {
+    let known_actions = known_actions.iter().map(String::as_str).collect::<Vec<_>>();
+    let lints = lint_policy(&PolicySet::from(policy.clone()), &known_actions);
+
+    let errors = lints
+        .iter()
+        .filter(|lint| lint.severity == LintSeverity::Error)
+        .map(|lint| match lint.rule_index {
+            Some(index) => format!("rule {} ({}): {}", index, lint.action, lint.message),
+            None => lint.message.clone(),
+        })
+        .collect::<Vec<_>>();
+    if mode == LintMode::Reject && !errors.is_empty() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("policy lint failed: {}", errors.join("; ")),
+        ));
+    }
+
+    for lint in &lints {
+        warn!(
+            "policy {} lint {} {}: {}",
+            policy.policy_id,
+            lint.severity.as_str(),
+            lint.kind.as_str(),
+            lint.message
+        );
+    }
+    Ok(lints.into_iter().map(PolicyLintDto::from).collect())
+}
+
 fn seed_default_policy(state: &AppState) {
-    let policy = default_policy("fintech.mobile", "1.0.0", "prod");
+    let mut policy = default_policy("fintech.mobile", "1.0.0", "prod");
+    if sign_policy(&mut policy, &state.signer).is_err() {
+        return;
+    }
     let mut conn = state.db.lock().unwrap();
     let _ = store_policy(&mut conn, &policy, "ios");
 }
@@ -232,48 +795,48 @@ fn default_policy(app_id: &str, app_version: &str, env: &str) -> PolicyDto {
                 action: "login".to_string(),
                 decision: DecisionDto::StepUp,
                 conditions: Some(PolicyConditionsDto {
-                    attestation: None,
                     debugger: Some(true),
-                    hooking: None,
-                    proxy_detected: None,
-                    app_version: None,
-                    risk_score_gte: None,
+                    ..PolicyConditionsDto::default()
+                }),
+            },
+            PolicyRuleDto {
+                action: "transfer".to_string(),
+                decision: DecisionDto::Deny,
+                conditions: Some(PolicyConditionsDto {
+                    root: Some(true),
+                    ..PolicyConditionsDto::default()
+                }),
+            },
+            PolicyRuleDto {
+                action: "transfer".to_string(),
+                decision: DecisionDto::Deny,
+                conditions: Some(PolicyConditionsDto {
+                    jailbreak: Some(true),
+                    ..PolicyConditionsDto::default()
                 }),
             },
             PolicyRuleDto {
                 action: "transfer".to_string(),
                 decision: DecisionDto::Deny,
                 conditions: Some(PolicyConditionsDto {
-                    attestation: None,
-                    debugger: None,
-                    hooking: None,
                     proxy_detected: Some(true),
-                    app_version: None,
-                    risk_score_gte: None,
+                    ..PolicyConditionsDto::default()
                 }),
             },
             PolicyRuleDto {
                 action: "transfer".to_string(),
                 decision: DecisionDto::StepUp,
                 conditions: Some(PolicyConditionsDto {
-                    attestation: None,
-                    debugger: None,
-                    hooking: None,
-                    proxy_detected: None,
-                    app_version: None,
                     risk_score_gte: Some(70),
+                    ..PolicyConditionsDto::default()
                 }),
             },
             PolicyRuleDto {
                 action: "view_card".to_string(),
                 decision: DecisionDto::Degrade,
                 conditions: Some(PolicyConditionsDto {
-                    attestation: None,
-                    debugger: None,
                     hooking: Some(true),
-                    proxy_detected: None,
-                    app_version: None,
-                    risk_score_gte: None,
+                    ..PolicyConditionsDto::default()
                 }),
             },
             PolicyRuleDto {
@@ -281,26 +844,22 @@ fn default_policy(app_id: &str, app_version: &str, env: &str) -> PolicyDto {
                 decision: DecisionDto::StepUp,
                 conditions: Some(PolicyConditionsDto {
                     attestation: Some(AttestationStatusDto::Fail),
-                    debugger: None,
-                    hooking: None,
-                    proxy_detected: None,
-                    app_version: None,
-                    risk_score_gte: None,
+                    ..PolicyConditionsDto::default()
                 }),
             },
             PolicyRuleDto {
                 action: "change_password".to_string(),
                 decision: DecisionDto::Deny,
                 conditions: Some(PolicyConditionsDto {
-                    attestation: None,
-                    debugger: None,
-                    hooking: None,
-                    proxy_detected: None,
                     app_version: Some("1.0.0".to_string()),
-                    risk_score_gte: None,
+                    ..PolicyConditionsDto::default()
                 }),
             },
         ],
+        default_decision: None,
+        action_defaults: None,
+        risk_weights: None,
+        overrides: None,
         signature: "stub".to_string(),
         issued_at: Utc::now().to_rfc3339(),
     }
@@ -349,9 +908,68 @@ fn init_db(path: &str) -> Result<Connection, rusqlite::Error> {
         [],
     )?;
 
+    migrate_policy_version_columns(&conn)?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS org_default_policy (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            payload TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS policy_rollouts (
+            app_id TEXT NOT NULL,
+            app_version TEXT NOT NULL,
+            env TEXT NOT NULL,
+            device_platform TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            percentage INTEGER NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (app_id, app_version, env, device_platform)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS policy_overrides (
+            override_id INTEGER PRIMARY KEY AUTOINCREMENT,
+            app_id TEXT NOT NULL,
+            env TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
     Ok(conn)
 }
 
+/// Audit columns added to `policy_versions` after its first release.
+const POLICY_VERSION_AUDIT_COLUMNS: [(&str, &str); 3] = [
+    ("actor", "TEXT"),
+    ("reason", "TEXT"),
+    ("rolled_back_from", "TEXT"),
+];
+
+/// Adds the audit columns to a `policy_versions` table created before them.
+/// Safe to run on every start.
+fn migrate_policy_version_columns(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let existing = {
+        let mut stmt = conn.prepare("PRAGMA table_info(policy_versions)")?;
+        stmt.query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    for (column, kind) in POLICY_VERSION_AUDIT_COLUMNS {
+        if !existing.iter().any(|name| name == column) {
+            conn.execute(&format!("ALTER TABLE policy_versions ADD COLUMN {} {}", column, kind), [])?;
+        }
+    }
+    Ok(())
+}
+
 fn check_db(state: &AppState) -> Result<(), String> {
     let conn = state
         .db
@@ -363,8 +981,9 @@ fn check_db(state: &AppState) -> Result<(), String> {
 }
 
 #[derive(serde::Serialize)]
-struct StatusAccepted {
+struct ReportAccepted {
     status: String,
+    risk_score: u32,
 }
 
 #[derive(serde::Serialize)]
@@ -378,12 +997,61 @@ struct PolicyVersionRecord {
     device_platform: String,
     policy: PolicyDto,
     stored_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    /// `policy_id@issued_at` of the version a rollback restored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rolled_back_from: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct PolicyDiffResponse {
+    from: String,
+    to: String,
+    device_platform: String,
+    changes: Vec<RuleChangeDto>,
+}
+
+/// Who changed the current policy and why, stored with the version.
+struct VersionAudit {
+    actor: String,
+    reason: String,
+    rolled_back_from: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct PolicyRolloutRecord {
+    device_platform: String,
+    percentage: u32,
+    policy: PolicyDto,
+    updated_at: String,
+}
+
+#[derive(serde::Serialize)]
+struct PolicyOverrideRecord {
+    override_id: i64,
+    app_id: String,
+    env: String,
+    #[serde(rename = "override")]
+    policy_override: PolicyOverrideDto,
+    created_at: String,
 }
 
 fn store_policy(
     conn: &mut Connection,
     policy: &PolicyDto,
     device_platform: &str,
+) -> Result<String, rusqlite::Error> {
+    store_current_policy(conn, policy, device_platform, None)
+}
+
+fn store_current_policy(
+    conn: &mut Connection,
+    policy: &PolicyDto,
+    device_platform: &str,
+    audit: Option<&VersionAudit>,
 ) -> Result<String, rusqlite::Error> {
     let payload = serde_json::to_string(policy).unwrap_or_else(|_| "{}".to_string());
     let updated_at = Utc::now().to_rfc3339();
@@ -401,11 +1069,31 @@ fn store_policy(
         ],
     )?;
 
-    store_policy_version(conn, policy, device_platform, &updated_at)?;
+    store_policy_version(conn, policy, device_platform, &updated_at, audit)?;
 
     Ok(updated_at)
 }
 
+/// Stores `policy` as current like `store_policy`, recording the audit trail
+/// on its version.
+fn restore_policy(
+    conn: &mut Connection,
+    policy: &PolicyDto,
+    device_platform: &str,
+    audit: VersionAudit,
+) -> Result<PolicyVersionRecord, rusqlite::Error> {
+    let stored_at = store_current_policy(conn, policy, device_platform, Some(&audit))?;
+
+    Ok(PolicyVersionRecord {
+        device_platform: device_platform.to_string(),
+        policy: policy.clone(),
+        stored_at,
+        actor: Some(audit.actor),
+        reason: Some(audit.reason),
+        rolled_back_from: audit.rolled_back_from,
+    })
+}
+
 fn fetch_policy(
     conn: &mut Connection,
     app_id: &str,
@@ -421,15 +1109,156 @@ fn fetch_policy(
     let mut rows = stmt.query(params![app_id, app_version, env, device_platform])?;
     if let Some(row) = rows.next()? {
         let payload: String = row.get(0)?;
-        let policy = serde_json::from_str::<PolicyDto>(&payload).unwrap_or_else(|_| {
-            default_policy(app_id, app_version, env)
-        });
-        return Ok(Some(policy));
+        return Ok(parse_stored_policy(&payload));
+    }
+
+    Ok(None)
+}
+
+/// Resolves the policy for a device: exact version, then a stored version
+/// range, then the platform-agnostic (`any`) policy, then the org default.
+/// A staged rollout on the resolved slot serves its candidate to the devices
+/// `rollout_key` buckets into it.
+fn resolve_policy(
+    conn: &mut Connection,
+    app_id: &str,
+    app_version: &str,
+    env: &str,
+    device_platform: &str,
+    rollout_key: Option<&str>,
+) -> Result<Option<(PolicyDto, PolicyResolution, RolloutVariant)>, rusqlite::Error> {
+    if let Some(policy) = fetch_policy(conn, app_id, app_version, env, device_platform)? {
+        let (policy, variant) = select_rollout_variant(conn, policy, device_platform, rollout_key)?;
+        return Ok(Some((policy, PolicyResolution::Exact, variant)));
+    }
+
+    if let Some(policy) = fetch_range_policy(conn, app_id, app_version, env, device_platform)? {
+        let (policy, variant) = select_rollout_variant(conn, policy, device_platform, rollout_key)?;
+        return Ok(Some((policy, PolicyResolution::VersionRange, variant)));
+    }
+
+    if device_platform != ANY_PLATFORM {
+        let any_platform = match fetch_policy(conn, app_id, app_version, env, ANY_PLATFORM)? {
+            Some(policy) => Some(policy),
+            None => fetch_range_policy(conn, app_id, app_version, env, ANY_PLATFORM)?,
+        };
+        if let Some(policy) = any_platform {
+            let (policy, variant) = select_rollout_variant(conn, policy, ANY_PLATFORM, rollout_key)?;
+            return Ok(Some((policy, PolicyResolution::PlatformAny, variant)));
+        }
+    }
+
+    Ok(fetch_org_default(conn)?
+        .map(|policy| (policy, PolicyResolution::OrgDefault, RolloutVariant::Current)))
+}
+
+/// Swaps `current` for the rollout candidate of its slot when `rollout_key`
+/// falls in the rollout percentage. Devices without a key stay on `current`.
+fn select_rollout_variant(
+    conn: &mut Connection,
+    current: PolicyDto,
+    device_platform: &str,
+    rollout_key: Option<&str>,
+) -> Result<(PolicyDto, RolloutVariant), rusqlite::Error> {
+    let Some(rollout_key) = rollout_key else {
+        return Ok((current, RolloutVariant::Current));
+    };
+    let rollout = fetch_rollout(
+        conn,
+        &current.app_id,
+        &current.app_version,
+        &current.env,
+        device_platform,
+    )?;
+
+    match rollout {
+        Some(rollout) if rollout_bucket(&rollout.policy.policy_id, rollout_key) < rollout.percentage => {
+            Ok((rollout.policy, RolloutVariant::Candidate))
+        }
+        _ => Ok((current, RolloutVariant::Current)),
+    }
+}
+
+/// Deterministic bucket in 0..100 for a device. Salted with the candidate's
+/// `policy_id` so each rollout picks its own cohort, while raising the
+/// percentage of the same candidate only adds devices.
+fn rollout_bucket(policy_id: &str, rollout_key: &str) -> u32 {
+    let digest = Sha256::new()
+        .chain_update(policy_id.as_bytes())
+        .chain_update(b":")
+        .chain_update(rollout_key.as_bytes())
+        .finalize();
+    u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) % 100
+}
+
+/// Most recently updated policy whose stored `app_version` is a range
+/// containing `app_version`.
+fn fetch_range_policy(
+    conn: &mut Connection,
+    app_id: &str,
+    app_version: &str,
+    env: &str,
+    device_platform: &str,
+) -> Result<Option<PolicyDto>, rusqlite::Error> {
+    let version = match Version::parse(app_version) {
+        Ok(version) => version,
+        Err(_) => return Ok(None),
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT app_version, payload FROM policies
+         WHERE app_id = ?1 AND env = ?2 AND device_platform = ?3
+         ORDER BY updated_at DESC",
+    )?;
+    let mut rows = stmt.query(params![app_id, env, device_platform])?;
+
+    while let Some(row) = rows.next()? {
+        let stored_version: String = row.get(0)?;
+        let matches = VersionRange::parse(&stored_version)
+            .map(|range| !range.is_exact() && range.matches(&version))
+            .unwrap_or(false);
+        if matches {
+            let payload: String = row.get(1)?;
+            return Ok(parse_stored_policy(&payload));
+        }
     }
 
     Ok(None)
 }
 
+fn fetch_org_default(conn: &mut Connection) -> Result<Option<PolicyDto>, rusqlite::Error> {
+    let mut stmt = conn.prepare("SELECT payload FROM org_default_policy WHERE id = 1")?;
+    let mut rows = stmt.query([])?;
+    if let Some(row) = rows.next()? {
+        let payload: String = row.get(0)?;
+        return Ok(parse_stored_policy(&payload));
+    }
+
+    Ok(None)
+}
+
+fn store_org_default(conn: &mut Connection, policy: &PolicyDto) -> Result<String, rusqlite::Error> {
+    let payload = serde_json::to_string(policy).unwrap_or_else(|_| "{}".to_string());
+    let updated_at = Utc::now().to_rfc3339();
+
+    conn.execute(
+        "INSERT OR REPLACE INTO org_default_policy (id, payload, updated_at) VALUES (1, ?1, ?2)",
+        params![payload, updated_at],
+    )?;
+
+    Ok(updated_at)
+}
+
+fn parse_stored_policy(payload: &str) -> Option<PolicyDto> {
+    match serde_json::from_str::<PolicyDto>(payload) {
+        Ok(policy) => Some(policy),
+        Err(err) => {
+            warn!("skipping unreadable stored policy: {}", err);
+            None
+        }
+    }
+}
+
 fn fetch_all_policies(conn: &mut Connection) -> Result<Vec<PolicyRecord>, rusqlite::Error> {
     let mut stmt = conn.prepare("SELECT device_platform, payload FROM policies")?;
     let mut rows = stmt.query([])?;
@@ -454,12 +1283,13 @@ fn store_policy_version(
     policy: &PolicyDto,
     device_platform: &str,
     stored_at: &str,
+    audit: Option<&VersionAudit>,
 ) -> Result<(), rusqlite::Error> {
     let payload = serde_json::to_string(policy).unwrap_or_else(|_| "{}".to_string());
 
     conn.execute(
-        "INSERT OR REPLACE INTO policy_versions (policy_id, issued_at, app_id, app_version, env, device_platform, payload, stored_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        "INSERT OR REPLACE INTO policy_versions (policy_id, issued_at, app_id, app_version, env, device_platform, payload, stored_at, actor, reason, rolled_back_from)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
         params![
             policy.policy_id,
             policy.issued_at,
@@ -468,16 +1298,40 @@ fn store_policy_version(
             policy.env,
             device_platform,
             payload,
-            stored_at
+            stored_at,
+            audit.map(|audit| &audit.actor),
+            audit.map(|audit| &audit.reason),
+            audit.and_then(|audit| audit.rolled_back_from.as_ref())
         ],
     )?;
 
     Ok(())
 }
 
+fn fetch_policy_version(
+    conn: &mut Connection,
+    policy_id: &str,
+    issued_at: &str,
+    device_platform: &str,
+) -> Result<Option<PolicyDto>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT payload FROM policy_versions
+         WHERE policy_id = ?1 AND issued_at = ?2 AND device_platform = ?3",
+    )?;
+
+    let mut rows = stmt.query(params![policy_id, issued_at, device_platform])?;
+    if let Some(row) = rows.next()? {
+        let payload: String = row.get(0)?;
+        return Ok(parse_stored_policy(&payload));
+    }
+
+    Ok(None)
+}
+
 fn fetch_policy_versions(conn: &mut Connection) -> Result<Vec<PolicyVersionRecord>, rusqlite::Error> {
     let mut stmt = conn.prepare(
-        "SELECT device_platform, payload, stored_at FROM policy_versions ORDER BY stored_at DESC",
+        "SELECT device_platform, payload, stored_at, actor, reason, rolled_back_from
+         FROM policy_versions ORDER BY stored_at DESC",
     )?;
     let mut rows = stmt.query([])?;
     let mut records = Vec::new();
@@ -491,6 +1345,96 @@ fn fetch_policy_versions(conn: &mut Connection) -> Result<Vec<PolicyVersionRecor
                 device_platform,
                 policy,
                 stored_at,
+                actor: row.get(3)?,
+                reason: row.get(4)?,
+                rolled_back_from: row.get(5)?,
+            });
+        }
+    }
+
+    Ok(records)
+}
+
+fn store_rollout(
+    conn: &mut Connection,
+    policy: &PolicyDto,
+    device_platform: &str,
+    percentage: u32,
+) -> Result<PolicyRolloutRecord, rusqlite::Error> {
+    let payload = serde_json::to_string(policy).unwrap_or_else(|_| "{}".to_string());
+    let updated_at = Utc::now().to_rfc3339();
+
+    conn.execute(
+        "INSERT OR REPLACE INTO policy_rollouts (app_id, app_version, env, device_platform, payload, percentage, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            policy.app_id,
+            policy.app_version,
+            policy.env,
+            device_platform,
+            payload,
+            percentage,
+            updated_at
+        ],
+    )?;
+
+    store_policy_version(conn, policy, device_platform, &updated_at, None)?;
+
+    Ok(PolicyRolloutRecord {
+        device_platform: device_platform.to_string(),
+        percentage,
+        policy: policy.clone(),
+        updated_at,
+    })
+}
+
+fn fetch_rollout(
+    conn: &mut Connection,
+    app_id: &str,
+    app_version: &str,
+    env: &str,
+    device_platform: &str,
+) -> Result<Option<PolicyRolloutRecord>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT payload, percentage, updated_at FROM policy_rollouts
+         WHERE app_id = ?1 AND app_version = ?2 AND env = ?3 AND device_platform = ?4",
+    )?;
+
+    let mut rows = stmt.query(params![app_id, app_version, env, device_platform])?;
+    if let Some(row) = rows.next()? {
+        let payload: String = row.get(0)?;
+        let percentage: u32 = row.get(1)?;
+        let updated_at: String = row.get(2)?;
+        return Ok(parse_stored_policy(&payload).map(|policy| PolicyRolloutRecord {
+            device_platform: device_platform.to_string(),
+            percentage,
+            policy,
+            updated_at,
+        }));
+    }
+
+    Ok(None)
+}
+
+fn fetch_rollouts(conn: &mut Connection) -> Result<Vec<PolicyRolloutRecord>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT device_platform, payload, percentage, updated_at FROM policy_rollouts
+         ORDER BY updated_at DESC",
+    )?;
+    let mut rows = stmt.query([])?;
+    let mut records = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        let device_platform: String = row.get(0)?;
+        let payload: String = row.get(1)?;
+        let percentage: u32 = row.get(2)?;
+        let updated_at: String = row.get(3)?;
+        if let Ok(policy) = serde_json::from_str::<PolicyDto>(&payload) {
+            records.push(PolicyRolloutRecord {
+                device_platform,
+                percentage,
+                policy,
+                updated_at,
             });
         }
     }
@@ -498,6 +1442,140 @@ fn fetch_policy_versions(conn: &mut Connection) -> Result<Vec<PolicyVersionRecor
     Ok(records)
 }
 
+/// Stores the candidate as the current policy of its slot and removes the
+/// rollout. Returns `None` when the slot has no rollout.
+fn promote_rollout(
+    conn: &mut Connection,
+    target: &PolicyRolloutTargetDto,
+) -> Result<Option<String>, rusqlite::Error> {
+    let rollout = fetch_rollout(
+        conn,
+        &target.app_id,
+        &target.app_version,
+        &target.env,
+        &target.device_platform,
+    )?;
+    let Some(rollout) = rollout else {
+        return Ok(None);
+    };
+
+    let stored_at = store_policy(conn, &rollout.policy, &target.device_platform)?;
+    delete_rollout(conn, target)?;
+    Ok(Some(stored_at))
+}
+
+fn delete_rollout(
+    conn: &mut Connection,
+    target: &PolicyRolloutTargetDto,
+) -> Result<bool, rusqlite::Error> {
+    let deleted = conn.execute(
+        "DELETE FROM policy_rollouts
+         WHERE app_id = ?1 AND app_version = ?2 AND env = ?3 AND device_platform = ?4",
+        params![
+            target.app_id,
+            target.app_version,
+            target.env,
+            target.device_platform
+        ],
+    )?;
+    Ok(deleted > 0)
+}
+
+fn store_override(
+    conn: &mut Connection,
+    request: PolicyOverrideRequestDto,
+    now: DateTime<Utc>,
+) -> Result<PolicyOverrideRecord, rusqlite::Error> {
+    let policy_override = PolicyOverrideDto {
+        actions: request.actions,
+        except_actions: request.except_actions,
+        decision: request.decision,
+        expires_at: request.expires_at,
+        reason: request.reason,
+    };
+    let payload = serde_json::to_string(&policy_override).unwrap_or_else(|_| "{}".to_string());
+    let created_at = now.to_rfc3339();
+
+    conn.execute(
+        "INSERT INTO policy_overrides (app_id, env, payload, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![request.app_id, request.env, payload, created_at],
+    )?;
+
+    Ok(PolicyOverrideRecord {
+        override_id: conn.last_insert_rowid(),
+        app_id: request.app_id,
+        env: request.env,
+        policy_override,
+        created_at,
+    })
+}
+
+/// Overrides for an app/env that have not expired, most recent first.
+fn fetch_active_overrides(
+    conn: &mut Connection,
+    app_id: &str,
+    env: &str,
+    now: DateTime<Utc>,
+) -> Result<Vec<PolicyOverrideRecord>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT override_id, payload, created_at FROM policy_overrides
+         WHERE app_id = ?1 AND env = ?2
+         ORDER BY override_id DESC",
+    )?;
+    let mut rows = stmt.query(params![app_id, env])?;
+    let mut records = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        let override_id: i64 = row.get(0)?;
+        let payload: String = row.get(1)?;
+        let created_at: String = row.get(2)?;
+        match serde_json::from_str::<PolicyOverrideDto>(&payload) {
+            Ok(policy_override) if override_is_active(&policy_override.expires_at, now) => {
+                records.push(PolicyOverrideRecord {
+                    override_id,
+                    app_id: app_id.to_string(),
+                    env: env.to_string(),
+                    policy_override,
+                    created_at,
+                });
+            }
+            Ok(_) => {}
+            Err(err) => warn!("skipping unreadable policy override {}: {}", override_id, err),
+        }
+    }
+
+    Ok(records)
+}
+
+/// Puts the active admin overrides for the policy's app/env ahead of any the
+/// stored policy carries, dropping expired ones so they are not re-signed.
+fn apply_overrides(
+    conn: &mut Connection,
+    policy: &mut PolicyDto,
+    now: DateTime<Utc>,
+) -> Result<(), rusqlite::Error> {
+    let mut overrides = fetch_active_overrides(conn, &policy.app_id, &policy.env, now)?
+        .into_iter()
+        .map(|record| record.policy_override)
+        .collect::<Vec<_>>();
+    overrides.extend(
+        policy
+            .overrides
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|policy_override| override_is_active(&policy_override.expires_at, now)),
+    );
+    policy.overrides = (!overrides.is_empty()).then_some(overrides);
+    Ok(())
+}
+
+fn override_is_active(expires_at: &str, now: DateTime<Utc>) -> bool {
+    DateTime::parse_from_rfc3339(expires_at)
+        .map(|expires_at| expires_at > now)
+        .unwrap_or(false)
+}
+
 fn require_auth(
     headers: &HeaderMap,
     token: &Option<String>,
@@ -540,4 +1618,351 @@ mod tests {
         assert_eq!(versions.len(), 1);
         assert_eq!(versions[0].device_platform, "ios");
     }
+
+    #[test]
+    fn resolve_policy_walks_tiers_in_order() {
+        let mut conn = init_db(":memory:").expect("db init");
+        let resolve = |conn: &mut Connection, version: &str, platform: &str| {
+            resolve_policy(conn, "app.test", version, "prod", platform, None)
+                .expect("resolve")
+                .map(|(policy, resolution, _)| (policy.policy_id, resolution))
+        };
+
+        assert_eq!(resolve(&mut conn, "1.0.0", "ios"), None);
+
+        let mut org_default = default_policy("org", "*", "prod");
+        org_default.policy_id = "pol_org".to_string();
+        store_org_default(&mut conn, &org_default).expect("store org default");
+        assert_eq!(
+            resolve(&mut conn, "1.0.0", "ios"),
+            Some(("pol_org".to_string(), PolicyResolution::OrgDefault))
+        );
+
+        let mut any_platform = default_policy("app.test", "^1", "prod");
+        any_platform.policy_id = "pol_any".to_string();
+        store_policy(&mut conn, &any_platform, ANY_PLATFORM).expect("store any");
+        assert_eq!(
+            resolve(&mut conn, "1.0.0", "ios"),
+            Some(("pol_any".to_string(), PolicyResolution::PlatformAny))
+        );
+
+        let mut range = default_policy("app.test", ">=1.0.0 <2.0.0", "prod");
+        range.policy_id = "pol_range".to_string();
+        store_policy(&mut conn, &range, "ios").expect("store range");
+        assert_eq!(
+            resolve(&mut conn, "1.4.2", "ios"),
+            Some(("pol_range".to_string(), PolicyResolution::VersionRange))
+        );
+        assert_eq!(
+            resolve(&mut conn, "2.0.0", "ios"),
+            Some(("pol_org".to_string(), PolicyResolution::OrgDefault))
+        );
+
+        let mut exact = default_policy("app.test", "1.4.2", "prod");
+        exact.policy_id = "pol_exact".to_string();
+        store_policy(&mut conn, &exact, "ios").expect("store exact");
+        assert_eq!(
+            resolve(&mut conn, "1.4.2", "ios"),
+            Some(("pol_exact".to_string(), PolicyResolution::Exact))
+        );
+    }
+
+    #[test]
+    fn rollback_restores_a_version_and_records_who_and_why() {
+        let mut conn = init_db(":memory:").expect("db init");
+        let mut first = default_policy("app.test", "1.0.0", "prod");
+        first.issued_at = "2026-02-06T21:00:00Z".to_string();
+        store_policy(&mut conn, &first, "ios").expect("store first");
+
+        let mut second = default_policy("app.test", "1.0.0", "prod");
+        second.policy_id = "pol_second".to_string();
+        second.rules.truncate(1);
+        store_policy(&mut conn, &second, "ios").expect("store second");
+
+        let restored = fetch_policy_version(&mut conn, "policy_default", "2026-02-06T21:00:00Z", "ios")
+            .expect("fetch version")
+            .expect("version exists");
+        assert!(
+            fetch_policy_version(&mut conn, "policy_default", "2026-02-06T21:00:00Z", "android")
+                .expect("fetch version")
+                .is_none()
+        );
+
+        let mut reissued = restored.clone();
+        reissued.issued_at = "2026-02-07T09:00:00Z".to_string();
+        let audit = VersionAudit {
+            actor: "oncall@example.com".to_string(),
+            reason: "pol_second locks out logins".to_string(),
+            rolled_back_from: Some("policy_default@2026-02-06T21:00:00Z".to_string()),
+        };
+        restore_policy(&mut conn, &reissued, "ios", audit).expect("restore");
+
+        let current = fetch_policy(&mut conn, "app.test", "1.0.0", "prod", "ios")
+            .expect("fetch policy")
+            .expect("policy exists");
+        assert_eq!(current.policy_id, "policy_default");
+        assert_eq!(current.rules, first.rules);
+
+        let versions = fetch_policy_versions(&mut conn).expect("fetch versions");
+        assert_eq!(versions.len(), 3);
+        let rollback = versions
+            .iter()
+            .find(|record| record.policy.issued_at == "2026-02-07T09:00:00Z")
+            .expect("rollback version");
+        assert_eq!(rollback.actor.as_deref(), Some("oncall@example.com"));
+        assert_eq!(
+            rollback.rolled_back_from.as_deref(),
+            Some("policy_default@2026-02-06T21:00:00Z")
+        );
+        assert_eq!(versions.iter().filter(|record| record.actor.is_none()).count(), 2);
+    }
+
+    #[test]
+    fn diff_reports_rule_changes_between_versions() {
+        use rust_core::adapters::serialization::RuleChangeKindDto;
+
+        let before = default_policy("app.test", "1.0.0", "prod");
+        let mut after = before.clone();
+        after.rules[0].decision = DecisionDto::Deny;
+        after.rules.swap(1, 2);
+        after.rules.pop();
+
+        let changes = diff_policy_rules(before.rules, after.rules)
+            .into_iter()
+            .map(|change| (change.change, change.action, change.from_index, change.to_index))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                (RuleChangeKindDto::DecisionChanged, "login".to_string(), Some(0), Some(0)),
+                (RuleChangeKindDto::Reordered, "transfer".to_string(), Some(2), Some(1)),
+                (RuleChangeKindDto::Reordered, "transfer".to_string(), Some(1), Some(2)),
+                (RuleChangeKindDto::Removed, "change_password".to_string(), Some(7), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn rollout_serves_candidate_to_a_stable_share_of_devices() {
+        let mut conn = init_db(":memory:").expect("db init");
+        let current = default_policy("app.test", "1.0.0", "prod");
+        store_policy(&mut conn, &current, "ios").expect("store current");
+
+        let mut candidate = default_policy("app.test", "1.0.0", "prod");
+        candidate.policy_id = "pol_candidate".to_string();
+        candidate.rules[0].decision = DecisionDto::Deny;
+        let rollout = PolicyRolloutDto {
+            device_platform: "ios".to_string(),
+            percentage: 20,
+            policy: candidate,
+        };
+        assert!(validate_policy_rollout(&rollout).is_ok());
+        store_rollout(&mut conn, &rollout.policy, "ios", rollout.percentage).expect("store rollout");
+
+        let devices = (0..1000).map(|index| format!("device-{index}")).collect::<Vec<_>>();
+        let serve = |conn: &mut Connection, device: Option<&str>| {
+            resolve_policy(conn, "app.test", "1.0.0", "prod", "ios", device)
+                .expect("resolve")
+                .map(|(policy, _, variant)| (policy.policy_id, variant))
+                .expect("policy exists")
+        };
+        let cohort = |conn: &mut Connection| {
+            devices
+                .iter()
+                .filter(|device| serve(conn, Some(device.as_str())).1 == RolloutVariant::Candidate)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let canaries = cohort(&mut conn);
+        assert!((150..250).contains(&canaries.len()), "{} canaries", canaries.len());
+        assert_eq!(cohort(&mut conn), canaries);
+        assert_eq!(
+            serve(&mut conn, Some(canaries[0].as_str())),
+            ("pol_candidate".to_string(), RolloutVariant::Candidate)
+        );
+        assert_eq!(
+            serve(&mut conn, None),
+            ("policy_default".to_string(), RolloutVariant::Current)
+        );
+
+        store_rollout(&mut conn, &rollout.policy, "ios", 50).expect("ramp rollout");
+        let ramped = cohort(&mut conn);
+        assert!(canaries.iter().all(|device| ramped.contains(device)));
+
+        let target = PolicyRolloutTargetDto {
+            app_id: "app.test".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "prod".to_string(),
+            device_platform: "ios".to_string(),
+        };
+        assert!(delete_rollout(&mut conn, &target).expect("rollback"));
+        assert!(cohort(&mut conn).is_empty());
+        assert_eq!(promote_rollout(&mut conn, &target).expect("promote"), None);
+
+        store_rollout(&mut conn, &rollout.policy, "ios", 5).expect("restart rollout");
+        assert!(promote_rollout(&mut conn, &target).expect("promote").is_some());
+        assert_eq!(serve(&mut conn, None).0, "pol_candidate");
+        assert!(fetch_rollouts(&mut conn).expect("rollouts").is_empty());
+    }
+
+    #[test]
+    fn upsert_accepts_condition_trees_and_rejects_empty_groups() {
+        let body = r#"{
+            "device_platform": "ios",
+            "policy": {
+                "policy_id": "pol_tree",
+                "app_id": "app.test",
+                "app_version": "1.0.0",
+                "env": "prod",
+                "rules": [{
+                    "action": "transfer",
+                    "decision": "DENY",
+                    "conditions": {
+                        "any_of": [{"debugger": true}, {"hooking": true}],
+                        "not": {"attestation": "pass"}
+                    }
+                }],
+                "signature": "stub",
+                "issued_at": "2026-02-06T21:00:00Z"
+            }
+        }"#;
+        let mut upsert: PolicyUpsertDto = serde_json::from_str(body).expect("parse upsert");
+        assert!(validate_policy(&upsert.policy).is_ok());
+
+        let conditions = upsert.policy.rules[0].conditions.as_mut().expect("conditions");
+        conditions.any_of = Some(Vec::new());
+        assert!(validate_policy(&upsert.policy).is_err());
+    }
+
+    #[test]
+    fn upsert_rejects_malformed_app_version_ranges() {
+        let mut policy = default_policy("app.test", "1.0.0", "prod");
+        let conditions = policy.rules[0].conditions.as_mut().expect("conditions");
+
+        conditions.app_version = Some(">=1.2.0 <2.0.0".to_string());
+        assert!(validate_policy(&policy).is_ok());
+
+        let conditions = policy.rules[0].conditions.as_mut().expect("conditions");
+        conditions.app_version = Some(">=1.x".to_string());
+        let err = validate_policy(&policy).unwrap_err();
+        assert!(err.message.contains("app_version"));
+    }
+
+    #[test]
+    fn upsert_lint_rejects_shadowed_rules_unless_in_warn_mode() {
+        use rust_core::adapters::serialization::{LintKindDto, LintSeverityDto};
+
+        let clean = default_policy("app.test", "1.0.0", "prod");
+        assert_eq!(lint_upsert(&clean, LintMode::Reject, &[]), Ok(Vec::new()));
+
+        let mut policy = clean.clone();
+        policy.rules.insert(
+            0,
+            PolicyRuleDto {
+                action: policy.rules[0].action.clone(),
+                decision: DecisionDto::Allow,
+                conditions: None,
+            },
+        );
+        policy.rules.push(PolicyRuleDto {
+            action: "open_account".to_string(),
+            decision: DecisionDto::StepUp,
+            conditions: None,
+        });
+
+        let (status, message) = lint_upsert(&policy, LintMode::Reject, &[]).unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(message.contains("rule 1"));
+
+        let warnings = lint_upsert(&policy, LintMode::Warn, &[]).expect("warn mode");
+        let kinds = warnings
+            .iter()
+            .map(|lint| (lint.kind, lint.severity))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (LintKindDto::ShadowedRule, LintSeverityDto::Error),
+                (LintKindDto::UnknownAction, LintSeverityDto::Warning),
+            ]
+        );
+
+        let known = vec!["open_account".to_string()];
+        assert_eq!(lint_upsert(&policy, LintMode::Warn, &known).expect("warn mode").len(), 1);
+    }
+
+    #[test]
+    fn active_overrides_are_served_ahead_of_stored_ones() {
+        use rust_core::domain::policy::{Decision, PolicyEngine, PolicySet};
+        use rust_core::domain::risk::RiskScore;
+        use rust_core::domain::telemetry::{ActionContext, IntegritySignals};
+
+        let mut conn = init_db(":memory:").expect("db init");
+        let now: DateTime<Utc> = "2026-02-06T21:00:00Z".parse().unwrap();
+        let request = |actions: Option<Vec<String>>, decision, expires_at: &str| {
+            PolicyOverrideRequestDto {
+                app_id: "app.test".to_string(),
+                env: "prod".to_string(),
+                actions,
+                except_actions: Some(vec!["view_card".to_string()]),
+                decision,
+                expires_at: expires_at.to_string(),
+                reason: Some("incident-42".to_string()),
+            }
+        };
+
+        let read_only = request(None, DecisionDto::Deny, "2026-02-06T23:00:00Z");
+        assert!(validate_policy_override_request(&read_only).is_ok());
+        store_override(&mut conn, read_only, now).expect("store override");
+        store_override(&mut conn, request(None, DecisionDto::StepUp, "2026-02-06T20:00:00Z"), now)
+            .expect("store expired override");
+        assert!(validate_policy_override_request(&request(None, DecisionDto::Allow, "2026-02-06T23:00:00Z")).is_err());
+
+        let active = fetch_active_overrides(&mut conn, "app.test", "prod", now).expect("fetch");
+        assert_eq!(active.len(), 1);
+        assert!(fetch_active_overrides(&mut conn, "app.test", "stage", now).expect("fetch").is_empty());
+
+        let mut policy = default_policy("app.test", "1.0.0", "prod");
+        apply_overrides(&mut conn, &mut policy, now).expect("apply overrides");
+        assert!(validate_policy(&policy).is_ok());
+
+        let policy = PolicySet::from(policy);
+        let signals = IntegritySignals {
+            jailbreak: false,
+            root: false,
+            debugger: false,
+            hooking: false,
+            proxy_detected: false,
+        };
+        let explain = |name: &str, now| {
+            let ctx = ActionContext {
+                name: name.to_string(),
+                context: None,
+            };
+            PolicyEngine::explain_at(&policy, &ctx, &signals, None, RiskScore::new(0), now)
+        };
+        assert_eq!(explain("transfer", now).decision, Decision::Deny);
+        assert_eq!(explain("transfer", now).override_index, Some(0));
+        assert_eq!(explain("view_card", now).override_index, None);
+
+        let later = "2026-02-07T00:00:00Z".parse().unwrap();
+        assert_eq!(explain("transfer", later).decision, Decision::Allow);
+    }
+
+    #[test]
+    fn sign_policy_produces_verifiable_signature() {
+        use rust_core::adapters::crypto::Ed25519Verifier;
+        use rust_core::ports::SignatureVerifier;
+
+        let signer = Ed25519Signer::generate("policy-service").expect("signer");
+        let mut policy = default_policy("app.test", "1.0.0", "prod");
+        sign_policy(&mut policy, &signer).expect("sign");
+
+        let verifier = Ed25519Verifier::new(vec![signer.public_key()]);
+        assert!(verifier.verify(&policy.signing_payload(), &policy.signature).is_ok());
+
+        policy.rules[1].decision = DecisionDto::Allow;
+        assert!(verifier.verify(&policy.signing_payload(), &policy.signature).is_err());
+    }
 }
@@ -0,0 +1,1457 @@
+//! Server-side verification of App Attest and Play Integrity evidence. Clients
+//! send the raw attestation objects, assertions and tokens; the verdict issued
+//! here replaces whatever attestation status the device claims.
+//!
+//! Evidence must be bound to a challenge issued here for one action of a
+//! session. A challenge is single-use: once for verification and once for the
+//! telemetry event reporting the action, both before it expires. The verdict is
+//! stored on the challenge, and the event must report exactly that verdict.
+
+use std::collections::HashMap;
+
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use ciborium::Value as Cbor;
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, Nonce, UnboundKey};
+use ring::digest::{SHA256, digest};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{ECDSA_P256_SHA256_ASN1, ECDSA_P256_SHA256_FIXED, UnparsedPublicKey};
+use rusqlite::{Connection, OptionalExtension, params};
+use rust_core::adapters::serialization::{
+    AttestationChallengeDto, AttestationChallengeRequestDto, AttestationProviderDto,
+    AttestationResultDto, AttestationStatusDto, AttestationVerdictDto, AttestationVerifyRequestDto,
+    TelemetryEventDto,
+};
+use rust_core::{AttestationProvider, AttestationStatus};
+use serde::Deserialize;
+use x509_parser::prelude::*;
+
+/// Extension of the App Attest credential certificate holding the nonce.
+const APP_ATTEST_NONCE_OID: &str = "1.2.840.113635.100.8.2";
+const APP_ATTEST_AAGUID_PRODUCTION: &[u8] = b"appattest\0\0\0\0\0\0\0";
+const APP_ATTEST_AAGUID_DEVELOPMENT: &[u8] = b"appattestdevelop";
+const DEFAULT_PLAY_INTEGRITY_MAX_AGE_SECS: i64 = 5 * 60;
+const DEFAULT_CHALLENGE_TTL_SECS: i64 = 5 * 60;
+const CHALLENGE_BYTES: usize = 32;
+
+/// Roots, keys and app identities evidence is checked against. Loaded from the
+/// JSON file named by `ATTESTATION_CONFIG`; without it every request is
+/// rejected as not configured.
+pub struct AttestationConfig {
+    /// DER certificates App Attest chains must end at (Apple's App Attest root
+    /// in production).
+    app_attest_roots: Vec<Vec<u8>>,
+    /// Accept keys from the App Attest development environment.
+    allow_development: bool,
+    /// AES-256 key that unwraps Play Integrity tokens.
+    play_decryption_key: Option<[u8; 32]>,
+    /// Uncompressed P-256 point that signs Play Integrity verdicts.
+    play_verification_key: Option<Vec<u8>>,
+    play_max_token_age: Duration,
+    /// How long an issued challenge can be used.
+    challenge_ttl: Duration,
+    apps: HashMap<String, AttestedApp>,
+}
+
+/// Platform identities of an `app_id`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AttestedApp {
+    /// `TEAMID.bundle.id`, whose hash App Attest puts in `rpIdHash`.
+    pub apple_app_id: Option<String>,
+    pub android_package: Option<String>,
+    /// Signing certificate digests as Play Integrity reports them (base64url
+    /// SHA-256). Empty accepts any certificate Play recognizes.
+    #[serde(default)]
+    pub android_cert_sha256: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AttestationConfigFile {
+    /// PEM files with the App Attest root certificates.
+    #[serde(default)]
+    app_attest_roots: Vec<String>,
+    #[serde(default)]
+    app_attest_allow_development: bool,
+    /// Base64 AES-256 key from the Play Console.
+    play_integrity_decryption_key: Option<String>,
+    /// Base64 DER public key from the Play Console.
+    play_integrity_verification_key: Option<String>,
+    play_integrity_max_token_age_secs: Option<i64>,
+    challenge_ttl_secs: Option<i64>,
+    #[serde(default)]
+    apps: HashMap<String, AttestedApp>,
+}
+
+impl Default for AttestationConfig {
+    fn default() -> Self {
+        Self {
+            app_attest_roots: Vec::new(),
+            allow_development: false,
+            play_decryption_key: None,
+            play_verification_key: None,
+            play_max_token_age: Duration::seconds(DEFAULT_PLAY_INTEGRITY_MAX_AGE_SECS),
+            challenge_ttl: Duration::seconds(DEFAULT_CHALLENGE_TTL_SECS),
+            apps: HashMap::new(),
+        }
+    }
+}
+
+impl AttestationConfig {
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("ATTESTATION_CONFIG") {
+            Ok(path) => Self::load(&path),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    fn load(path: &str) -> Result<Self, String> {
+        let raw = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+        let file: AttestationConfigFile =
+            serde_json::from_str(&raw).map_err(|err| format!("{}: {}", path, err))?;
+
+        let mut app_attest_roots = Vec::with_capacity(file.app_attest_roots.len());
+        for root_path in &file.app_attest_roots {
+            let pem = std::fs::read(root_path).map_err(|err| format!("{}: {}", root_path, err))?;
+            let (_, pem) = x509_parser::pem::parse_x509_pem(&pem)
+                .map_err(|err| format!("{}: {}", root_path, err))?;
+            X509Certificate::from_der(&pem.contents)
+                .map_err(|err| format!("{}: {}", root_path, err))?;
+            app_attest_roots.push(pem.contents);
+        }
+
+        let play_decryption_key = match &file.play_integrity_decryption_key {
+            Some(key) => Some(
+                STANDARD
+                    .decode(key)
+                    .ok()
+                    .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                    .ok_or("play_integrity_decryption_key must be a base64 AES-256 key")?,
+            ),
+            None => None,
+        };
+        let play_verification_key = match &file.play_integrity_verification_key {
+            Some(key) => Some(
+                STANDARD
+                    .decode(key)
+                    .ok()
+                    .and_then(|der| {
+                        SubjectPublicKeyInfo::from_der(&der)
+                            .ok()
+                            .map(|(_, spki)| spki.subject_public_key.data.to_vec())
+                    })
+                    .ok_or("play_integrity_verification_key must be a base64 DER public key")?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            app_attest_roots,
+            allow_development: file.app_attest_allow_development,
+            play_decryption_key,
+            play_verification_key,
+            play_max_token_age: Duration::seconds(
+                file.play_integrity_max_token_age_secs
+                    .unwrap_or(DEFAULT_PLAY_INTEGRITY_MAX_AGE_SECS),
+            ),
+            challenge_ttl: Duration::seconds(
+                file.challenge_ttl_secs
+                    .filter(|secs| *secs > 0)
+                    .unwrap_or(DEFAULT_CHALLENGE_TTL_SECS),
+            ),
+            apps: file.apps,
+        })
+    }
+}
+
+/// Why evidence could not be turned into a passing verdict.
+#[derive(Debug)]
+pub enum AttestationError {
+    /// The request cannot be checked: unknown app, missing configuration,
+    /// undecodable fields.
+    Invalid(String),
+    /// The evidence was checked and cannot be trusted.
+    Rejected(String),
+    /// The challenge already got a verdict.
+    Duplicate(String),
+    /// The challenge expired before evidence bound to it arrived.
+    Stale(String),
+    Storage(rusqlite::Error),
+}
+
+impl From<rusqlite::Error> for AttestationError {
+    fn from(err: rusqlite::Error) -> Self {
+        Self::Storage(err)
+    }
+}
+
+pub fn init_attestation_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_attest_keys (
+            app_id TEXT NOT NULL,
+            key_id TEXT NOT NULL,
+            public_key TEXT NOT NULL,
+            counter INTEGER NOT NULL,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (app_id, key_id)
+        )",
+        [],
+    )?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS attestation_challenges (
+            challenge TEXT PRIMARY KEY,
+            app_id TEXT NOT NULL,
+            session_id TEXT NOT NULL,
+            action TEXT NOT NULL,
+            issued_at TEXT NOT NULL,
+            expires_at TEXT NOT NULL,
+            verified_at TEXT,
+            verdict_provider TEXT,
+            verdict_status TEXT,
+            event_id TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_attestation_challenges_expires_at
+            ON attestation_challenges (expires_at);",
+    )?;
+    Ok(())
+}
+
+/// Issues a random challenge for one action of a session.
+pub fn issue_challenge(
+    conn: &Connection,
+    config: &AttestationConfig,
+    request: &AttestationChallengeRequestDto,
+    now: DateTime<Utc>,
+) -> Result<AttestationChallengeDto, AttestationError> {
+    let mut bytes = [0u8; CHALLENGE_BYTES];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| AttestationError::Invalid("could not generate a challenge".to_string()))?;
+    let challenge = URL_SAFE_NO_PAD.encode(bytes);
+    let expires_at = (now + config.challenge_ttl).to_rfc3339_opts(SecondsFormat::Millis, true);
+
+    conn.execute(
+        "INSERT INTO attestation_challenges (challenge, app_id, session_id, action, issued_at, expires_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            challenge,
+            request.app_id,
+            request.session_id,
+            request.action,
+            now.to_rfc3339_opts(SecondsFormat::Millis, true),
+            expires_at
+        ],
+    )?;
+
+    Ok(AttestationChallengeDto {
+        challenge,
+        expires_at,
+    })
+}
+
+/// Checks the challenge an event's attestation carries: issued for the event's
+/// app, session and action, verified here with the provider and status the
+/// event reports, not expired at `now` and not used by another event. The event
+/// claims it with [`claim_event_challenge`].
+pub fn check_event_challenge(
+    conn: &Connection,
+    event: &TelemetryEventDto,
+    attestation: &AttestationResultDto,
+    challenge: &str,
+    now: DateTime<Utc>,
+) -> Result<(), AttestationError> {
+    let issued = load_challenge(conn, challenge)?
+        .ok_or_else(|| rejected("challenge was not issued by this server"))?;
+    let session_id = event
+        .session
+        .as_ref()
+        .map(|session| session.session_id.as_str());
+    if issued.app_id != event.app_id
+        || Some(issued.session_id.as_str()) != session_id
+        || issued.action != event.action.name
+    {
+        return Err(rejected(
+            "challenge was issued for another app, session or action",
+        ));
+    }
+    let Some((provider, status)) = &issued.verdict else {
+        return Err(rejected("challenge has no verdict from this server"));
+    };
+    if provider != provider_name(&attestation.provider)
+        || status != status_name(&attestation.result)
+    {
+        return Err(rejected(&format!(
+            "attestation does not match the {} {} verdict issued for the challenge",
+            provider, status
+        )));
+    }
+    if let Some(event_id) = issued.event_id
+        && event_id != event.event_id
+    {
+        return Err(rejected(&format!(
+            "challenge was already used by event_id {}",
+            event_id
+        )));
+    }
+
+    let occurred_at = DateTime::parse_from_rfc3339(&event.timestamp)
+        .map_err(|_| {
+            AttestationError::Invalid("timestamp must be an RFC 3339 timestamp".to_string())
+        })?
+        .with_timezone(&Utc);
+    if occurred_at < issued.issued_at {
+        return Err(rejected("event predates its challenge"));
+    }
+    if now > issued.expires_at {
+        return Err(rejected("challenge has expired"));
+    }
+    Ok(())
+}
+
+pub fn claim_event_challenge(
+    conn: &Connection,
+    challenge: &str,
+    event_id: &str,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE attestation_challenges SET event_id = ?1 WHERE challenge = ?2",
+        params![event_id, challenge],
+    )?;
+    Ok(())
+}
+
+/// Forgets challenges that expired before `expired_before`; events bound to
+/// them are stale by then anyway.
+pub fn prune_challenges(conn: &Connection, expired_before: &str) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM attestation_challenges WHERE expires_at < ?1",
+        params![expired_before],
+    )?;
+    Ok(())
+}
+
+/// Stores the verdict issued for evidence bound to `challenge`, which events
+/// reporting it must match.
+pub fn record_verdict(
+    conn: &Connection,
+    challenge: &str,
+    provider: &AttestationProviderDto,
+    status: &AttestationStatusDto,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE attestation_challenges SET verdict_provider = ?1, verdict_status = ?2
+         WHERE challenge = ?3",
+        params![provider_name(provider), status_name(status), challenge],
+    )?;
+    Ok(())
+}
+
+fn provider_name(provider: &AttestationProviderDto) -> &'static str {
+    AttestationProvider::from(provider.clone()).as_str()
+}
+
+fn status_name(status: &AttestationStatusDto) -> &'static str {
+    AttestationStatus::from(status.clone()).as_str()
+}
+
+/// Marks a challenge as used by a verification of `app_id` evidence.
+fn claim_verification_challenge(
+    conn: &Connection,
+    app_id: &str,
+    challenge: &str,
+    now: DateTime<Utc>,
+) -> Result<(), AttestationError> {
+    let issued = load_challenge(conn, challenge)?
+        .ok_or_else(|| rejected("challenge was not issued by this server"))?;
+    if issued.app_id != app_id {
+        return Err(rejected("challenge was issued for another app"));
+    }
+    if issued.verified {
+        return Err(AttestationError::Duplicate("challenge was already used".to_string()));
+    }
+    if now > issued.expires_at {
+        return Err(AttestationError::Stale("challenge has expired".to_string()));
+    }
+
+    conn.execute(
+        "UPDATE attestation_challenges SET verified_at = ?1 WHERE challenge = ?2",
+        params![now.to_rfc3339_opts(SecondsFormat::Millis, true), challenge],
+    )?;
+    Ok(())
+}
+
+struct IssuedChallenge {
+    app_id: String,
+    session_id: String,
+    action: String,
+    issued_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    verified: bool,
+    /// `(provider, status)` of the verdict issued for the challenge.
+    verdict: Option<(String, String)>,
+    event_id: Option<String>,
+}
+
+fn load_challenge(
+    conn: &Connection,
+    challenge: &str,
+) -> Result<Option<IssuedChallenge>, AttestationError> {
+    let row = conn
+        .query_row(
+            "SELECT app_id, session_id, action, issued_at, expires_at, verified_at,
+                verdict_provider, verdict_status, event_id
+             FROM attestation_challenges WHERE challenge = ?1",
+            params![challenge],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                    row.get::<_, Option<String>>(6)?,
+                    row.get::<_, Option<String>>(7)?,
+                    row.get::<_, Option<String>>(8)?,
+                ))
+            },
+        )
+        .optional()?;
+    let Some((
+        app_id,
+        session_id,
+        action,
+        issued_at,
+        expires_at,
+        verified_at,
+        verdict_provider,
+        verdict_status,
+        event_id,
+    )) = row
+    else {
+        return Ok(None);
+    };
+
+    let parse = |value: &str| {
+        DateTime::parse_from_rfc3339(value)
+            .map(|value| value.with_timezone(&Utc))
+            .map_err(|_| rejected("stored challenge is corrupt"))
+    };
+    Ok(Some(IssuedChallenge {
+        app_id,
+        session_id,
+        action,
+        issued_at: parse(&issued_at)?,
+        expires_at: parse(&expires_at)?,
+        verified: verified_at.is_some(),
+        verdict: verdict_provider.zip(verdict_status),
+        event_id,
+    }))
+}
+
+/// Verifies the evidence in `request` (already validated) and issues the
+/// result, stored on the challenge once it was claimed. Evidence that does not
+/// hold up yields a `fail` verdict with its reason; requests that cannot be
+/// checked at all, and challenges already used or expired, are errors. The
+/// claim is rolled back when the evidence cannot be checked, so the challenge
+/// can still be used.
+pub fn issue_verdict(
+    conn: &Connection,
+    config: &AttestationConfig,
+    request: &AttestationVerifyRequestDto,
+    now: DateTime<Utc>,
+) -> Result<AttestationVerdictDto, AttestationError> {
+    let app = config.apps.get(&request.app_id).ok_or_else(|| {
+        AttestationError::Invalid(format!(
+            "{} is not configured for attestation",
+            request.app_id
+        ))
+    })?;
+    let tx = conn.unchecked_transaction()?;
+    let (result, reason) =
+        match claim_verification_challenge(&tx, &request.app_id, &request.challenge, now) {
+            Ok(()) => {
+                let (result, reason) = match verify_evidence(&tx, config, app, request, now) {
+                    Ok(()) => (AttestationStatusDto::Pass, None),
+                    Err(AttestationError::Rejected(reason)) => {
+                        (AttestationStatusDto::Fail, Some(reason))
+                    }
+                    // Dropping `tx` releases the claim.
+                    Err(err) => return Err(err),
+                };
+                record_verdict(&tx, &request.challenge, &request.provider, &result)?;
+                (result, reason)
+            }
+            // Not issued here or for another app: nothing to record a verdict on.
+            Err(AttestationError::Rejected(reason)) => (AttestationStatusDto::Fail, Some(reason)),
+            Err(err) => return Err(err),
+        };
+    tx.commit()?;
+
+    Ok(AttestationVerdictDto {
+        attestation: AttestationResultDto {
+            provider: request.provider.clone(),
+            result,
+            timestamp: Some(now.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            challenge: Some(request.challenge.clone()),
+        },
+        reason,
+    })
+}
+
+fn verify_evidence(
+    conn: &Connection,
+    config: &AttestationConfig,
+    app: &AttestedApp,
+    request: &AttestationVerifyRequestDto,
+    now: DateTime<Utc>,
+) -> Result<(), AttestationError> {
+    match request.provider {
+        AttestationProviderDto::AppAttest => {
+            let apple_app_id = app.apple_app_id.as_deref().ok_or_else(|| {
+                AttestationError::Invalid(format!("{} has no apple_app_id", request.app_id))
+            })?;
+            let key_id = request.key_id.as_deref().unwrap_or_default();
+            match (&request.attestation, &request.assertion) {
+                (Some(attestation), None) => verify_app_attest_attestation(
+                    conn,
+                    config,
+                    &request.app_id,
+                    apple_app_id,
+                    key_id,
+                    attestation,
+                    &request.challenge,
+                    now,
+                ),
+                (None, Some(assertion)) => verify_app_attest_assertion(
+                    conn,
+                    &request.app_id,
+                    apple_app_id,
+                    key_id,
+                    assertion,
+                    &request.challenge,
+                ),
+                _ => Err(AttestationError::Invalid(
+                    "app_attest requires exactly one of attestation or assertion".to_string(),
+                )),
+            }
+        }
+        AttestationProviderDto::PlayIntegrity => verify_play_integrity(
+            config,
+            app,
+            request.token.as_deref().unwrap_or_default(),
+            &request.challenge,
+            now,
+        ),
+        AttestationProviderDto::None => Err(AttestationError::Invalid(
+            "provider must be app_attest or play_integrity".to_string(),
+        )),
+    }
+}
+
+/// Checks a one-time App Attest attestation and registers its key. Follows
+/// Apple's "Validating apps that connect to your server" steps.
+#[allow(clippy::too_many_arguments)]
+fn verify_app_attest_attestation(
+    conn: &Connection,
+    config: &AttestationConfig,
+    app_id: &str,
+    apple_app_id: &str,
+    key_id: &str,
+    attestation: &str,
+    challenge: &str,
+    now: DateTime<Utc>,
+) -> Result<(), AttestationError> {
+    let key_id_bytes = decode_base64("key_id", key_id)?;
+    let object = decode_cbor("attestation", attestation)?;
+    if cbor_text(&object, "fmt") != Some("apple-appattest") {
+        return Err(rejected("attestation format is not apple-appattest"));
+    }
+    let statement =
+        cbor_field(&object, "attStmt").ok_or_else(|| rejected("attestation has no attStmt"))?;
+    let chain = match cbor_field(statement, "x5c") {
+        Some(Cbor::Array(certs)) => certs
+            .iter()
+            .map(|cert| cert.as_bytes().map(Vec::as_slice))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| rejected("attestation x5c is malformed"))?,
+        _ => return Err(rejected("attestation has no x5c")),
+    };
+    let auth_data =
+        cbor_bytes(&object, "authData").ok_or_else(|| rejected("attestation has no authData"))?;
+
+    let credential = verify_chain(&chain, &config.app_attest_roots, now)?;
+
+    let client_data_hash = sha256(challenge.as_bytes());
+    let expected_nonce = sha256(&[auth_data, client_data_hash.as_slice()].concat());
+    let nonce = credential
+        .extensions()
+        .iter()
+        .find(|extension| extension.oid.to_id_string() == APP_ATTEST_NONCE_OID)
+        .and_then(|extension| nonce_from_extension(extension.value))
+        .ok_or_else(|| rejected("credential certificate has no nonce"))?;
+    if nonce != expected_nonce.as_slice() {
+        return Err(rejected("nonce does not match the challenge"));
+    }
+
+    let public_key = credential.public_key().subject_public_key.data.to_vec();
+    if sha256(&public_key) != key_id_bytes {
+        return Err(rejected("key_id does not match the credential public key"));
+    }
+
+    let auth = AuthenticatorData::parse(auth_data)?;
+    if auth.rp_id_hash != sha256(apple_app_id.as_bytes()).as_slice() {
+        return Err(rejected("rpIdHash does not match the app id"));
+    }
+    if auth.counter != 0 {
+        return Err(rejected("attestation counter must be 0"));
+    }
+    match auth.aaguid {
+        Some(APP_ATTEST_AAGUID_PRODUCTION) => {}
+        Some(APP_ATTEST_AAGUID_DEVELOPMENT) if config.allow_development => {}
+        _ => return Err(rejected("aaguid is not an accepted App Attest environment")),
+    }
+    if auth.credential_id != Some(key_id_bytes.as_slice()) {
+        return Err(rejected("credential id does not match key_id"));
+    }
+
+    let inserted = conn.execute(
+        "INSERT OR IGNORE INTO app_attest_keys (app_id, key_id, public_key, counter, created_at)
+         VALUES (?1, ?2, ?3, 0, ?4)",
+        params![
+            app_id,
+            key_id,
+            STANDARD.encode(&public_key),
+            now.to_rfc3339_opts(SecondsFormat::Secs, true)
+        ],
+    )?;
+    if inserted == 0 {
+        return Err(rejected("key_id is already attested"));
+    }
+    Ok(())
+}
+
+/// Checks an assertion from an attested key and advances its counter.
+fn verify_app_attest_assertion(
+    conn: &Connection,
+    app_id: &str,
+    apple_app_id: &str,
+    key_id: &str,
+    assertion: &str,
+    challenge: &str,
+) -> Result<(), AttestationError> {
+    let (public_key, counter) = conn
+        .query_row(
+            "SELECT public_key, counter FROM app_attest_keys WHERE app_id = ?1 AND key_id = ?2",
+            params![app_id, key_id],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+        )
+        .optional()?
+        .ok_or_else(|| rejected("key_id has not been attested"))?;
+    let public_key = STANDARD
+        .decode(public_key)
+        .map_err(|_| rejected("stored public key is corrupt"))?;
+
+    let object = decode_cbor("assertion", assertion)?;
+    let signature =
+        cbor_bytes(&object, "signature").ok_or_else(|| rejected("assertion has no signature"))?;
+    let auth_data = cbor_bytes(&object, "authenticatorData")
+        .ok_or_else(|| rejected("assertion has no authenticatorData"))?;
+
+    let client_data_hash = sha256(challenge.as_bytes());
+    let nonce = sha256(&[auth_data, client_data_hash.as_slice()].concat());
+    UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, &public_key)
+        .verify(&nonce, signature)
+        .map_err(|_| rejected("assertion signature does not verify"))?;
+
+    let auth = AuthenticatorData::parse(auth_data)?;
+    if auth.rp_id_hash != sha256(apple_app_id.as_bytes()).as_slice() {
+        return Err(rejected("rpIdHash does not match the app id"));
+    }
+    if i64::from(auth.counter) <= counter {
+        return Err(rejected("assertion counter did not increase"));
+    }
+
+    conn.execute(
+        "UPDATE app_attest_keys SET counter = ?1 WHERE app_id = ?2 AND key_id = ?3",
+        params![i64::from(auth.counter), app_id, key_id],
+    )?;
+    Ok(())
+}
+
+/// Decrypts and verifies a Play Integrity token locally, then checks that the
+/// verdict is for this app, this challenge and a recognized app and device.
+fn verify_play_integrity(
+    config: &AttestationConfig,
+    app: &AttestedApp,
+    token: &str,
+    challenge: &str,
+    now: DateTime<Utc>,
+) -> Result<(), AttestationError> {
+    let package = app
+        .android_package
+        .as_deref()
+        .ok_or_else(|| AttestationError::Invalid("app has no android_package".to_string()))?;
+    let (Some(decryption_key), Some(verification_key)) =
+        (&config.play_decryption_key, &config.play_verification_key)
+    else {
+        return Err(AttestationError::Invalid(
+            "Play Integrity keys are not configured".to_string(),
+        ));
+    };
+
+    let jws = decrypt_jwe(token, decryption_key)?;
+    let payload = verify_jws(&jws, verification_key)?;
+    let verdict: PlayIntegrityPayload = serde_json::from_slice(&payload)
+        .map_err(|err| rejected(&format!("verdict is malformed: {}", err)))?;
+
+    let details = &verdict.request_details;
+    if details.request_package_name != package {
+        return Err(rejected("verdict is for another package"));
+    }
+    if details.nonce != challenge {
+        return Err(rejected("nonce does not match the challenge"));
+    }
+    let issued_at = details
+        .timestamp_millis
+        .parse::<i64>()
+        .ok()
+        .and_then(DateTime::<Utc>::from_timestamp_millis)
+        .ok_or_else(|| rejected("verdict timestamp is malformed"))?;
+    if now - issued_at > config.play_max_token_age {
+        return Err(rejected("verdict is too old"));
+    }
+
+    let integrity = &verdict.app_integrity;
+    if integrity.app_recognition_verdict != "PLAY_RECOGNIZED" {
+        return Err(rejected(&format!(
+            "app is not recognized by Play: {}",
+            integrity.app_recognition_verdict
+        )));
+    }
+    if integrity.package_name.as_deref() != Some(package) {
+        return Err(rejected("app integrity is for another package"));
+    }
+    if !app.android_cert_sha256.is_empty()
+        && !integrity
+            .certificate_sha256_digest
+            .iter()
+            .any(|digest| app.android_cert_sha256.contains(digest))
+    {
+        return Err(rejected("app is signed with an unexpected certificate"));
+    }
+    if !verdict
+        .device_integrity
+        .device_recognition_verdict
+        .iter()
+        .any(|label| label == "MEETS_DEVICE_INTEGRITY")
+    {
+        return Err(rejected("device does not meet device integrity"));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlayIntegrityPayload {
+    request_details: PlayRequestDetails,
+    app_integrity: PlayAppIntegrity,
+    device_integrity: PlayDeviceIntegrity,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlayRequestDetails {
+    request_package_name: String,
+    nonce: String,
+    timestamp_millis: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlayAppIntegrity {
+    app_recognition_verdict: String,
+    package_name: Option<String>,
+    #[serde(default)]
+    certificate_sha256_digest: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlayDeviceIntegrity {
+    #[serde(default)]
+    device_recognition_verdict: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct JoseHeader {
+    alg: String,
+    enc: Option<String>,
+}
+
+/// Compact JWE with `A256KW` key wrapping and `A256GCM` content encryption.
+fn decrypt_jwe(token: &str, key: &[u8; 32]) -> Result<Vec<u8>, AttestationError> {
+    let parts = token.split('.').collect::<Vec<_>>();
+    let [header_b64, wrapped_key, iv, ciphertext, tag] = parts.as_slice() else {
+        return Err(rejected("token is not a compact JWE"));
+    };
+    let header: JoseHeader = serde_json::from_slice(&decode_base64url(header_b64)?)
+        .map_err(|_| rejected("token header is malformed"))?;
+    if header.alg != "A256KW" || header.enc.as_deref() != Some("A256GCM") {
+        return Err(rejected("token must use A256KW and A256GCM"));
+    }
+
+    let content_key = aes_kw::KekAes256::from(*key)
+        .unwrap_vec(&decode_base64url(wrapped_key)?)
+        .map_err(|_| rejected("token key does not unwrap"))?;
+    let content_key = UnboundKey::new(&AES_256_GCM, &content_key)
+        .map_err(|_| rejected("token key has the wrong size"))?;
+    let nonce = Nonce::try_assume_unique_for_key(&decode_base64url(iv)?)
+        .map_err(|_| rejected("token iv has the wrong size"))?;
+
+    let mut sealed = decode_base64url(ciphertext)?;
+    sealed.extend(decode_base64url(tag)?);
+    let plaintext = LessSafeKey::new(content_key)
+        .open_in_place(nonce, Aad::from(header_b64.as_bytes()), &mut sealed)
+        .map_err(|_| rejected("token does not decrypt"))?;
+    Ok(plaintext.to_vec())
+}
+
+/// Compact JWS signed with `ES256`; returns the payload.
+fn verify_jws(jws: &[u8], public_key: &[u8]) -> Result<Vec<u8>, AttestationError> {
+    let jws = std::str::from_utf8(jws).map_err(|_| rejected("token payload is not a JWS"))?;
+    let parts = jws.split('.').collect::<Vec<_>>();
+    let [header_b64, payload_b64, signature] = parts.as_slice() else {
+        return Err(rejected("token payload is not a compact JWS"));
+    };
+    let header: JoseHeader = serde_json::from_slice(&decode_base64url(header_b64)?)
+        .map_err(|_| rejected("verdict header is malformed"))?;
+    if header.alg != "ES256" {
+        return Err(rejected("verdict must be signed with ES256"));
+    }
+
+    let signed = format!("{}.{}", header_b64, payload_b64);
+    UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, public_key)
+        .verify(signed.as_bytes(), &decode_base64url(signature)?)
+        .map_err(|_| rejected("verdict signature does not verify"))?;
+    decode_base64url(payload_b64)
+}
+
+/// Verifies `chain` (leaf first) up to one of `roots` and returns the leaf.
+fn verify_chain<'a>(
+    chain: &[&'a [u8]],
+    roots: &[Vec<u8>],
+    now: DateTime<Utc>,
+) -> Result<X509Certificate<'a>, AttestationError> {
+    if roots.is_empty() {
+        return Err(AttestationError::Invalid(
+            "App Attest roots are not configured".to_string(),
+        ));
+    }
+    let at = ASN1Time::from_timestamp(now.timestamp())
+        .map_err(|_| AttestationError::Invalid("clock is out of range".to_string()))?;
+
+    let certs = chain
+        .iter()
+        .map(|der| X509Certificate::from_der(der).map(|(_, cert)| cert))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| rejected("x5c holds an invalid certificate"))?;
+    let Some((leaf, issuers)) = certs.split_first() else {
+        return Err(rejected("x5c is empty"));
+    };
+
+    let mut subject = leaf;
+    for issuer in issuers {
+        if !issuer.is_ca() {
+            return Err(rejected("x5c issuer is not a CA"));
+        }
+        subject
+            .verify_signature(Some(issuer.public_key()))
+            .map_err(|_| rejected("x5c signature does not verify"))?;
+        subject = issuer;
+    }
+    let anchored = roots.iter().any(|root| {
+        X509Certificate::from_der(root).is_ok_and(|(_, root)| {
+            root.validity().is_valid_at(at)
+                && subject.verify_signature(Some(root.public_key())).is_ok()
+        })
+    });
+    if !anchored {
+        return Err(rejected("x5c does not chain to a configured root"));
+    }
+    if !certs.iter().all(|cert| cert.validity().is_valid_at(at)) {
+        return Err(rejected(
+            "x5c holds an expired or not yet valid certificate",
+        ));
+    }
+
+    Ok(certs.into_iter().next().expect("chain has a leaf"))
+}
+
+/// `SEQUENCE { [1] EXPLICIT OCTET STRING nonce }`, short-form lengths only.
+fn nonce_from_extension(value: &[u8]) -> Option<&[u8]> {
+    match value {
+        [0x30, _, 0xa1, _, 0x04, len, nonce @ ..] if nonce.len() == usize::from(*len) => {
+            Some(nonce)
+        }
+        _ => None,
+    }
+}
+
+/// WebAuthn authenticator data as App Attest lays it out.
+struct AuthenticatorData<'a> {
+    rp_id_hash: &'a [u8],
+    counter: u32,
+    aaguid: Option<&'a [u8]>,
+    credential_id: Option<&'a [u8]>,
+}
+
+impl<'a> AuthenticatorData<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, AttestationError> {
+        if data.len() < 37 {
+            return Err(rejected("authenticator data is truncated"));
+        }
+        let counter = u32::from_be_bytes([data[33], data[34], data[35], data[36]]);
+        let (aaguid, credential_id) = if data.len() >= 55 {
+            let id_len = usize::from(u16::from_be_bytes([data[53], data[54]]));
+            let id = data
+                .get(55..55 + id_len)
+                .ok_or_else(|| rejected("authenticator data is truncated"))?;
+            (Some(&data[37..53]), Some(id))
+        } else {
+            (None, None)
+        };
+        Ok(Self {
+            rp_id_hash: &data[..32],
+            counter,
+            aaguid,
+            credential_id,
+        })
+    }
+}
+
+fn rejected(reason: &str) -> AttestationError {
+    AttestationError::Rejected(reason.to_string())
+}
+
+fn sha256(data: &[u8]) -> Vec<u8> {
+    digest(&SHA256, data).as_ref().to_vec()
+}
+
+fn decode_base64(field: &str, value: &str) -> Result<Vec<u8>, AttestationError> {
+    STANDARD
+        .decode(value)
+        .map_err(|_| AttestationError::Invalid(format!("{} must be base64", field)))
+}
+
+fn decode_base64url(value: &str) -> Result<Vec<u8>, AttestationError> {
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| rejected("token holds invalid base64url"))
+}
+
+fn decode_cbor(field: &str, value: &str) -> Result<Cbor, AttestationError> {
+    let bytes = decode_base64(field, value)?;
+    ciborium::de::from_reader(bytes.as_slice())
+        .map_err(|_| AttestationError::Invalid(format!("{} must be CBOR", field)))
+}
+
+fn cbor_field<'a>(value: &'a Cbor, key: &str) -> Option<&'a Cbor> {
+    value
+        .as_map()?
+        .iter()
+        .find(|(name, _)| name.as_text() == Some(key))
+        .map(|(_, value)| value)
+}
+
+fn cbor_text<'a>(value: &'a Cbor, key: &str) -> Option<&'a str> {
+    cbor_field(value, key)?.as_text()
+}
+
+fn cbor_bytes<'a>(value: &'a Cbor, key: &str) -> Option<&'a [u8]> {
+    cbor_field(value, key)?.as_bytes().map(Vec::as_slice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{
+        BasicConstraints, CertificateParams, CustomExtension, DnType, IsCa, KeyPair,
+        PKCS_ECDSA_P256_SHA256, PKCS_ECDSA_P384_SHA384,
+    };
+    use ring::signature::{
+        ECDSA_P256_SHA256_ASN1_SIGNING, ECDSA_P256_SHA256_FIXED_SIGNING, EcdsaKeyPair, KeyPair as _,
+    };
+
+    const APPLE_APP_ID: &str = "TEAM123456.com.fintech.mobile";
+    const PACKAGE: &str = "com.fintech.mobile";
+    /// DER prefix of a P-256 `SubjectPublicKeyInfo` holding an uncompressed point.
+    const P256_SPKI_PREFIX: [u8; 26] = [
+        0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08,
+        0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+    ];
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-02-06T21:05:00Z")
+            .expect("now")
+            .with_timezone(&Utc)
+    }
+
+    fn db() -> Connection {
+        let conn = Connection::open_in_memory().expect("db");
+        init_attestation_tables(&conn).expect("tables");
+        conn
+    }
+
+    fn app() -> AttestedApp {
+        AttestedApp {
+            apple_app_id: Some(APPLE_APP_ID.to_string()),
+            android_package: Some(PACKAGE.to_string()),
+            android_cert_sha256: vec!["cert-digest".to_string()],
+        }
+    }
+
+    fn ca(
+        name: &str,
+        issuer: Option<(&rcgen::Certificate, &KeyPair)>,
+    ) -> (rcgen::Certificate, KeyPair) {
+        let key = KeyPair::generate_for(&PKCS_ECDSA_P384_SHA384).expect("ca key");
+        let mut params = CertificateParams::new(Vec::<String>::new()).expect("params");
+        params.distinguished_name.push(DnType::CommonName, name);
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let cert = match issuer {
+            Some((issuer, issuer_key)) => params.signed_by(&key, issuer, issuer_key),
+            None => params.self_signed(&key),
+        }
+        .expect("ca cert");
+        (cert, key)
+    }
+
+    /// A locally generated App Attest setup: root, intermediate and a device key.
+    struct AppAttestFixture {
+        root: rcgen::Certificate,
+        intermediate: rcgen::Certificate,
+        intermediate_key: KeyPair,
+        device: EcdsaKeyPair,
+        device_key: KeyPair,
+        key_id: Vec<u8>,
+    }
+
+    impl AppAttestFixture {
+        fn new() -> Self {
+            let (root, root_key) = ca("Test App Attest Root", None);
+            let (intermediate, intermediate_key) =
+                ca("Test App Attest CA", Some((&root, &root_key)));
+            let device_key = KeyPair::generate_for(&PKCS_ECDSA_P256_SHA256).expect("device key");
+            let device = EcdsaKeyPair::from_pkcs8(
+                &ECDSA_P256_SHA256_ASN1_SIGNING,
+                &device_key.serialize_der(),
+                &SystemRandom::new(),
+            )
+            .expect("device signer");
+            let key_id = sha256(device.public_key().as_ref());
+            Self {
+                root,
+                intermediate,
+                intermediate_key,
+                device,
+                device_key,
+                key_id,
+            }
+        }
+
+        fn config(&self) -> AttestationConfig {
+            AttestationConfig {
+                app_attest_roots: vec![self.root.der().to_vec()],
+                apps: HashMap::from([("fintech.mobile".to_string(), app())]),
+                ..AttestationConfig::default()
+            }
+        }
+
+        fn auth_data(&self, app_id: &str, counter: u32, credential: bool) -> Vec<u8> {
+            let mut data = sha256(app_id.as_bytes());
+            data.push(0x40);
+            data.extend(counter.to_be_bytes());
+            if credential {
+                data.extend(APP_ATTEST_AAGUID_PRODUCTION);
+                data.extend((self.key_id.len() as u16).to_be_bytes());
+                data.extend(&self.key_id);
+            }
+            data
+        }
+
+        fn attestation(&self, challenge: &str) -> String {
+            let auth_data = self.auth_data(APPLE_APP_ID, 0, true);
+            let nonce = sha256(&[auth_data.as_slice(), &sha256(challenge.as_bytes())].concat());
+            let mut extension = vec![0x30, 0x24, 0xa1, 0x22, 0x04, 0x20];
+            extension.extend(&nonce);
+
+            let mut params = CertificateParams::new(Vec::<String>::new()).expect("params");
+            params.distinguished_name.push(DnType::CommonName, "device");
+            params.custom_extensions = vec![CustomExtension::from_oid_content(
+                &[1, 2, 840, 113635, 100, 8, 2],
+                extension,
+            )];
+            let credential = params
+                .signed_by(&self.device_key, &self.intermediate, &self.intermediate_key)
+                .expect("credential cert");
+
+            let object = Cbor::Map(vec![
+                (
+                    Cbor::Text("fmt".into()),
+                    Cbor::Text("apple-appattest".into()),
+                ),
+                (
+                    Cbor::Text("attStmt".into()),
+                    Cbor::Map(vec![(
+                        Cbor::Text("x5c".into()),
+                        Cbor::Array(vec![
+                            Cbor::Bytes(credential.der().to_vec()),
+                            Cbor::Bytes(self.intermediate.der().to_vec()),
+                        ]),
+                    )]),
+                ),
+                (Cbor::Text("authData".into()), Cbor::Bytes(auth_data)),
+            ]);
+            encode_cbor(&object)
+        }
+
+        fn assertion(&self, challenge: &str, counter: u32) -> String {
+            let auth_data = self.auth_data(APPLE_APP_ID, counter, false);
+            let nonce = sha256(&[auth_data.as_slice(), &sha256(challenge.as_bytes())].concat());
+            let signature = self
+                .device
+                .sign(&SystemRandom::new(), &nonce)
+                .expect("sign");
+            let object = Cbor::Map(vec![
+                (
+                    Cbor::Text("signature".into()),
+                    Cbor::Bytes(signature.as_ref().to_vec()),
+                ),
+                (
+                    Cbor::Text("authenticatorData".into()),
+                    Cbor::Bytes(auth_data),
+                ),
+            ]);
+            encode_cbor(&object)
+        }
+
+        fn request(&self, challenge: &str) -> AttestationVerifyRequestDto {
+            AttestationVerifyRequestDto {
+                app_id: "fintech.mobile".to_string(),
+                provider: AttestationProviderDto::AppAttest,
+                challenge: challenge.to_string(),
+                key_id: Some(STANDARD.encode(&self.key_id)),
+                attestation: None,
+                assertion: None,
+                token: None,
+            }
+        }
+    }
+
+    fn encode_cbor(value: &Cbor) -> String {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(value, &mut bytes).expect("cbor");
+        STANDARD.encode(bytes)
+    }
+
+    fn verdict(
+        conn: &Connection,
+        config: &AttestationConfig,
+        request: &AttestationVerifyRequestDto,
+    ) -> (AttestationStatusDto, Option<String>) {
+        let verdict = issue_verdict(conn, config, request, now()).expect("verdict");
+        (verdict.attestation.result, verdict.reason)
+    }
+
+    fn challenge(conn: &Connection, config: &AttestationConfig) -> String {
+        let request = AttestationChallengeRequestDto {
+            app_id: "fintech.mobile".to_string(),
+            session_id: "sess_1".to_string(),
+            action: "login".to_string(),
+        };
+        issue_challenge(conn, config, &request, now())
+            .expect("challenge")
+            .challenge
+    }
+
+    #[test]
+    fn app_attest_key_is_attested_once_then_asserts_with_rising_counter() {
+        let conn = db();
+        let fixture = AppAttestFixture::new();
+        let config = fixture.config();
+
+        let issued = challenge(&conn, &config);
+        let mut attest = fixture.request(&issued);
+        attest.attestation = Some(fixture.attestation(&issued));
+        let mut wrong_challenge = attest.clone();
+        wrong_challenge.challenge = challenge(&conn, &config);
+        assert_eq!(
+            verdict(&conn, &config, &wrong_challenge).1.as_deref(),
+            Some("nonce does not match the challenge")
+        );
+        let stored_verdict = |challenge: &str| {
+            load_challenge(&conn, challenge)
+                .expect("load")
+                .and_then(|issued| issued.verdict)
+        };
+        assert_eq!(
+            stored_verdict(&wrong_challenge.challenge),
+            Some(("app_attest".to_string(), "fail".to_string()))
+        );
+        assert_eq!(stored_verdict(&issued), None);
+        let verdict_for_key = issue_verdict(&conn, &config, &attest, now()).expect("verdict");
+        assert_eq!(
+            verdict_for_key.attestation.result,
+            AttestationStatusDto::Pass
+        );
+        assert_eq!(verdict_for_key.attestation.challenge, Some(issued.clone()));
+        assert!(matches!(
+            issue_verdict(&conn, &config, &attest, now()),
+            Err(AttestationError::Duplicate(_))
+        ));
+        // Reusing the challenge does not overwrite the verdict it got.
+        assert_eq!(
+            stored_verdict(&issued),
+            Some(("app_attest".to_string(), "pass".to_string()))
+        );
+
+        let issued = challenge(&conn, &config);
+        let mut reattest = fixture.request(&issued);
+        reattest.attestation = Some(fixture.attestation(&issued));
+        assert_eq!(
+            verdict(&conn, &config, &reattest).1.as_deref(),
+            Some("key_id is already attested")
+        );
+
+        let issued = challenge(&conn, &config);
+        let mut assert = fixture.request(&issued);
+        assert.assertion = Some(fixture.assertion(&issued, 1));
+        assert_eq!(
+            verdict(&conn, &config, &assert),
+            (AttestationStatusDto::Pass, None)
+        );
+
+        let issued = challenge(&conn, &config);
+        let mut replayed_counter = fixture.request(&issued);
+        replayed_counter.assertion = Some(fixture.assertion(&issued, 1));
+        assert_eq!(
+            verdict(&conn, &config, &replayed_counter).1.as_deref(),
+            Some("assertion counter did not increase")
+        );
+
+        let signed_for = challenge(&conn, &config);
+        let mut forged = fixture.request(&challenge(&conn, &config));
+        forged.assertion = Some(fixture.assertion(&signed_for, 2));
+        assert_eq!(
+            verdict(&conn, &config, &forged).1.as_deref(),
+            Some("assertion signature does not verify")
+        );
+    }
+
+    #[test]
+    fn evidence_must_be_bound_to_a_live_issued_challenge() {
+        let conn = db();
+        let fixture = AppAttestFixture::new();
+        let config = fixture.config();
+
+        let mut unissued = fixture.request("client-chosen");
+        unissued.attestation = Some(fixture.attestation("client-chosen"));
+        assert_eq!(
+            verdict(&conn, &config, &unissued).1.as_deref(),
+            Some("challenge was not issued by this server")
+        );
+
+        let issued = challenge(&conn, &config);
+        let mut late = fixture.request(&issued);
+        late.attestation = Some(fixture.attestation(&issued));
+        assert!(matches!(
+            issue_verdict(&conn, &config, &late, now() + Duration::minutes(6)),
+            Err(AttestationError::Stale(_))
+        ));
+    }
+
+    #[test]
+    fn evidence_that_cannot_be_checked_leaves_the_challenge_usable() {
+        let conn = db();
+        let fixture = AppAttestFixture::new();
+        let config = fixture.config();
+
+        let issued = challenge(&conn, &config);
+        let empty = fixture.request(&issued);
+        assert!(matches!(
+            issue_verdict(&conn, &config, &empty, now()),
+            Err(AttestationError::Invalid(_))
+        ));
+        let issued_challenge = load_challenge(&conn, &issued).expect("load").expect("issued");
+        assert!(!issued_challenge.verified);
+        assert_eq!(issued_challenge.verdict, None);
+
+        let mut attest = fixture.request(&issued);
+        attest.attestation = Some(fixture.attestation(&issued));
+        assert_eq!(
+            verdict(&conn, &config, &attest),
+            (AttestationStatusDto::Pass, None)
+        );
+    }
+
+    #[test]
+    fn app_attest_chain_must_end_at_a_configured_root() {
+        let conn = db();
+        let fixture = AppAttestFixture::new();
+        let other = AppAttestFixture::new();
+        let config = other.config();
+
+        let issued = challenge(&conn, &config);
+        let mut attest = fixture.request(&issued);
+        attest.attestation = Some(fixture.attestation(&issued));
+        let mut unknown_app = attest.clone();
+        unknown_app.app_id = "other.app".to_string();
+        assert!(matches!(
+            issue_verdict(&conn, &config, &unknown_app, now()),
+            Err(AttestationError::Invalid(_))
+        ));
+
+        assert_eq!(
+            verdict(&conn, &config, &attest).1.as_deref(),
+            Some("x5c does not chain to a configured root")
+        );
+    }
+
+    /// Play Integrity token as Google issues it: an ES256 JWS inside an
+    /// A256KW/A256GCM JWE.
+    fn play_token(
+        payload: &serde_json::Value,
+        signer: &EcdsaKeyPair,
+        decryption_key: &[u8; 32],
+    ) -> String {
+        let rng = SystemRandom::new();
+        let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"ES256"}"#);
+        let body = URL_SAFE_NO_PAD.encode(payload.to_string());
+        let signature = signer
+            .sign(&rng, format!("{}.{}", header, body).as_bytes())
+            .expect("sign");
+        let jws = format!(
+            "{}.{}.{}",
+            header,
+            body,
+            URL_SAFE_NO_PAD.encode(signature.as_ref())
+        );
+
+        let mut content_key = [0u8; 32];
+        let mut iv = [0u8; 12];
+        rng.fill(&mut content_key).expect("cek");
+        rng.fill(&mut iv).expect("iv");
+        let wrapped = aes_kw::KekAes256::from(*decryption_key)
+            .wrap_vec(&content_key)
+            .expect("wrap");
+        let jwe_header = URL_SAFE_NO_PAD.encode(br#"{"alg":"A256KW","enc":"A256GCM"}"#);
+        let mut sealed = jws.into_bytes();
+        let tag = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &content_key).expect("key"))
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(iv),
+                Aad::from(jwe_header.as_bytes()),
+                &mut sealed,
+            )
+            .expect("seal");
+        [
+            jwe_header,
+            URL_SAFE_NO_PAD.encode(wrapped),
+            URL_SAFE_NO_PAD.encode(iv),
+            URL_SAFE_NO_PAD.encode(sealed),
+            URL_SAFE_NO_PAD.encode(tag.as_ref()),
+        ]
+        .join(".")
+    }
+
+    fn play_payload(nonce: &str, device_verdict: &[&str]) -> serde_json::Value {
+        serde_json::json!({
+            "requestDetails": {
+                "requestPackageName": PACKAGE,
+                "nonce": nonce,
+                "timestampMillis": (now() - Duration::seconds(30)).timestamp_millis().to_string(),
+            },
+            "appIntegrity": {
+                "appRecognitionVerdict": "PLAY_RECOGNIZED",
+                "packageName": PACKAGE,
+                "certificateSha256Digest": ["cert-digest"],
+                "versionCode": "42",
+            },
+            "deviceIntegrity": { "deviceRecognitionVerdict": device_verdict },
+        })
+    }
+
+    #[test]
+    fn play_integrity_tokens_are_decrypted_verified_and_bound_to_the_challenge() {
+        let conn = db();
+        let rng = SystemRandom::new();
+        let pkcs8 =
+            EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).expect("pkcs8");
+        let signer =
+            EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng)
+                .expect("signer");
+        let decryption_key = [7u8; 32];
+        let spki = [P256_SPKI_PREFIX.as_slice(), signer.public_key().as_ref()].concat();
+        let (_, spki) = SubjectPublicKeyInfo::from_der(&spki).expect("spki");
+        let config = AttestationConfig {
+            play_decryption_key: Some(decryption_key),
+            play_verification_key: Some(spki.subject_public_key.data.to_vec()),
+            apps: HashMap::from([("fintech.mobile".to_string(), app())]),
+            ..AttestationConfig::default()
+        };
+        let request = |token: String, challenge: &str| AttestationVerifyRequestDto {
+            app_id: "fintech.mobile".to_string(),
+            provider: AttestationProviderDto::PlayIntegrity,
+            challenge: challenge.to_string(),
+            key_id: None,
+            attestation: None,
+            assertion: None,
+            token: Some(token),
+        };
+
+        let issued = challenge(&conn, &config);
+        let token = play_token(
+            &play_payload(&issued, &["MEETS_DEVICE_INTEGRITY"]),
+            &signer,
+            &decryption_key,
+        );
+        assert_eq!(
+            verdict(&conn, &config, &request(token.clone(), &issued)),
+            (AttestationStatusDto::Pass, None)
+        );
+        assert!(matches!(
+            issue_verdict(&conn, &config, &request(token.clone(), &issued), now()),
+            Err(AttestationError::Duplicate(_))
+        ));
+        assert_eq!(
+            verdict(&conn, &config, &request(token, &challenge(&conn, &config)))
+                .1
+                .as_deref(),
+            Some("nonce does not match the challenge")
+        );
+
+        let issued = challenge(&conn, &config);
+        let emulator = play_token(&play_payload(&issued, &[]), &signer, &decryption_key);
+        assert_eq!(
+            verdict(&conn, &config, &request(emulator, &issued))
+                .1
+                .as_deref(),
+            Some("device does not meet device integrity")
+        );
+
+        let issued = challenge(&conn, &config);
+        let wrong_key = play_token(
+            &play_payload(&issued, &["MEETS_DEVICE_INTEGRITY"]),
+            &signer,
+            &[8u8; 32],
+        );
+        assert_eq!(
+            verdict(&conn, &config, &request(wrong_key, &issued))
+                .1
+                .as_deref(),
+            Some("token key does not unwrap")
+        );
+    }
+}
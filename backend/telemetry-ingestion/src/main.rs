@@ -1,26 +1,144 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
+mod attestation;
+
 use axum::{
-    extract::State,
+    extract::{Path as UrlPath, Query, State},
     http::StatusCode,
-    http::{header::AUTHORIZATION, HeaderMap},
+    http::{header::{AUTHORIZATION, CONTENT_TYPE}, HeaderMap},
     routing::post,
     routing::get,
     response::Html,
     Json, Router,
 };
-use chrono::Utc;
-use rusqlite::{params, Connection};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use rust_core::adapters::crypto::{Ed25519PublicKey, Ed25519Verifier};
 use rust_core::adapters::serialization::{
-    validate_telemetry_event, TelemetryEventDto,
+    validate_app_key, validate_app_risk_profile, validate_attestation_challenge_request,
+    validate_attestation_verify_request, validate_risk_score_request, validate_telemetry_event,
+    AppKeyDto, AppRiskProfileDto, AttestationChallengeDto, AttestationChallengeRequestDto,
+    AttestationStatusDto, AttestationVerdictDto, AttestationVerifyRequestDto,
+    RiskScoreRequestDto, RiskScoreResponseDto, TelemetryBatchItemDto, TelemetryBatchResultDto,
+    TelemetryBatchStatusDto, TelemetryEventDto,
+};
+use rust_core::app::policy_verification::verify_policy_signature;
+use rust_core::ports::SignatureVerifier;
+use rust_core::domain::telemetry::Platform;
+use rust_core::{
+    AttestationProvider, AttestationResult, AttestationStatus, Finding, IntegritySignals,
+    RiskObservation, RiskWeights, SignedPolicy, SigningPayloadVersion, TelemetryEvent,
+};
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::attestation::{
+    check_event_challenge, claim_event_challenge, init_attestation_tables, issue_challenge,
+    issue_verdict, prune_challenges, AttestationConfig, AttestationError,
 };
-use tracing::info;
+
+const DEFAULT_MAX_BATCH_EVENTS: usize = 100;
+const DEFAULT_MAX_EVENT_AGE_SECS: i64 = 7 * 24 * 60 * 60;
+const DEFAULT_MAX_FUTURE_SKEW_SECS: i64 = 5 * 60;
+/// Most recent events of a device considered by `/v1/telemetry/risk`.
+const MAX_RISK_OBSERVATIONS: usize = 1000;
+const DEFAULT_EVENT_PAGE_SIZE: usize = 50;
+const MAX_EVENT_PAGE_SIZE: usize = 500;
+/// `group_by` names accepted by `/v1/telemetry/stats` and the columns behind them.
+const STATS_DIMENSIONS: [(&str, &str); 6] = [
+    ("app_id", "app_id"),
+    ("app_version", "app_version"),
+    ("env", "env"),
+    ("platform", "platform"),
+    ("action", "action_name"),
+    ("attestation", "attestation_result"),
+];
+
+/// Queryable columns copied out of the `payload` JSON when an event is stored.
+/// Databases created before these existed are migrated by `init_db`.
+const EVENT_COLUMNS: [(&str, &str); 14] = [
+    ("app_id", "TEXT"),
+    ("app_version", "TEXT"),
+    ("env", "TEXT"),
+    ("platform", "TEXT"),
+    ("action_name", "TEXT"),
+    ("jailbreak", "INTEGER"),
+    ("root", "INTEGER"),
+    ("debugger", "INTEGER"),
+    ("hooking", "INTEGER"),
+    ("proxy_detected", "INTEGER"),
+    ("attestation_result", "TEXT"),
+    ("occurred_at", "TEXT"),
+    ("session_id", "TEXT"),
+    ("user_id_hash", "TEXT"),
+];
 
 #[derive(Clone)]
 struct AppState {
     db: Arc<Mutex<Connection>>,
     api_token: Option<String>,
+    admission: AdmissionRules,
+    max_batch_events: usize,
+    attestation: Arc<AttestationConfig>,
+    /// Policy-service key the policies of risk profiles must be signed with;
+    /// empty when `POLICY_PUBLIC_KEY` is not set.
+    policy_verifier: Arc<Ed25519Verifier>,
+}
+
+/// Checks an event must pass, besides DTO validation, before it is stored.
+#[derive(Clone, Copy)]
+struct AdmissionRules {
+    min_payload_version: SigningPayloadVersion,
+    /// Oldest event `timestamp` accepted, relative to `received_at`. Generous by
+    /// default because the SDK queues events while the device is offline.
+    max_event_age: Duration,
+    /// How far an event `timestamp` may be ahead of `received_at`.
+    max_future_skew: Duration,
+    /// Reject events reporting a `pass` or `fail` attestation without a
+    /// server-issued challenge (on unless the env opts out). Events that carry
+    /// one are always checked.
+    require_attestation_challenge: bool,
+}
+
+/// Why an event was not admitted; each kind maps to its own status code.
+#[derive(Debug)]
+enum AdmissionError {
+    Invalid(String),
+    Unauthorized(String),
+    Stale(String),
+    FutureDated(String),
+    Duplicate(String),
+    Challenge(String),
+    Storage(rusqlite::Error),
+}
+
+impl AdmissionError {
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::Invalid(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Duplicate(_) => StatusCode::CONFLICT,
+            Self::Challenge(_) => StatusCode::FORBIDDEN,
+            Self::Stale(_) => StatusCode::GONE,
+            Self::FutureDated(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Replay, skew and challenge rejections carry a stable prefix clients can match on.
+    fn message(self) -> String {
+        match self {
+            Self::Invalid(message) | Self::Unauthorized(message) => message,
+            Self::Stale(message) => format!("stale_event: {}", message),
+            Self::FutureDated(message) => format!("future_event: {}", message),
+            Self::Duplicate(message) => format!("duplicate_event: {}", message),
+            Self::Challenge(message) => format!("attestation_challenge: {}", message),
+            Self::Storage(err) => err.to_string(),
+        }
+    }
 }
 
 #[tokio::main]
@@ -28,16 +146,48 @@ async fn main() {
     tracing_subscriber::fmt::init();
     let db_path = std::env::var("TELEMETRY_DB_PATH").unwrap_or_else(|_| "data/telemetry.db".to_string());
     let api_token = std::env::var("API_TOKEN").ok();
+    // Legacy v1 signatures only cover part of the event; set to 1 to accept
+    // them from SDKs that predate the canonical payload.
+    let min_payload_version = std::env::var("TELEMETRY_MIN_PAYLOAD_VERSION")
+        .ok()
+        .and_then(|value| value.parse::<u32>().ok())
+        .and_then(SigningPayloadVersion::from_number)
+        .unwrap_or(SigningPayloadVersion::V2);
+    let max_event_age = std::env::var("TELEMETRY_MAX_EVENT_AGE_SECS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_MAX_EVENT_AGE_SECS);
+    let max_future_skew = std::env::var("TELEMETRY_MAX_FUTURE_SKEW_SECS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_MAX_FUTURE_SKEW_SECS);
+    let max_batch_events = std::env::var("TELEMETRY_MAX_BATCH_EVENTS")
+        .ok()
+        .and_then(|value| value.parse::<usize>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_MAX_BATCH_EVENTS);
+    // Opt out with `false` only while SDKs that cannot fetch challenges are in the field.
+    let require_attestation_challenge = std::env::var("TELEMETRY_REQUIRE_ATTESTATION_CHALLENGE")
+        .map(|value| value != "false" && value != "0")
+        .unwrap_or(true);
+    let attestation = AttestationConfig::from_env().expect("failed to load attestation config");
+    let policy_verifier = load_policy_verifier().expect("failed to load POLICY_PUBLIC_KEY");
     let conn = init_db(&db_path).expect("failed to init telemetry db");
     let state = AppState {
         db: Arc::new(Mutex::new(conn)),
         api_token,
+        admission: AdmissionRules {
+            min_payload_version,
+            max_event_age: Duration::seconds(max_event_age),
+            max_future_skew: Duration::seconds(max_future_skew),
+            require_attestation_challenge,
+        },
+        max_batch_events,
+        attestation: Arc::new(attestation),
+        policy_verifier: Arc::new(policy_verifier),
     };
 
-    let app = Router::new()
-        .route("/", get(root_status))
-        .route("/v1/telemetry/events", post(ingest_event))
-        .with_state(state);
+    let app = router(state);
 
     let addr = "0.0.0.0:8081";
     info!("telemetry-ingestion listening on {}", addr);
@@ -46,29 +196,258 @@ async fn main() {
     axum::serve(listener, app).await.unwrap();
 }
 
+fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/", get(root_status))
+        .route("/v1/telemetry/events", post(ingest_event).get(list_events))
+        .route("/v1/telemetry/events/batch", post(ingest_batch))
+        // `events:batch` is a single path segment, so it is captured and matched here.
+        .route("/v1/telemetry/:segment", post(ingest_batch_segment))
+        .route("/v1/telemetry/stats", get(telemetry_stats))
+        .route("/v1/telemetry/risk", post(score_risk))
+        .route("/v1/apps/keys", post(register_app_key))
+        .route("/v1/apps/risk-profile", post(register_risk_profile))
+        .route("/v1/attestation/challenge", post(attestation_challenge))
+        .route("/v1/attestation/verify", post(verify_attestation))
+        .with_state(state)
+}
+
 async fn ingest_event(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(payload): Json<TelemetryEventDto>,
 ) -> Result<Json<StatusOk>, (StatusCode, String)> {
     require_auth(&headers, &state.api_token)?;
-    validate_telemetry_event(&payload)
+    let now = Utc::now();
+
+    let conn = state
+        .db
+        .lock()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "storage lock".to_string()))?;
+
+    admit_event(&conn, &payload, &state.admission, now)
+        .map_err(|rejection| (rejection.status(), rejection.message()))?;
+
+    record_event(&conn, &payload, &state.admission, now)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    Ok(Json(StatusOk { status: "ok".to_string() }))
+}
+
+async fn list_events(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<EventQuery>,
+) -> Result<Json<EventPage>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+    let filter = EventFilter::from_query(&query).map_err(|err| (StatusCode::BAD_REQUEST, err))?;
+
+    let conn = state
+        .db
+        .lock()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "storage lock".to_string()))?;
+    let page = fetch_events(&conn, &filter)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    Ok(Json(page))
+}
+
+async fn telemetry_stats(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<EventQuery>,
+    Query(stats): Query<StatsQuery>,
+) -> Result<Json<StatsResponse>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+    let filter = EventFilter::from_query(&query).map_err(|err| (StatusCode::BAD_REQUEST, err))?;
+    let bucket = StatsBucketSize::parse(stats.bucket.as_deref())
+        .map_err(|err| (StatusCode::BAD_REQUEST, err))?;
+    let group_by = parse_dimensions(stats.group_by.as_deref())
+        .map_err(|err| (StatusCode::BAD_REQUEST, err))?;
+
+    let conn = state
+        .db
+        .lock()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "storage lock".to_string()))?;
+    let buckets = fetch_stats(&conn, &filter, bucket, &group_by)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    Ok(Json(StatsResponse {
+        bucket: bucket.as_str().to_string(),
+        group_by: group_by.iter().map(|(name, _)| name.to_string()).collect(),
+        buckets,
+    }))
+}
+
+async fn score_risk(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<RiskScoreRequestDto>,
+) -> Result<Json<RiskScoreResponseDto>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+    validate_risk_score_request(&payload)
         .map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
+    let now = Utc::now();
 
-    let payload_json = serde_json::to_string(&payload)
+    let conn = state
+        .db
+        .lock()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "storage lock".to_string()))?;
+    let observations = load_risk_observations(&conn, &payload, now - state.admission.max_event_age, now)
         .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
-    let received_at = Utc::now().to_rfc3339();
+    let (weights, findings) =
+        load_risk_profile(&conn, &payload.app_id, &payload.app_version, &payload.env)
+            .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    let score = weights.score_history(&observations, &findings);
+
+    Ok(Json(RiskScoreResponseDto {
+        risk_score: score.value(),
+        events_considered: observations.len(),
+        computed_at: now.to_rfc3339(),
+    }))
+}
+
+async fn attestation_challenge(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<AttestationChallengeRequestDto>,
+) -> Result<Json<AttestationChallengeDto>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+    validate_attestation_challenge_request(&payload)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
 
     let conn = state
         .db
         .lock()
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "storage lock".to_string()))?;
+    let challenge = issue_challenge(&conn, &state.attestation, &payload, Utc::now())
+        .map_err(attestation_error)?;
 
-    conn.execute(
-        "INSERT OR IGNORE INTO events (event_id, payload, received_at) VALUES (?1, ?2, ?3)",
-        params![payload.event_id, payload_json, received_at],
-    )
-    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    Ok(Json(challenge))
+}
+
+async fn verify_attestation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<AttestationVerifyRequestDto>,
+) -> Result<Json<AttestationVerdictDto>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+    validate_attestation_verify_request(&payload)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
+
+    let conn = state
+        .db
+        .lock()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "storage lock".to_string()))?;
+    let verdict = issue_verdict(&conn, &state.attestation, &payload, Utc::now())
+        .map_err(attestation_error)?;
+
+    Ok(Json(verdict))
+}
+
+fn attestation_error(err: AttestationError) -> (StatusCode, String) {
+    match err {
+        AttestationError::Invalid(message) | AttestationError::Rejected(message) => {
+            (StatusCode::BAD_REQUEST, message)
+        }
+        AttestationError::Duplicate(message) => (StatusCode::CONFLICT, message),
+        AttestationError::Stale(message) => (StatusCode::GONE, message),
+        AttestationError::Storage(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+/// Serves `POST /v1/telemetry/events:batch`; any other segment is a 404.
+async fn ingest_batch_segment(
+    UrlPath(segment): UrlPath<String>,
+    state: State<AppState>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<TelemetryBatchResultDto>, (StatusCode, String)> {
+    if segment != "events:batch" {
+        return Err((StatusCode::NOT_FOUND, "not found".to_string()));
+    }
+    ingest_batch(state, headers, body).await
+}
+
+async fn ingest_batch(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<TelemetryBatchResultDto>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+
+    let ndjson = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/x-ndjson"));
+    let events = parse_batch(&body, ndjson).map_err(|err| (StatusCode::BAD_REQUEST, err))?;
+    if events.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "batch is empty".to_string()));
+    }
+    if events.len() > state.max_batch_events {
+        return Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("batch exceeds {} events", state.max_batch_events),
+        ));
+    }
+
+    let mut conn = state
+        .db
+        .lock()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "storage lock".to_string()))?;
+    let results = store_batch(&mut conn, events, &state.admission, Utc::now())
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let accepted = results
+        .iter()
+        .filter(|item| item.status == TelemetryBatchStatusDto::Accepted)
+        .count();
+    info!("telemetry batch: {} accepted, {} rejected", accepted, results.len() - accepted);
+
+    Ok(Json(TelemetryBatchResultDto {
+        accepted,
+        rejected: results.len() - accepted,
+        results,
+    }))
+}
+
+async fn register_app_key(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<AppKeyDto>,
+) -> Result<Json<StatusOk>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+    validate_app_key(&payload)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
+    Ed25519PublicKey::from_base64(&payload.key_id, &payload.public_key)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
+
+    let conn = state
+        .db
+        .lock()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "storage lock".to_string()))?;
+    store_app_key(&conn, &payload)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    Ok(Json(StatusOk { status: "ok".to_string() }))
+}
+
+async fn register_risk_profile(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<AppRiskProfileDto>,
+) -> Result<Json<StatusOk>, (StatusCode, String)> {
+    require_auth(&headers, &state.api_token)?;
+    validate_app_risk_profile(&payload)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.message))?;
+    verify_risk_profile(&state.policy_verifier, &payload)?;
+
+    let conn = state
+        .db
+        .lock()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "storage lock".to_string()))?;
+    store_risk_profile(&conn, &payload)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
 
     Ok(Json(StatusOk { status: "ok".to_string() }))
 }
@@ -91,6 +470,206 @@ struct StatusOk {
     status: String,
 }
 
+#[derive(Deserialize)]
+struct EventQuery {
+    app_id: Option<String>,
+    env: Option<String>,
+    app_version: Option<String>,
+    platform: Option<String>,
+    action: Option<String>,
+    jailbreak: Option<bool>,
+    root: Option<bool>,
+    debugger: Option<bool>,
+    hooking: Option<bool>,
+    proxy_detected: Option<bool>,
+    attestation: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    session_id: Option<String>,
+    user_id_hash: Option<String>,
+    cursor: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(serde::Serialize)]
+struct EventRecord {
+    event: TelemetryEventDto,
+    received_at: String,
+}
+
+#[derive(serde::Serialize)]
+struct EventPage {
+    events: Vec<EventRecord>,
+    /// Pass back as `cursor` to fetch the next (older) page; absent on the last page.
+    next_cursor: Option<String>,
+}
+
+/// Aggregation options; the event filters come from `EventQuery`.
+#[derive(Deserialize)]
+struct StatsQuery {
+    bucket: Option<String>,
+    /// Comma-separated `STATS_DIMENSIONS` names.
+    group_by: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum StatsBucketSize {
+    Hour,
+    Day,
+}
+
+impl StatsBucketSize {
+    fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.unwrap_or("day") {
+            "hour" => Ok(Self::Hour),
+            "day" => Ok(Self::Day),
+            other => Err(format!("bucket must be hour or day, got {}", other)),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+        }
+    }
+
+    /// Start of the bucket, derived from the normalized `occurred_at` text.
+    fn sql(&self) -> &'static str {
+        match self {
+            Self::Hour => "substr(occurred_at, 1, 13) || ':00:00Z'",
+            Self::Day => "substr(occurred_at, 1, 10) || 'T00:00:00Z'",
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct StatsResponse {
+    bucket: String,
+    group_by: Vec<String>,
+    buckets: Vec<StatsBucket>,
+}
+
+#[derive(serde::Serialize)]
+struct StatsBucket {
+    start: String,
+    group: BTreeMap<String, Option<String>>,
+    total: i64,
+    signals: SignalCounts,
+    attestation: AttestationCounts,
+}
+
+#[derive(serde::Serialize)]
+struct SignalCounts {
+    jailbreak: i64,
+    root: i64,
+    debugger: i64,
+    hooking: i64,
+    proxy_detected: i64,
+}
+
+#[derive(serde::Serialize)]
+struct AttestationCounts {
+    pass: i64,
+    fail: i64,
+    unknown: i64,
+    /// Events sent without an attestation result.
+    missing: i64,
+}
+
+/// SQL `WHERE` clause and bind values built from an `EventQuery`.
+struct EventFilter {
+    clauses: Vec<String>,
+    values: Vec<Value>,
+    limit: usize,
+}
+
+impl EventFilter {
+    fn from_query(query: &EventQuery) -> Result<Self, String> {
+        let mut filter = Self {
+            clauses: Vec::new(),
+            values: Vec::new(),
+            limit: query
+                .limit
+                .unwrap_or(DEFAULT_EVENT_PAGE_SIZE)
+                .clamp(1, MAX_EVENT_PAGE_SIZE),
+        };
+
+        let text_filters = [
+            ("app_id", &query.app_id),
+            ("env", &query.env),
+            ("app_version", &query.app_version),
+            ("platform", &query.platform),
+            ("action_name", &query.action),
+            ("attestation_result", &query.attestation),
+            ("session_id", &query.session_id),
+            ("user_id_hash", &query.user_id_hash),
+        ];
+        for (column, value) in text_filters {
+            if let Some(value) = value {
+                filter.push(&format!("{} = ?", column), Value::Text(value.clone()));
+            }
+        }
+
+        let flag_filters = [
+            ("jailbreak", query.jailbreak),
+            ("root", query.root),
+            ("debugger", query.debugger),
+            ("hooking", query.hooking),
+            ("proxy_detected", query.proxy_detected),
+        ];
+        for (column, value) in flag_filters {
+            if let Some(value) = value {
+                filter.push(&format!("{} = ?", column), Value::Integer(i64::from(value)));
+            }
+        }
+
+        if let Some(from) = &query.from {
+            let from = normalize_timestamp(from).ok_or("from must be an RFC 3339 timestamp")?;
+            filter.push("occurred_at >= ?", Value::Text(from));
+        }
+        if let Some(to) = &query.to {
+            let to = normalize_timestamp(to).ok_or("to must be an RFC 3339 timestamp")?;
+            filter.push("occurred_at < ?", Value::Text(to));
+        }
+        if let Some(cursor) = &query.cursor {
+            let rowid = cursor
+                .parse::<i64>()
+                .map_err(|_| "cursor is invalid".to_string())?;
+            filter.push("rowid < ?", Value::Integer(rowid));
+        }
+
+        Ok(filter)
+    }
+
+    fn push(&mut self, clause: &str, value: Value) {
+        self.clauses.push(clause.to_string());
+        self.values.push(value);
+    }
+
+    fn where_clause(&self) -> String {
+        if self.clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", self.clauses.join(" AND "))
+        }
+    }
+}
+
+fn parse_dimensions(value: Option<&str>) -> Result<Vec<(&'static str, &'static str)>, String> {
+    let mut dimensions = Vec::new();
+    for name in value.unwrap_or("").split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        let dimension = STATS_DIMENSIONS
+            .iter()
+            .find(|(candidate, _)| *candidate == name)
+            .ok_or_else(|| format!("unsupported group_by dimension {}", name))?;
+        if !dimensions.contains(dimension) {
+            dimensions.push(*dimension);
+        }
+    }
+    Ok(dimensions)
+}
+
 fn init_db(path: &str) -> Result<Connection, rusqlite::Error> {
     if let Some(parent) = Path::new(path).parent() {
         std::fs::create_dir_all(parent).ok();
@@ -105,10 +684,659 @@ fn init_db(path: &str) -> Result<Connection, rusqlite::Error> {
         )",
         [],
     )?;
+    migrate_event_columns(&conn)?;
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS event_nonces (
+            nonce TEXT PRIMARY KEY,
+            event_id TEXT NOT NULL,
+            seen_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_event_nonces_seen_at ON event_nonces (seen_at);",
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_keys (
+            app_id TEXT NOT NULL,
+            key_id TEXT NOT NULL,
+            public_key TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (app_id, key_id)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_risk_profiles (
+            app_id TEXT NOT NULL,
+            app_version TEXT NOT NULL,
+            env TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (app_id, app_version, env)
+        )",
+        [],
+    )?;
+    init_attestation_tables(&conn)?;
 
     Ok(conn)
 }
 
+/// Splits a batch body (JSON array or NDJSON) into events. Entries that do not
+/// parse are kept as errors so they can be rejected individually.
+fn parse_batch(body: &str, ndjson: bool) -> Result<Vec<Result<TelemetryEventDto, String>>, String> {
+    if ndjson {
+        return Ok(body
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str::<TelemetryEventDto>(line).map_err(|err| err.to_string()))
+            .collect());
+    }
+
+    let values = serde_json::from_str::<Vec<serde_json::Value>>(body)
+        .map_err(|err| format!("batch must be a JSON array of events: {}", err))?;
+    Ok(values
+        .into_iter()
+        .map(|value| serde_json::from_value::<TelemetryEventDto>(value).map_err(|err| err.to_string()))
+        .collect())
+}
+
+/// Admits and stores a batch in a single transaction. Rejected events do not
+/// abort the batch; a storage error rolls the whole batch back.
+fn store_batch(
+    conn: &mut Connection,
+    events: Vec<Result<TelemetryEventDto, String>>,
+    rules: &AdmissionRules,
+    now: DateTime<Utc>,
+) -> Result<Vec<TelemetryBatchItemDto>, rusqlite::Error> {
+    let tx = conn.transaction()?;
+    let mut results = Vec::with_capacity(events.len());
+
+    for (index, event) in events.into_iter().enumerate() {
+        let event_id = event.as_ref().ok().map(|event| event.event_id.clone());
+        let checked = match event {
+            Ok(event) => match admit_event(&tx, &event, rules, now) {
+                Ok(()) => Ok(event),
+                Err(AdmissionError::Storage(err)) => return Err(err),
+                Err(rejection) => Err(rejection.message()),
+            },
+            Err(err) => Err(err),
+        };
+
+        let item = match checked {
+            Ok(event) => {
+                record_event(&tx, &event, rules, now)?;
+                TelemetryBatchItemDto {
+                    index,
+                    event_id,
+                    status: TelemetryBatchStatusDto::Accepted,
+                    error: None,
+                }
+            }
+            Err(error) => TelemetryBatchItemDto {
+                index,
+                event_id,
+                status: TelemetryBatchStatusDto::Rejected,
+                error: Some(error),
+            },
+        };
+        results.push(item);
+    }
+
+    tx.commit()?;
+    Ok(results)
+}
+
+/// Runs every admission check; signatures are verified before the replay
+/// lookup so unauthenticated traffic cannot fill the nonce cache.
+fn admit_event(
+    conn: &Connection,
+    event: &TelemetryEventDto,
+    rules: &AdmissionRules,
+    now: DateTime<Utc>,
+) -> Result<(), AdmissionError> {
+    validate_telemetry_event(event).map_err(|err| AdmissionError::Invalid(err.message))?;
+    check_event_time(&event.timestamp, rules, now)?;
+    verify_event_signature(conn, event, rules.min_payload_version)
+        .map_err(AdmissionError::Unauthorized)?;
+    check_replay(conn, event)?;
+    check_attestation_challenge(conn, event, rules, now)
+}
+
+fn check_event_time(
+    timestamp: &str,
+    rules: &AdmissionRules,
+    now: DateTime<Utc>,
+) -> Result<(), AdmissionError> {
+    let occurred_at = DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|_| AdmissionError::Invalid("timestamp must be an RFC 3339 timestamp".to_string()))?
+        .with_timezone(&Utc);
+
+    if now - occurred_at > rules.max_event_age {
+        return Err(AdmissionError::Stale(format!(
+            "timestamp {} is more than {} seconds old",
+            timestamp,
+            rules.max_event_age.num_seconds()
+        )));
+    }
+    if occurred_at - now > rules.max_future_skew {
+        return Err(AdmissionError::FutureDated(format!(
+            "timestamp {} is more than {} seconds ahead of the server clock",
+            timestamp,
+            rules.max_future_skew.num_seconds()
+        )));
+    }
+    Ok(())
+}
+
+fn check_replay(conn: &Connection, event: &TelemetryEventDto) -> Result<(), AdmissionError> {
+    let stored = conn
+        .query_row(
+            "SELECT 1 FROM events WHERE event_id = ?1",
+            params![event.event_id],
+            |_| Ok(()),
+        )
+        .optional()
+        .map_err(AdmissionError::Storage)?
+        .is_some();
+    if stored {
+        return Err(AdmissionError::Duplicate(format!(
+            "event_id {} was already ingested",
+            event.event_id
+        )));
+    }
+
+    let replayed = conn
+        .query_row(
+            "SELECT event_id FROM event_nonces WHERE nonce = ?1",
+            params![event.signature],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .map_err(AdmissionError::Storage)?;
+    if let Some(original) = replayed {
+        return Err(AdmissionError::Duplicate(format!(
+            "signature was already used by event_id {}",
+            original
+        )));
+    }
+    Ok(())
+}
+
+/// Checks the challenge bound to the event's attestation. With
+/// `require_attestation_challenge`, any `pass` or `fail` claim must carry one,
+/// whatever provider it names.
+fn check_attestation_challenge(
+    conn: &Connection,
+    event: &TelemetryEventDto,
+    rules: &AdmissionRules,
+    now: DateTime<Utc>,
+) -> Result<(), AdmissionError> {
+    let Some(attestation) = &event.attestation else {
+        return Ok(());
+    };
+    match &attestation.challenge {
+        Some(challenge) => check_event_challenge(conn, event, attestation, challenge, now)
+            .map_err(|err| match err {
+                AttestationError::Invalid(message) => AdmissionError::Invalid(message),
+                AttestationError::Rejected(message)
+                | AttestationError::Duplicate(message)
+                | AttestationError::Stale(message) => AdmissionError::Challenge(message),
+                AttestationError::Storage(err) => AdmissionError::Storage(err),
+            }),
+        None if rules.require_attestation_challenge
+            && attestation.result != AttestationStatusDto::Unknown =>
+        {
+            Err(AdmissionError::Challenge(
+                "attestation.challenge is required".to_string(),
+            ))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Stores an admitted event and remembers its signature as a nonce. Nonces are
+/// kept for `max_event_age + max_future_skew`; a replay arriving later is
+/// rejected as stale instead.
+fn record_event(
+    conn: &Connection,
+    event: &TelemetryEventDto,
+    rules: &AdmissionRules,
+    now: DateTime<Utc>,
+) -> Result<(), rusqlite::Error> {
+    let seen_at = now.to_rfc3339_opts(SecondsFormat::Millis, true);
+    insert_event(conn, event, &now.to_rfc3339())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO event_nonces (nonce, event_id, seen_at) VALUES (?1, ?2, ?3)",
+        params![event.signature, event.event_id, seen_at],
+    )?;
+    if let Some(challenge) = event
+        .attestation
+        .as_ref()
+        .and_then(|attestation| attestation.challenge.as_deref())
+    {
+        claim_event_challenge(conn, challenge, &event.event_id)?;
+    }
+
+    let expired_before = (now - rules.max_event_age - rules.max_future_skew)
+        .to_rfc3339_opts(SecondsFormat::Millis, true);
+    conn.execute(
+        "DELETE FROM event_nonces WHERE seen_at < ?1",
+        params![expired_before],
+    )?;
+    prune_challenges(conn, &expired_before)?;
+    Ok(())
+}
+
+/// Adds missing `EVENT_COLUMNS`, backfills them from `payload` and creates the
+/// query indexes. Safe to run on every start.
+fn migrate_event_columns(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let existing = {
+        let mut stmt = conn.prepare("PRAGMA table_info(events)")?;
+        stmt.query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    for (column, kind) in EVENT_COLUMNS {
+        if !existing.iter().any(|name| name == column) {
+            conn.execute(&format!("ALTER TABLE events ADD COLUMN {} {}", column, kind), [])?;
+        }
+    }
+
+    let pending = {
+        let mut stmt = conn.prepare("SELECT rowid, payload FROM events WHERE app_id IS NULL")?;
+        stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    if !pending.is_empty() {
+        let assignments = EVENT_COLUMNS
+            .iter()
+            .map(|(column, _)| format!("{} = ?", column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!("UPDATE events SET {} WHERE rowid = ?", assignments);
+        for (rowid, payload) in pending {
+            match serde_json::from_str::<TelemetryEventDto>(&payload) {
+                Ok(event) => {
+                    let mut values = event_column_values(&event);
+                    values.push(Value::Integer(rowid));
+                    conn.execute(&sql, params_from_iter(values))?;
+                }
+                Err(err) => warn!("skipping backfill of unreadable event row {}: {}", rowid, err),
+            }
+        }
+    }
+
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_events_app_env_time ON events (app_id, env, occurred_at);
+        CREATE INDEX IF NOT EXISTS idx_events_occurred_at ON events (occurred_at);
+        CREATE INDEX IF NOT EXISTS idx_events_action ON events (action_name);
+        CREATE INDEX IF NOT EXISTS idx_events_session ON events (session_id);
+        CREATE INDEX IF NOT EXISTS idx_events_user ON events (user_id_hash);",
+    )
+}
+
+fn insert_event(
+    conn: &Connection,
+    event: &TelemetryEventDto,
+    received_at: &str,
+) -> Result<(), rusqlite::Error> {
+    let payload = serde_json::to_string(event)
+        .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))?;
+    let columns = EVENT_COLUMNS
+        .iter()
+        .map(|(column, _)| *column)
+        .collect::<Vec<_>>();
+    let sql = format!(
+        "INSERT INTO events (event_id, payload, received_at, {}) VALUES (?{})",
+        columns.join(", "),
+        ", ?".repeat(columns.len() + 2)
+    );
+
+    let mut values = vec![
+        Value::Text(event.event_id.clone()),
+        Value::Text(payload),
+        Value::Text(received_at.to_string()),
+    ];
+    values.extend(event_column_values(event));
+    conn.execute(&sql, params_from_iter(values))?;
+    Ok(())
+}
+
+/// Values for `EVENT_COLUMNS`, in the same order.
+fn event_column_values(event: &TelemetryEventDto) -> Vec<Value> {
+    let text = |value: Option<&str>| value.map_or(Value::Null, |value| Value::Text(value.to_string()));
+    let flag = |value: bool| Value::Integer(i64::from(value));
+    let session = event.session.as_ref();
+
+    vec![
+        text(Some(&event.app_id)),
+        text(Some(&event.app_version)),
+        text(Some(&event.env)),
+        text(Some(Platform::from(event.device.platform.clone()).as_str())),
+        text(Some(&event.action.name)),
+        flag(event.signals.jailbreak),
+        flag(event.signals.root),
+        flag(event.signals.debugger),
+        flag(event.signals.hooking),
+        flag(event.signals.proxy_detected),
+        text(
+            event
+                .attestation
+                .as_ref()
+                .map(|attestation| AttestationStatus::from(attestation.result.clone()).as_str()),
+        ),
+        text(normalize_timestamp(&event.timestamp).as_deref()),
+        text(session.map(|session| session.session_id.as_str())),
+        text(session.and_then(|session| session.user_id_hash.as_deref())),
+    ]
+}
+
+/// Fixed-width UTC form so stored timestamps compare correctly as text.
+fn normalize_timestamp(value: &str) -> Option<String> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+/// Newest events first; `rowid` doubles as the pagination cursor.
+fn fetch_events(conn: &Connection, filter: &EventFilter) -> Result<EventPage, rusqlite::Error> {
+    let sql = format!(
+        "SELECT rowid, payload, received_at FROM events {} ORDER BY rowid DESC LIMIT {}",
+        filter.where_clause(),
+        filter.limit + 1
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt
+        .query_map(params_from_iter(filter.values.iter()), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let next_cursor = if rows.len() > filter.limit {
+        rows.truncate(filter.limit);
+        rows.last().map(|(rowid, _, _)| rowid.to_string())
+    } else {
+        None
+    };
+
+    let events = rows
+        .into_iter()
+        .filter_map(|(rowid, payload, received_at)| {
+            match serde_json::from_str::<TelemetryEventDto>(&payload) {
+                Ok(event) => Some(EventRecord { event, received_at }),
+                Err(err) => {
+                    warn!("skipping unreadable event row {}: {}", rowid, err);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    Ok(EventPage {
+        events,
+        next_cursor,
+    })
+}
+
+/// Counts per time bucket and `group_by` combination. Events whose timestamp
+/// could not be parsed at ingestion have no bucket and are left out.
+fn fetch_stats(
+    conn: &Connection,
+    filter: &EventFilter,
+    bucket: StatsBucketSize,
+    group_by: &[(&'static str, &'static str)],
+) -> Result<Vec<StatsBucket>, rusqlite::Error> {
+    let mut keys = vec![bucket.sql().to_string()];
+    keys.extend(group_by.iter().map(|(_, column)| column.to_string()));
+    let keys = keys.join(", ");
+
+    let mut clauses = filter.clauses.clone();
+    clauses.push("occurred_at IS NOT NULL".to_string());
+    let sql = format!(
+        "SELECT {keys}, COUNT(*),
+            COUNT(*) FILTER (WHERE jailbreak = 1),
+            COUNT(*) FILTER (WHERE root = 1),
+            COUNT(*) FILTER (WHERE debugger = 1),
+            COUNT(*) FILTER (WHERE hooking = 1),
+            COUNT(*) FILTER (WHERE proxy_detected = 1),
+            COUNT(*) FILTER (WHERE attestation_result = 'pass'),
+            COUNT(*) FILTER (WHERE attestation_result = 'fail'),
+            COUNT(*) FILTER (WHERE attestation_result = 'unknown'),
+            COUNT(*) FILTER (WHERE attestation_result IS NULL)
+        FROM events WHERE {} GROUP BY {keys} ORDER BY {keys}",
+        clauses.join(" AND "),
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(filter.values.iter()), |row| {
+        let mut group = BTreeMap::new();
+        for (index, (name, _)) in group_by.iter().enumerate() {
+            group.insert(name.to_string(), row.get::<_, Option<String>>(index + 1)?);
+        }
+        let count = |offset: usize| row.get::<_, i64>(group_by.len() + offset);
+        Ok(StatsBucket {
+            start: row.get(0)?,
+            group,
+            total: count(1)?,
+            signals: SignalCounts {
+                jailbreak: count(2)?,
+                root: count(3)?,
+                debugger: count(4)?,
+                hooking: count(5)?,
+                proxy_detected: count(6)?,
+            },
+            attestation: AttestationCounts {
+                pass: count(7)?,
+                fail: count(8)?,
+                unknown: count(9)?,
+                missing: count(10)?,
+            },
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Recent events of the requested session or user, read from the indexed
+/// columns. Only events inside the admission window are considered.
+fn load_risk_observations(
+    conn: &Connection,
+    request: &RiskScoreRequestDto,
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<Vec<RiskObservation>, rusqlite::Error> {
+    let mut filter = EventFilter {
+        clauses: Vec::new(),
+        values: Vec::new(),
+        limit: MAX_RISK_OBSERVATIONS,
+    };
+    filter.push("app_id = ?", Value::Text(request.app_id.clone()));
+    filter.push("env = ?", Value::Text(request.env.clone()));
+    filter.push("app_version = ?", Value::Text(request.app_version.clone()));
+    if let Some(session_id) = &request.session_id {
+        filter.push("session_id = ?", Value::Text(session_id.clone()));
+    }
+    if let Some(user_id_hash) = &request.user_id_hash {
+        filter.push("user_id_hash = ?", Value::Text(user_id_hash.clone()));
+    }
+    filter.push(
+        "occurred_at >= ?",
+        Value::Text(since.to_rfc3339_opts(SecondsFormat::Millis, true)),
+    );
+
+    let sql = format!(
+        "SELECT jailbreak, root, debugger, hooking, proxy_detected, attestation_result, occurred_at
+        FROM events {} ORDER BY occurred_at DESC LIMIT {}",
+        filter.where_clause(),
+        filter.limit
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(filter.values.iter()), |row| {
+        let attestation = row
+            .get::<_, Option<String>>(5)?
+            .map(|status| AttestationResult {
+                // Scoring only looks at the status.
+                provider: AttestationProvider::None,
+                status: match status.as_str() {
+                    "pass" => AttestationStatus::Pass,
+                    "fail" => AttestationStatus::Fail,
+                    _ => AttestationStatus::Unknown,
+                },
+                timestamp: None,
+                challenge: None,
+            });
+        let occurred_at = row.get::<_, String>(6)?;
+        let age_secs = DateTime::parse_from_rfc3339(&occurred_at)
+            .map(|dt| (now - dt.with_timezone(&Utc)).num_seconds().max(0) as u64)
+            .unwrap_or(0);
+
+        Ok(RiskObservation {
+            signals: IntegritySignals {
+                jailbreak: row.get(0)?,
+                root: row.get(1)?,
+                debugger: row.get(2)?,
+                hooking: row.get(3)?,
+                proxy_detected: row.get(4)?,
+            },
+            attestation,
+            age_secs,
+        })
+    })?;
+
+    rows.collect()
+}
+
+fn store_app_key(conn: &Connection, key: &AppKeyDto) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT OR REPLACE INTO app_keys (app_id, key_id, public_key, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![key.app_id, key.key_id, key.public_key, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+/// Only policies signed by the policy-service are accepted, so the weights
+/// scored with are the ones the SDK verified.
+fn verify_risk_profile(
+    verifier: &Ed25519Verifier,
+    profile: &AppRiskProfileDto,
+) -> Result<(), (StatusCode, String)> {
+    if verifier.is_empty() {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "POLICY_PUBLIC_KEY is not configured".to_string(),
+        ));
+    }
+    verify_policy_signature(&SignedPolicy::from(profile.policy.clone()), verifier)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.message))
+}
+
+fn store_risk_profile(conn: &Connection, profile: &AppRiskProfileDto) -> Result<(), rusqlite::Error> {
+    let payload = serde_json::to_string(profile)
+        .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO app_risk_profiles (app_id, app_version, env, payload, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            profile.policy.app_id,
+            profile.policy.app_version,
+            profile.policy.env,
+            payload,
+            Utc::now().to_rfc3339()
+        ],
+    )?;
+    Ok(())
+}
+
+/// Weights of the signed policy and findings registered for an app version and env;
+/// default weights and no findings when none were registered.
+fn load_risk_profile(
+    conn: &Connection,
+    app_id: &str,
+    app_version: &str,
+    env: &str,
+) -> Result<(RiskWeights, Vec<Finding>), rusqlite::Error> {
+    let payload = conn
+        .query_row(
+            "SELECT payload FROM app_risk_profiles WHERE app_id = ?1 AND app_version = ?2 AND env = ?3",
+            params![app_id, app_version, env],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()?;
+    let Some(payload) = payload else {
+        return Ok((RiskWeights::default(), Vec::new()));
+    };
+
+    let profile = serde_json::from_str::<AppRiskProfileDto>(&payload).map_err(|err| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(err))
+    })?;
+    let weights = profile.policy.risk_weights.map(RiskWeights::from).unwrap_or_default();
+    let findings = profile
+        .findings
+        .unwrap_or_default()
+        .into_iter()
+        .map(Finding::from)
+        .collect();
+    Ok((weights, findings))
+}
+
+/// Policy-service public key (`POLICY_PUBLIC_KEY`, base64) under
+/// `POLICY_PUBLIC_KEY_ID` (default `policy-service`).
+fn load_policy_verifier() -> Result<Ed25519Verifier, String> {
+    let Ok(public_key) = std::env::var("POLICY_PUBLIC_KEY") else {
+        warn!("POLICY_PUBLIC_KEY not set; risk profiles cannot be registered");
+        return Ok(Ed25519Verifier::default());
+    };
+    let key_id =
+        std::env::var("POLICY_PUBLIC_KEY_ID").unwrap_or_else(|_| "policy-service".to_string());
+    let key = Ed25519PublicKey::from_base64(&key_id, &public_key).map_err(|err| err.message)?;
+    Ok(Ed25519Verifier::new(vec![key]))
+}
+
+fn load_app_verifier(conn: &Connection, app_id: &str) -> Result<Ed25519Verifier, rusqlite::Error> {
+    let mut stmt = conn.prepare("SELECT key_id, public_key FROM app_keys WHERE app_id = ?1")?;
+    let mut rows = stmt.query(params![app_id])?;
+    let mut keys = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        let key_id: String = row.get(0)?;
+        let public_key: String = row.get(1)?;
+        if let Ok(key) = Ed25519PublicKey::from_base64(&key_id, &public_key) {
+            keys.push(key);
+        }
+    }
+
+    Ok(Ed25519Verifier::new(keys))
+}
+
+fn verify_event_signature(
+    conn: &Connection,
+    event: &TelemetryEventDto,
+    min_payload_version: SigningPayloadVersion,
+) -> Result<(), String> {
+    let domain = TelemetryEvent::from(event.clone());
+    if domain.payload_version < min_payload_version {
+        return Err(format!(
+            "payload_version {} is below the minimum {}",
+            domain.payload_version.number(),
+            min_payload_version.number()
+        ));
+    }
+
+    let verifier = load_app_verifier(conn, &event.app_id).map_err(|err| err.to_string())?;
+    if verifier.is_empty() {
+        return Err(format!("no signing keys registered for app {}", event.app_id));
+    }
+
+    verifier
+        .verify(&domain.signing_payload(), &event.signature)
+        .map_err(|err| err.message)
+}
+
 fn check_db(state: &AppState) -> Result<(), String> {
     let conn = state
         .db
@@ -140,3 +1368,678 @@ fn require_auth(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attestation::record_verdict;
+    use rust_core::adapters::serialization::{
+        ActionContextDto, AttestationProviderDto, AttestationResultDto, AttestationStatusDto, DeviceInfoDto, IntegritySignalsDto, PlatformDto, RiskBreakdownDto,
+        RiskContributionDto, RiskFactorDto, SessionInfoDto,
+    };
+    use rust_core::ports::CryptoSigner;
+    use rust_core::Ed25519Signer;
+
+    fn event(app_id: &str) -> TelemetryEventDto {
+        TelemetryEventDto {
+            event_id: "evt_1".to_string(),
+            app_id: app_id.to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "prod".to_string(),
+            device: DeviceInfoDto {
+                platform: PlatformDto::Ios,
+                os_version: "17.0".to_string(),
+                model: "iPhone".to_string(),
+            },
+            session: None,
+            signals: IntegritySignalsDto {
+                jailbreak: false,
+                root: false,
+                debugger: false,
+                hooking: false,
+                proxy_detected: false,
+            },
+            attestation: None,
+            action: ActionContextDto {
+                name: "login".to_string(),
+                context: None,
+            },
+            timestamp: "2026-02-06T21:00:00Z".to_string(),
+            signature: String::new(),
+            payload_version: Some(3),
+            risk_breakdown: None,
+        }
+    }
+
+    fn sign(signer: &Ed25519Signer, event: &mut TelemetryEventDto) {
+        let payload = TelemetryEvent::from(event.clone()).signing_payload();
+        event.signature = signer.sign(&payload).expect("sign");
+    }
+
+    fn register(conn: &Connection, app_id: &str, signer: &Ed25519Signer) {
+        store_app_key(
+            conn,
+            &AppKeyDto {
+                app_id: app_id.to_string(),
+                key_id: signer.key_id().to_string(),
+                public_key: signer.public_key().to_base64(),
+            },
+        )
+        .expect("store key");
+    }
+
+    fn rules() -> AdmissionRules {
+        AdmissionRules {
+            min_payload_version: SigningPayloadVersion::V2,
+            max_event_age: Duration::hours(1),
+            max_future_skew: Duration::minutes(5),
+            require_attestation_challenge: true,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-02-06T21:05:00Z")
+            .expect("now")
+            .with_timezone(&Utc)
+    }
+
+    #[tokio::test]
+    async fn router_serves_batch_ingestion_on_the_batch_action_path() {
+        use axum::body::{to_bytes, Body};
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let conn = init_db(":memory:").expect("db init");
+        let signer = Ed25519Signer::generate("device-1").expect("signer");
+        register(&conn, "app.batch", &signer);
+        let app = router(AppState {
+            db: Arc::new(Mutex::new(conn)),
+            api_token: None,
+            admission: rules(),
+            max_batch_events: 10,
+            attestation: Arc::new(AttestationConfig::default()),
+            policy_verifier: Arc::new(Ed25519Verifier::default()),
+        });
+
+        let body = |event_id: &str| {
+            let mut batch_event = event("app.batch");
+            batch_event.event_id = event_id.to_string();
+            batch_event.timestamp = Utc::now().to_rfc3339();
+            sign(&signer, &mut batch_event);
+            serde_json::to_string(&vec![batch_event]).expect("json")
+        };
+        let request = |uri: &str, body: String| {
+            Request::post(uri)
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .expect("request")
+        };
+
+        for (uri, event_id) in [
+            ("/v1/telemetry/events:batch", "batch-1"),
+            ("/v1/telemetry/events/batch", "batch-2"),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(request(uri, body(event_id)))
+                .await
+                .expect("response");
+            assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+            let bytes = to_bytes(response.into_body(), usize::MAX).await.expect("body");
+            let result: TelemetryBatchResultDto = serde_json::from_slice(&bytes).expect("result");
+            assert_eq!((result.accepted, result.rejected), (1, 0), "{}", uri);
+        }
+
+        let response = app
+            .oneshot(request("/v1/telemetry/events:purge", body("batch-3")))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn accepts_events_signed_with_registered_key() {
+        let conn = init_db(":memory:").expect("db init");
+        let signer = Ed25519Signer::generate("k1").expect("signer");
+        register(&conn, "app.test", &signer);
+
+        let mut dto = event("app.test");
+        sign(&signer, &mut dto);
+
+        assert!(verify_event_signature(&conn, &dto, SigningPayloadVersion::V1).is_ok());
+    }
+
+    #[test]
+    fn rejects_tampered_and_unregistered_events() {
+        let conn = init_db(":memory:").expect("db init");
+        let signer = Ed25519Signer::generate("k1").expect("signer");
+        register(&conn, "app.test", &signer);
+
+        let mut dto = event("app.test");
+        sign(&signer, &mut dto);
+        dto.action.name = "transfer".to_string();
+        assert!(verify_event_signature(&conn, &dto, SigningPayloadVersion::V1).is_err());
+
+        let mut other_app = event("app.other");
+        sign(&signer, &mut other_app);
+        assert!(verify_event_signature(&conn, &other_app, SigningPayloadVersion::V1).is_err());
+    }
+
+    #[test]
+    fn risk_breakdown_is_stored_and_covered_by_the_signature() {
+        let conn = init_db(":memory:").expect("db init");
+        let signer = Ed25519Signer::generate("k1").expect("signer");
+        register(&conn, "app.test", &signer);
+
+        let mut dto = event("app.test");
+        dto.signals.debugger = true;
+        dto.risk_breakdown = Some(RiskBreakdownDto {
+            score: 30,
+            factors: vec![RiskContributionDto {
+                factor: RiskFactorDto::Debugger,
+                category: None,
+                severity: None,
+                weight: 30,
+                points: 30,
+                capped: false,
+            }],
+        });
+        sign(&signer, &mut dto);
+        assert!(validate_telemetry_event(&dto).is_ok());
+        assert!(verify_event_signature(&conn, &dto, SigningPayloadVersion::V3).is_ok());
+        let mut unsigned_breakdown = dto.clone();
+        unsigned_breakdown.payload_version = Some(2);
+        assert!(validate_telemetry_event(&unsigned_breakdown).is_err());
+
+        let stored: TelemetryEventDto =
+            serde_json::from_str(&serde_json::to_string(&dto).expect("json")).expect("parse");
+        assert_eq!(stored.risk_breakdown, dto.risk_breakdown);
+
+        let mut tampered = dto.clone();
+        if let Some(breakdown) = tampered.risk_breakdown.as_mut() {
+            breakdown.score = 0;
+            breakdown.factors.clear();
+        }
+        assert!(verify_event_signature(&conn, &tampered, SigningPayloadVersion::V2).is_err());
+
+        let mut inconsistent = dto;
+        if let Some(breakdown) = inconsistent.risk_breakdown.as_mut() {
+            breakdown.score = 90;
+        }
+        assert!(validate_telemetry_event(&inconsistent).is_err());
+    }
+
+    #[test]
+    fn legacy_payloads_verify_until_minimum_is_raised() {
+        let conn = init_db(":memory:").expect("db init");
+        let signer = Ed25519Signer::generate("k1").expect("signer");
+        register(&conn, "app.test", &signer);
+
+        let mut legacy = event("app.test");
+        legacy.payload_version = None;
+        sign(&signer, &mut legacy);
+        assert!(verify_event_signature(&conn, &legacy, SigningPayloadVersion::V1).is_ok());
+        assert!(verify_event_signature(&conn, &legacy, SigningPayloadVersion::V2).is_err());
+
+        let mut tampered = event("app.test");
+        sign(&signer, &mut tampered);
+        tampered.signals.debugger = true;
+        assert!(verify_event_signature(&conn, &tampered, SigningPayloadVersion::V1).is_err());
+    }
+
+    #[test]
+    fn batch_stores_valid_events_and_rejects_the_rest() {
+        let mut conn = init_db(":memory:").expect("db init");
+        let signer = Ed25519Signer::generate("k1").expect("signer");
+        register(&conn, "app.test", &signer);
+
+        let mut valid = event("app.test");
+        sign(&signer, &mut valid);
+        let mut tampered = event("app.test");
+        tampered.event_id = "evt_2".to_string();
+        sign(&signer, &mut tampered);
+        tampered.signals.hooking = true;
+        let mut blank = event("app.test");
+        blank.event_id = "evt_3".to_string();
+        blank.action.name = String::new();
+
+        let body = [&valid, &tampered, &blank]
+            .iter()
+            .map(|event| serde_json::to_string(event).expect("json"))
+            .chain(["{\"event_id\":".to_string()])
+            .collect::<Vec<_>>()
+            .join("\n");
+        let events = parse_batch(&body, true).expect("ndjson");
+        let results = store_batch(&mut conn, events, &rules(), now()).expect("store");
+
+        let statuses = results.iter().map(|item| item.status.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                TelemetryBatchStatusDto::Accepted,
+                TelemetryBatchStatusDto::Rejected,
+                TelemetryBatchStatusDto::Rejected,
+                TelemetryBatchStatusDto::Rejected,
+            ]
+        );
+        assert_eq!(results[1].event_id.as_deref(), Some("evt_2"));
+        assert_eq!(results[3].event_id, None);
+
+        let stored: i64 = conn
+            .query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(stored, 1);
+
+        let array = serde_json::to_string(&vec![&valid]).expect("json");
+        assert_eq!(parse_batch(&array, false).expect("array").len(), 1);
+        assert!(parse_batch("{}", false).is_err());
+    }
+
+    #[test]
+    fn admission_rejects_stale_future_and_replayed_events() {
+        let conn = init_db(":memory:").expect("db init");
+        let signer = Ed25519Signer::generate("k1").expect("signer");
+        register(&conn, "app.test", &signer);
+
+        let mut stale = event("app.test");
+        stale.timestamp = "2026-02-06T19:59:59Z".to_string();
+        sign(&signer, &mut stale);
+        let rejection = admit_event(&conn, &stale, &rules(), now()).unwrap_err();
+        assert_eq!(rejection.status(), StatusCode::GONE);
+        assert!(rejection.message().starts_with("stale_event:"));
+
+        let mut future = event("app.test");
+        future.timestamp = "2026-02-06T21:10:01Z".to_string();
+        sign(&signer, &mut future);
+        let rejection = admit_event(&conn, &future, &rules(), now()).unwrap_err();
+        assert_eq!(rejection.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let mut undated = event("app.test");
+        undated.timestamp = "yesterday".to_string();
+        sign(&signer, &mut undated);
+        let rejection = admit_event(&conn, &undated, &rules(), now()).unwrap_err();
+        assert_eq!(rejection.status(), StatusCode::BAD_REQUEST);
+
+        let mut dto = event("app.test");
+        sign(&signer, &mut dto);
+        admit_event(&conn, &dto, &rules(), now()).expect("fresh event");
+        record_event(&conn, &dto, &rules(), now()).expect("record");
+
+        let rejection = admit_event(&conn, &dto, &rules(), now()).unwrap_err();
+        assert_eq!(rejection.status(), StatusCode::CONFLICT);
+        assert!(rejection.message().starts_with("duplicate_event:"));
+
+        // The nonce cache still catches the replay once the event row is gone.
+        conn.execute("DELETE FROM events", []).expect("purge");
+        let rejection = admit_event(&conn, &dto, &rules(), now()).unwrap_err();
+        assert_eq!(rejection.status(), StatusCode::CONFLICT);
+    }
+
+    #[test]
+    fn attestation_challenge_binds_one_event_of_the_session_action() {
+        let conn = init_db(":memory:").expect("db init");
+        let signer = Ed25519Signer::generate("k1").expect("signer");
+        register(&conn, "app.test", &signer);
+        let issued_at = DateTime::parse_from_rfc3339("2026-02-06T20:58:00Z")
+            .expect("issued_at")
+            .with_timezone(&Utc);
+        let challenge = issue_challenge(
+            &conn,
+            &AttestationConfig::default(),
+            &AttestationChallengeRequestDto {
+                app_id: "app.test".to_string(),
+                session_id: "sess_1".to_string(),
+                action: "login".to_string(),
+            },
+            issued_at,
+        )
+        .expect("challenge")
+        .challenge;
+        let live = issued_at + Duration::minutes(2);
+        let attested = |event_id: &str, challenge: &str| {
+            let mut dto = event("app.test");
+            dto.event_id = event_id.to_string();
+            dto.session = Some(SessionInfoDto {
+                session_id: "sess_1".to_string(),
+                user_id_hash: None,
+            });
+            dto.attestation = Some(AttestationResultDto {
+                provider: AttestationProviderDto::AppAttest,
+                result: AttestationStatusDto::Pass,
+                timestamp: None,
+                challenge: Some(challenge.to_string()),
+            });
+            sign(&signer, &mut dto);
+            dto
+        };
+
+        let rejection = admit_event(&conn, &attested("evt_unverified", &challenge), &rules(), live)
+            .unwrap_err();
+        assert!(rejection.message().contains("no verdict"));
+        record_verdict(
+            &conn,
+            &challenge,
+            &AttestationProviderDto::AppAttest,
+            &AttestationStatusDto::Pass,
+        )
+        .expect("verdict");
+
+        let mut other_provider = attested("evt_other_provider", &challenge);
+        if let Some(attestation) = other_provider.attestation.as_mut() {
+            attestation.provider = AttestationProviderDto::PlayIntegrity;
+        }
+        sign(&signer, &mut other_provider);
+        let rejection = admit_event(&conn, &other_provider, &rules(), live).unwrap_err();
+        assert!(rejection.message().contains("does not match the app_attest pass verdict"));
+
+        let mut other_action = attested("evt_transfer", &challenge);
+        other_action.action.name = "transfer".to_string();
+        sign(&signer, &mut other_action);
+        let rejection = admit_event(&conn, &other_action, &rules(), live).unwrap_err();
+        assert_eq!(rejection.status(), StatusCode::FORBIDDEN);
+        assert!(rejection.message().starts_with("attestation_challenge:"));
+
+        // Expiry is checked on the server clock, whatever the event timestamp says.
+        let rejection = admit_event(&conn, &attested("evt_late", &challenge), &rules(), now())
+            .unwrap_err();
+        assert!(rejection.message().contains("expired"));
+
+        let login = attested("evt_login", &challenge);
+        admit_event(&conn, &login, &rules(), live).expect("bound event");
+        record_event(&conn, &login, &rules(), live).expect("record");
+        let rejection = admit_event(&conn, &attested("evt_again", &challenge), &rules(), live)
+            .unwrap_err();
+        assert!(rejection.message().contains("already used by event_id evt_login"));
+
+        let rejection = admit_event(&conn, &attested("evt_forged", "made-up"), &rules(), live)
+            .unwrap_err();
+        assert_eq!(rejection.status(), StatusCode::FORBIDDEN);
+
+        let mut unbound = attested("evt_unbound", &challenge);
+        if let Some(attestation) = unbound.attestation.as_mut() {
+            attestation.challenge = None;
+        }
+        sign(&signer, &mut unbound);
+        let rejection = admit_event(&conn, &unbound, &rules(), live).unwrap_err();
+        assert_eq!(rejection.status(), StatusCode::FORBIDDEN);
+        let opted_out = AdmissionRules {
+            require_attestation_challenge: false,
+            ..rules()
+        };
+        admit_event(&conn, &unbound, &opted_out, live).expect("challenge not required");
+        let strict = rules();
+
+        let mut providerless = unbound.clone();
+        providerless.event_id = "evt_providerless".to_string();
+        if let Some(attestation) = providerless.attestation.as_mut() {
+            attestation.provider = AttestationProviderDto::None;
+        }
+        sign(&signer, &mut providerless);
+        let rejection = admit_event(&conn, &providerless, &strict, live).unwrap_err();
+        assert_eq!(rejection.status(), StatusCode::FORBIDDEN);
+        if let Some(attestation) = providerless.attestation.as_mut() {
+            attestation.result = AttestationStatusDto::Unknown;
+        }
+        sign(&signer, &mut providerless);
+        admit_event(&conn, &providerless, &strict, live).expect("unknown claims need no challenge");
+    }
+
+    #[test]
+    fn risk_score_decays_stored_events_of_the_session() {
+        let conn = init_db(":memory:").expect("db init");
+        let samples = [
+            ("evt_old", "sess_1", true, "2026-02-06T19:05:00Z"),
+            ("evt_new", "sess_1", false, "2026-02-06T21:00:00Z"),
+            ("evt_other", "sess_2", true, "2026-02-06T21:00:00Z"),
+        ];
+        for (event_id, session_id, hooked, timestamp) in samples {
+            let mut dto = event("app.test");
+            dto.event_id = event_id.to_string();
+            dto.session = Some(SessionInfoDto {
+                session_id: session_id.to_string(),
+                user_id_hash: None,
+            });
+            dto.signals.hooking = hooked;
+            dto.timestamp = timestamp.to_string();
+            insert_event(&conn, &dto, "2026-02-06T21:00:00Z").expect("insert");
+        }
+
+        let request = RiskScoreRequestDto {
+            app_id: "app.test".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "prod".to_string(),
+            session_id: Some("sess_1".to_string()),
+            user_id_hash: None,
+        };
+        let since = now() - Duration::days(1);
+        let observations = load_risk_observations(&conn, &request, since, now()).expect("load");
+        assert_eq!(observations.len(), 2);
+
+        let weights = RiskWeights {
+            decay_half_life_secs: Some(3600),
+            ..RiskWeights::default()
+        };
+        // Hooking (30 points) seen two hours ago has decayed to a quarter.
+        assert_eq!(weights.score_history(&observations, &[]).value(), 8);
+        assert_eq!(RiskWeights::default().score_history(&observations, &[]).value(), 30);
+    }
+
+    #[test]
+    fn risk_score_uses_the_signed_policy_registered_for_the_app_version_and_env() {
+        use rust_core::adapters::serialization::{
+            DecisionDto, FindingDto, PolicyDto, PolicyRuleDto, RiskWeightsDto, SeverityDto,
+        };
+
+        let conn = init_db(":memory:").expect("db init");
+        for (event_id, version, env) in [
+            ("evt_prod", "1.0.0", "prod"),
+            ("evt_staging", "1.0.0", "staging"),
+            ("evt_old_version", "0.9.0", "prod"),
+        ] {
+            let mut dto = event("app.test");
+            dto.event_id = event_id.to_string();
+            dto.app_version = version.to_string();
+            dto.env = env.to_string();
+            dto.session = Some(SessionInfoDto {
+                session_id: "sess_1".to_string(),
+                user_id_hash: None,
+            });
+            dto.signals.debugger = true;
+            dto.timestamp = "2026-02-06T21:00:00Z".to_string();
+            insert_event(&conn, &dto, "2026-02-06T21:00:00Z").expect("insert");
+        }
+
+        let policy_signer = Ed25519Signer::generate("policy-service").expect("signer");
+        let verifier = Ed25519Verifier::new(vec![policy_signer.public_key()]);
+        let mut policy = PolicyDto {
+            policy_id: "pol_1".to_string(),
+            app_id: "app.test".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "prod".to_string(),
+            rules: vec![PolicyRuleDto {
+                action: "transfer".to_string(),
+                decision: DecisionDto::Allow,
+                conditions: None,
+            }],
+            default_decision: None,
+            action_defaults: None,
+            risk_weights: Some(RiskWeightsDto {
+                debugger: Some(50),
+                ..RiskWeightsDto::default()
+            }),
+            overrides: None,
+            signature: String::new(),
+            issued_at: "2026-02-06T21:00:00Z".to_string(),
+        };
+        policy.signature = policy_signer.sign(&policy.signing_payload()).expect("sign");
+        let profile = AppRiskProfileDto {
+            policy,
+            findings: Some(vec![FindingDto {
+                category: "storage".to_string(),
+                severity: SeverityDto::High,
+                evidence: None,
+            }]),
+        };
+
+        let unconfigured = verify_risk_profile(&Ed25519Verifier::default(), &profile).unwrap_err();
+        assert_eq!(unconfigured.0, StatusCode::SERVICE_UNAVAILABLE);
+        let mut tampered = profile.clone();
+        tampered.policy.risk_weights = Some(RiskWeightsDto {
+            debugger: Some(0),
+            ..RiskWeightsDto::default()
+        });
+        let rejection = verify_risk_profile(&verifier, &tampered).unwrap_err();
+        assert_eq!(rejection.0, StatusCode::BAD_REQUEST);
+        verify_risk_profile(&verifier, &profile).expect("signed policy");
+        store_risk_profile(&conn, &profile).expect("store profile");
+
+        let request = RiskScoreRequestDto {
+            app_id: "app.test".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "prod".to_string(),
+            session_id: Some("sess_1".to_string()),
+            user_id_hash: None,
+        };
+        let observations =
+            load_risk_observations(&conn, &request, now() - Duration::days(1), now()).expect("load");
+        assert_eq!(observations.len(), 1);
+
+        let (weights, findings) = load_risk_profile(&conn, "app.test", "1.0.0", "prod").expect("profile");
+        assert_eq!(weights.debugger, 50);
+        assert_eq!(findings.len(), 1);
+        // Debugger (50 points) plus a high finding (20 points).
+        assert_eq!(weights.score_history(&observations, &findings).value(), 70);
+
+        let (weights, findings) = load_risk_profile(&conn, "app.test", "2.0.0", "prod").expect("profile");
+        assert_eq!(weights, RiskWeights::default());
+        assert!(findings.is_empty());
+    }
+
+    fn query() -> EventQuery {
+        EventQuery {
+            app_id: None,
+            env: None,
+            app_version: None,
+            platform: None,
+            action: None,
+            jailbreak: None,
+            root: None,
+            debugger: None,
+            hooking: None,
+            proxy_detected: None,
+            attestation: None,
+            from: None,
+            to: None,
+            session_id: None,
+            user_id_hash: None,
+            cursor: None,
+            limit: None,
+        }
+    }
+
+    #[test]
+    fn lists_events_by_indexed_filters_with_cursor() {
+        let conn = init_db(":memory:").expect("db init");
+        for (index, hooked) in [false, true, true, true].into_iter().enumerate() {
+            let mut dto = event("app.test");
+            dto.event_id = format!("evt_{}", index);
+            dto.signals.hooking = hooked;
+            dto.timestamp = format!("2026-02-06T21:0{}:00-03:00", index);
+            insert_event(&conn, &dto, "2026-02-07T00:10:00Z").expect("insert");
+        }
+
+        let mut hooked = query();
+        hooked.hooking = Some(true);
+        hooked.from = Some("2026-02-07T00:01:00Z".to_string());
+        hooked.limit = Some(2);
+        let filter = EventFilter::from_query(&hooked).expect("filter");
+        let page = fetch_events(&conn, &filter).expect("page");
+        let ids = page
+            .events
+            .iter()
+            .map(|record| record.event.event_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["evt_3", "evt_2"]);
+
+        hooked.cursor = page.next_cursor;
+        let filter = EventFilter::from_query(&hooked).expect("filter");
+        let page = fetch_events(&conn, &filter).expect("page");
+        assert_eq!(page.events.len(), 1);
+        assert_eq!(page.events[0].event.event_id, "evt_1");
+        assert!(page.next_cursor.is_none());
+
+        let mut invalid = query();
+        invalid.to = Some("yesterday".to_string());
+        assert!(EventFilter::from_query(&invalid).is_err());
+    }
+
+    #[test]
+    fn stats_count_signals_per_bucket_and_dimension() {
+        let conn = init_db(":memory:").expect("db init");
+        let samples = [
+            ("1.0.0", "login", true, "2026-02-06T10:00:00Z"),
+            ("1.0.0", "login", false, "2026-02-06T11:30:00Z"),
+            ("1.0.0", "transfer", true, "2026-02-06T12:00:00Z"),
+            ("1.1.0", "login", true, "2026-02-07T09:00:00Z"),
+        ];
+        for (index, (version, action, debugger, timestamp)) in samples.into_iter().enumerate() {
+            let mut dto = event("app.test");
+            dto.event_id = format!("evt_{}", index);
+            dto.app_version = version.to_string();
+            dto.action.name = action.to_string();
+            dto.signals.debugger = debugger;
+            dto.timestamp = timestamp.to_string();
+            insert_event(&conn, &dto, "2026-02-07T10:00:00Z").expect("insert");
+        }
+
+        let mut logins = query();
+        logins.action = Some("login".to_string());
+        let filter = EventFilter::from_query(&logins).expect("filter");
+        let group_by = parse_dimensions(Some("app_version")).expect("dimensions");
+        let buckets = fetch_stats(&conn, &filter, StatsBucketSize::Day, &group_by).expect("stats");
+
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].start, "2026-02-06T00:00:00Z");
+        assert_eq!(buckets[0].group["app_version"].as_deref(), Some("1.0.0"));
+        assert_eq!(buckets[0].total, 2);
+        assert_eq!(buckets[0].signals.debugger, 1);
+        assert_eq!(buckets[0].attestation.missing, 2);
+        assert_eq!(buckets[1].start, "2026-02-07T00:00:00Z");
+        assert_eq!(buckets[1].signals.debugger, 1);
+
+        let hourly = fetch_stats(&conn, &filter, StatsBucketSize::Hour, &[]).expect("stats");
+        assert_eq!(hourly.len(), 3);
+        assert_eq!(hourly[0].start, "2026-02-06T10:00:00Z");
+        assert!(parse_dimensions(Some("payload")).is_err());
+    }
+
+    #[test]
+    fn migration_backfills_columns_of_existing_events() {
+        let conn = Connection::open_in_memory().expect("db");
+        conn.execute(
+            "CREATE TABLE events (event_id TEXT PRIMARY KEY, payload TEXT NOT NULL, received_at TEXT NOT NULL)",
+            [],
+        )
+        .expect("legacy table");
+        let legacy = event("app.legacy");
+        conn.execute(
+            "INSERT INTO events (event_id, payload, received_at) VALUES (?1, ?2, ?3)",
+            params![
+                legacy.event_id,
+                serde_json::to_string(&legacy).expect("json"),
+                "2026-02-06T21:00:00Z"
+            ],
+        )
+        .expect("legacy row");
+
+        migrate_event_columns(&conn).expect("migrate");
+
+        let mut by_app = query();
+        by_app.app_id = Some("app.legacy".to_string());
+        by_app.platform = Some("ios".to_string());
+        let filter = EventFilter::from_query(&by_app).expect("filter");
+        let page = fetch_events(&conn, &filter).expect("page");
+        assert_eq!(page.events.len(), 1);
+        assert_eq!(page.events[0].event, legacy);
+    }
+}
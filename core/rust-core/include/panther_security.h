@@ -20,13 +20,20 @@ typedef struct {
     uint8_t proxy_detected;
 } FfiIntegritySignals;
 
-typedef struct {
+typedef struct FfiPolicyConditions {
     int32_t attestation_status;
+    int32_t jailbreak;
+    int32_t root;
     int32_t debugger;
     int32_t hooking;
     int32_t proxy_detected;
     FfiStr app_version;
     uint32_t risk_score_gte;
+    const struct FfiPolicyConditions *all_of_ptr;
+    size_t all_of_len;
+    const struct FfiPolicyConditions *any_of_ptr;
+    size_t any_of_len;
+    const struct FfiPolicyConditions *not_ptr;
 } FfiPolicyConditions;
 
 typedef struct {
@@ -35,6 +42,45 @@ typedef struct {
     FfiPolicyConditions conditions;
 } FfiPolicyRule;
 
+typedef struct {
+    FfiStr action;
+    uint32_t decision;
+} FfiActionDefault;
+
+typedef struct {
+    const FfiStr *ptr;
+    size_t len;
+} FfiStrArray;
+
+typedef struct {
+    FfiStrArray actions; /* empty applies the override to every action */
+    FfiStrArray except_actions;
+    uint32_t decision;
+    FfiStr expires_at; /* RFC 3339 */
+} FfiPolicyOverride;
+
+typedef struct {
+    FfiStr category;
+    uint32_t pct; /* percentage applied to the finding's severity points */
+} FfiFindingCategoryWeight;
+
+/* UINT32_MAX keeps the default weight. */
+typedef struct {
+    uint32_t jailbreak;
+    uint32_t root;
+    uint32_t debugger;
+    uint32_t hooking;
+    uint32_t proxy_detected;
+    uint32_t attestation_fail;
+    uint32_t attestation_unknown;
+    uint32_t finding_low;
+    uint32_t finding_medium;
+    uint32_t finding_high;
+    uint32_t finding_critical;
+    const FfiFindingCategoryWeight *finding_categories_ptr;
+    size_t finding_categories_len;
+} FfiRiskWeights;
+
 typedef struct {
     FfiStr policy_id;
     FfiStr app_id;
@@ -42,12 +88,53 @@ typedef struct {
     FfiStr env;
     const FfiPolicyRule *rules_ptr;
     size_t rules_len;
+    uint32_t default_decision; /* used when no rule matches; 0 = ALLOW */
+    const FfiActionDefault *action_defaults_ptr;
+    size_t action_defaults_len;
+    const FfiPolicyOverride *overrides_ptr;
+    size_t overrides_len;
+    const FfiRiskWeights *risk_weights; /* NULL scores with the default weights */
 } FfiPolicySet;
 
+#define PS_CONDITION_ATTESTATION (1u << 0)
+#define PS_CONDITION_JAILBREAK (1u << 1)
+#define PS_CONDITION_ROOT (1u << 2)
+#define PS_CONDITION_DEBUGGER (1u << 3)
+#define PS_CONDITION_HOOKING (1u << 4)
+#define PS_CONDITION_PROXY_DETECTED (1u << 5)
+#define PS_CONDITION_APP_VERSION (1u << 6)
+#define PS_CONDITION_RISK_SCORE (1u << 7)
+
 typedef struct {
-    const FfiStr *ptr;
-    size_t len;
-} FfiStrArray;
+    uint32_t decision;
+    int64_t rule_index;          /* -1 when the default decision applied */
+    uint32_t matched_conditions; /* bitmask of PS_CONDITION_* */
+    uint32_t risk_score;
+    uint8_t default_applied;
+    int64_t override_index;      /* -1 when no override forced the decision */
+} FfiEvaluation;
+
+#define PS_RISK_FACTOR_JAILBREAK 0u
+#define PS_RISK_FACTOR_ROOT 1u
+#define PS_RISK_FACTOR_DEBUGGER 2u
+#define PS_RISK_FACTOR_HOOKING 3u
+#define PS_RISK_FACTOR_PROXY_DETECTED 4u
+#define PS_RISK_FACTOR_ATTESTATION_FAIL 5u
+#define PS_RISK_FACTOR_ATTESTATION_UNKNOWN 6u
+#define PS_RISK_FACTOR_FINDING 7u
+
+typedef struct {
+    FfiStr category;
+    uint32_t severity; /* 0 = low, 1 = medium, 2 = high, 3 = critical */
+} FfiFinding;
+
+typedef struct {
+    uint32_t factor;       /* PS_RISK_FACTOR_* */
+    int64_t finding_index; /* index into the findings passed in, -1 otherwise */
+    uint32_t weight;
+    uint32_t points;
+    uint8_t capped;
+} FfiRiskContribution;
 
 typedef struct {
     FfiStrArray current;
@@ -75,6 +162,30 @@ uint32_t ps_evaluate_policy(
     uint32_t risk_score
 );
 
+/* Returns 0 on success, -1 on invalid input (out is then a DENY naming no rule). */
+int32_t ps_explain_policy(
+    const FfiPolicySet *policy,
+    FfiStr action,
+    FfiIntegritySignals signals,
+    int32_t attestation_status,
+    uint32_t risk_score,
+    FfiEvaluation *out
+);
+
+/* Scores with the risk weights of policy (the default weights when policy is NULL or
+   carries none). Returns the score (0-100), or -1 on invalid input or when out has
+   fewer than *out_len entries (6 + findings_len is always enough). */
+int32_t ps_risk_breakdown(
+    const FfiPolicySet *policy,
+    FfiIntegritySignals signals,
+    int32_t attestation_status,
+    const FfiFinding *findings_ptr,
+    size_t findings_len,
+    FfiRiskContribution *out,
+    size_t out_capacity,
+    size_t *out_len
+);
+
 uint8_t ps_pinning_is_allowed(FfiPinset pinset, FfiStr presented_hash);
 
 #ifdef __cplusplus
@@ -0,0 +1,195 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand_core::OsRng;
+
+use crate::ports::{CryptoSigner, PortError, SignatureVerifier};
+
+/// Signatures are encoded as `ed25519:<key_id>:<base64 signature>` so the
+/// verifier can pick the right public key without extra metadata.
+const ED25519_SCHEME: &str = "ed25519";
+
+#[derive(Clone)]
+pub struct Ed25519Signer {
+    key_id: String,
+    key: SigningKey,
+}
+
+impl Ed25519Signer {
+    pub fn generate(key_id: &str) -> Result<Self, PortError> {
+        validate_key_id(key_id)?;
+        Ok(Self {
+            key_id: key_id.to_string(),
+            key: SigningKey::generate(&mut OsRng),
+        })
+    }
+
+    pub fn from_base64_seed(key_id: &str, seed: &str) -> Result<Self, PortError> {
+        validate_key_id(key_id)?;
+        let bytes = decode_key_bytes(seed, "signing key")?;
+        Ok(Self {
+            key_id: key_id.to_string(),
+            key: SigningKey::from_bytes(&bytes),
+        })
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub fn seed_base64(&self) -> String {
+        STANDARD.encode(self.key.to_bytes())
+    }
+
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        Ed25519PublicKey {
+            key_id: self.key_id.clone(),
+            key: self.key.verifying_key(),
+        }
+    }
+}
+
+impl CryptoSigner for Ed25519Signer {
+    fn sign(&self, payload: &[u8]) -> Result<String, PortError> {
+        let signature = self.key.sign(payload);
+        Ok(format!(
+            "{}:{}:{}",
+            ED25519_SCHEME,
+            self.key_id,
+            STANDARD.encode(signature.to_bytes())
+        ))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ed25519PublicKey {
+    key_id: String,
+    key: VerifyingKey,
+}
+
+impl Ed25519PublicKey {
+    pub fn from_base64(key_id: &str, value: &str) -> Result<Self, PortError> {
+        validate_key_id(key_id)?;
+        let bytes = decode_key_bytes(value, "public key")?;
+        let key = VerifyingKey::from_bytes(&bytes)
+            .map_err(|_| PortError::new("public key is not a valid ed25519 point"))?;
+        Ok(Self {
+            key_id: key_id.to_string(),
+            key,
+        })
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.key.to_bytes())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Ed25519Verifier {
+    keys: Vec<Ed25519PublicKey>,
+}
+
+impl Ed25519Verifier {
+    pub fn new(keys: Vec<Ed25519PublicKey>) -> Self {
+        Self { keys }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+impl SignatureVerifier for Ed25519Verifier {
+    fn verify(&self, payload: &[u8], signature: &str) -> Result<(), PortError> {
+        let (key_id, signature) = parse_signature(signature)?;
+        let key = self
+            .keys
+            .iter()
+            .find(|candidate| candidate.key_id == key_id)
+            .ok_or_else(|| PortError::new(format!("unknown signing key: {}", key_id)))?;
+
+        key.key
+            .verify(payload, &signature)
+            .map_err(|_| PortError::new("signature verification failed"))
+    }
+}
+
+fn parse_signature(value: &str) -> Result<(&str, Signature), PortError> {
+    let mut parts = value.splitn(3, ':');
+    let (scheme, key_id, encoded) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(scheme), Some(key_id), Some(encoded)) => (scheme, key_id, encoded),
+        _ => return Err(PortError::new("malformed signature")),
+    };
+
+    if scheme != ED25519_SCHEME {
+        return Err(PortError::new(format!("unsupported signature scheme: {}", scheme)));
+    }
+
+    let bytes = STANDARD
+        .decode(encoded)
+        .map_err(|_| PortError::new("signature is not valid base64"))?;
+    let signature = Signature::from_slice(&bytes)
+        .map_err(|_| PortError::new("signature has an invalid length"))?;
+
+    Ok((key_id, signature))
+}
+
+fn decode_key_bytes(value: &str, label: &str) -> Result<[u8; 32], PortError> {
+    let bytes = STANDARD
+        .decode(value.trim())
+        .map_err(|_| PortError::new(format!("{} is not valid base64", label)))?;
+    bytes
+        .try_into()
+        .map_err(|_| PortError::new(format!("{} must be 32 bytes", label)))
+}
+
+fn validate_key_id(key_id: &str) -> Result<(), PortError> {
+    if key_id.trim().is_empty() || key_id.contains(':') {
+        return Err(PortError::new("key_id must be non-empty and must not contain ':'"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_round_trips_through_verifier() {
+        let signer = Ed25519Signer::generate("device-1").expect("signer");
+        let verifier = Ed25519Verifier::new(vec![signer.public_key()]);
+
+        let signature = signer.sign(b"payload").expect("sign");
+        assert!(signature.starts_with("ed25519:device-1:"));
+        assert!(verifier.verify(b"payload", &signature).is_ok());
+        assert!(verifier.verify(b"tampered", &signature).is_err());
+    }
+
+    #[test]
+    fn verifier_rejects_unknown_key_ids() {
+        let signer = Ed25519Signer::generate("device-1").expect("signer");
+        let other = Ed25519Signer::generate("device-2").expect("signer");
+        let verifier = Ed25519Verifier::new(vec![other.public_key()]);
+
+        let signature = signer.sign(b"payload").expect("sign");
+        let err = verifier.verify(b"payload", &signature).unwrap_err();
+        assert_eq!(err.message, "unknown signing key: device-1");
+    }
+
+    #[test]
+    fn signer_restores_from_exported_seed() {
+        let signer = Ed25519Signer::generate("device-1").expect("signer");
+        let restored = Ed25519Signer::from_base64_seed("device-1", &signer.seed_base64())
+            .expect("restore");
+        let public_key = Ed25519PublicKey::from_base64("device-1", &signer.public_key().to_base64())
+            .expect("public key");
+
+        let signature = restored.sign(b"payload").expect("sign");
+        assert!(Ed25519Verifier::new(vec![public_key])
+            .verify(b"payload", &signature)
+            .is_ok());
+    }
+}
@@ -1,6 +1,11 @@
+use std::collections::BTreeMap;
+
 use crate::domain::pinning::SpkiPinset;
-use crate::domain::policy::{Decision, PolicyConditions, PolicyRule, PolicySet, PolicyEngine};
-use crate::domain::risk::RiskScore;
+use crate::domain::policy::{
+    ConditionKind, Decision, Evaluation, PolicyConditions, PolicyDefaults, PolicyEngine,
+    PolicyOverride, PolicyRule, PolicySet, MAX_CONDITION_DEPTH,
+};
+use crate::domain::risk::{Finding, RiskContribution, RiskFactor, RiskScore, RiskWeights, Severity};
 use crate::domain::telemetry::{ActionContext, AttestationResult, AttestationStatus, IntegritySignals};
 use chrono::{DateTime, Utc};
 
@@ -9,6 +14,24 @@ const FFI_DECISION_STEP_UP: u32 = 1;
 const FFI_DECISION_DEGRADE: u32 = 2;
 const FFI_DECISION_DENY: u32 = 3;
 
+const FFI_CONDITION_ATTESTATION: u32 = 1 << 0;
+const FFI_CONDITION_JAILBREAK: u32 = 1 << 1;
+const FFI_CONDITION_ROOT: u32 = 1 << 2;
+const FFI_CONDITION_DEBUGGER: u32 = 1 << 3;
+const FFI_CONDITION_HOOKING: u32 = 1 << 4;
+const FFI_CONDITION_PROXY_DETECTED: u32 = 1 << 5;
+const FFI_CONDITION_APP_VERSION: u32 = 1 << 6;
+const FFI_CONDITION_RISK_SCORE: u32 = 1 << 7;
+
+const FFI_RISK_FACTOR_JAILBREAK: u32 = 0;
+const FFI_RISK_FACTOR_ROOT: u32 = 1;
+const FFI_RISK_FACTOR_DEBUGGER: u32 = 2;
+const FFI_RISK_FACTOR_HOOKING: u32 = 3;
+const FFI_RISK_FACTOR_PROXY_DETECTED: u32 = 4;
+const FFI_RISK_FACTOR_ATTESTATION_FAIL: u32 = 5;
+const FFI_RISK_FACTOR_ATTESTATION_UNKNOWN: u32 = 6;
+const FFI_RISK_FACTOR_FINDING: u32 = 7;
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
 pub struct FfiStr {
@@ -30,11 +53,19 @@ pub struct FfiIntegritySignals {
 #[derive(Copy, Clone, Debug)]
 pub struct FfiPolicyConditions {
     pub attestation_status: i32,
+    pub jailbreak: i32,
+    pub root: i32,
     pub debugger: i32,
     pub hooking: i32,
     pub proxy_detected: i32,
     pub app_version: FfiStr,
     pub risk_score_gte: u32,
+    pub all_of_ptr: *const FfiPolicyConditions,
+    pub all_of_len: usize,
+    pub any_of_ptr: *const FfiPolicyConditions,
+    pub any_of_len: usize,
+    /// Null when the rule has no negated group.
+    pub not_ptr: *const FfiPolicyConditions,
 }
 
 #[repr(C)]
@@ -54,6 +85,97 @@ pub struct FfiPolicySet {
     pub env: FfiStr,
     pub rules_ptr: *const FfiPolicyRule,
     pub rules_len: usize,
+    /// Decision when no rule matches (an `FFI_DECISION_*` value).
+    pub default_decision: u32,
+    pub action_defaults_ptr: *const FfiActionDefault,
+    pub action_defaults_len: usize,
+    pub overrides_ptr: *const FfiPolicyOverride,
+    pub overrides_len: usize,
+    /// Null leaves scoring to the default weights.
+    pub risk_weights: *const FfiRiskWeights,
+}
+
+/// Risk weights carried by a policy; see `RiskWeights`. `u32::MAX` keeps the
+/// default weight, as for an omitted `risk_weights` field. Decay does not apply
+/// to a single breakdown, so it is not passed.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct FfiRiskWeights {
+    pub jailbreak: u32,
+    pub root: u32,
+    pub debugger: u32,
+    pub hooking: u32,
+    pub proxy_detected: u32,
+    pub attestation_fail: u32,
+    pub attestation_unknown: u32,
+    pub finding_low: u32,
+    pub finding_medium: u32,
+    pub finding_high: u32,
+    pub finding_critical: u32,
+    pub finding_categories_ptr: *const FfiFindingCategoryWeight,
+    pub finding_categories_len: usize,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct FfiFindingCategoryWeight {
+    pub category: FfiStr,
+    /// Percentage applied to the finding's severity points.
+    pub pct: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct FfiActionDefault {
+    pub action: FfiStr,
+    pub decision: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct FfiPolicyOverride {
+    /// Empty applies the override to every action.
+    pub actions: FfiStrArray,
+    pub except_actions: FfiStrArray,
+    pub decision: u32,
+    /// RFC 3339 expiry.
+    pub expires_at: FfiStr,
+}
+
+/// Explanation filled by `ps_explain_policy`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct FfiEvaluation {
+    pub decision: u32,
+    /// Index of the rule that fired, or -1 when the default applied.
+    pub rule_index: i64,
+    /// Bitmask of `FFI_CONDITION_*` values that matched.
+    pub matched_conditions: u32,
+    pub risk_score: u32,
+    pub default_applied: u8,
+    /// Index of the override that forced the decision, or -1 when none did.
+    pub override_index: i64,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct FfiFinding {
+    pub category: FfiStr,
+    /// 0 = low, 1 = medium, 2 = high, 3 = critical.
+    pub severity: u32,
+}
+
+/// One contributing factor filled by `ps_risk_breakdown`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct FfiRiskContribution {
+    /// An `FFI_RISK_FACTOR_*` value.
+    pub factor: u32,
+    /// Index into the findings passed in, or -1 for signals and attestation.
+    pub finding_index: i64,
+    pub weight: u32,
+    pub points: u32,
+    pub capped: u8,
 }
 
 #[repr(C)]
@@ -72,6 +194,50 @@ pub struct FfiPinset {
     pub rotation_window_days: i32,
 }
 
+fn decision_to_ffi(decision: &Decision) -> u32 {
+    match decision {
+        Decision::Allow => FFI_DECISION_ALLOW,
+        Decision::StepUp => FFI_DECISION_STEP_UP,
+        Decision::Degrade => FFI_DECISION_DEGRADE,
+        Decision::Deny => FFI_DECISION_DENY,
+    }
+}
+
+fn condition_bit(kind: ConditionKind) -> u32 {
+    match kind {
+        ConditionKind::Attestation => FFI_CONDITION_ATTESTATION,
+        ConditionKind::Jailbreak => FFI_CONDITION_JAILBREAK,
+        ConditionKind::Root => FFI_CONDITION_ROOT,
+        ConditionKind::Debugger => FFI_CONDITION_DEBUGGER,
+        ConditionKind::Hooking => FFI_CONDITION_HOOKING,
+        ConditionKind::ProxyDetected => FFI_CONDITION_PROXY_DETECTED,
+        ConditionKind::AppVersion => FFI_CONDITION_APP_VERSION,
+        ConditionKind::RiskScore => FFI_CONDITION_RISK_SCORE,
+    }
+}
+
+impl From<&Evaluation> for FfiEvaluation {
+    fn from(evaluation: &Evaluation) -> Self {
+        Self {
+            decision: decision_to_ffi(&evaluation.decision),
+            rule_index: evaluation
+                .rule_index
+                .map(|index| index as i64)
+                .unwrap_or(-1),
+            matched_conditions: evaluation
+                .matched_conditions
+                .iter()
+                .fold(0, |bits, kind| bits | condition_bit(*kind)),
+            risk_score: evaluation.risk_score.value(),
+            default_applied: u8::from(evaluation.default_applied),
+            override_index: evaluation
+                .override_index
+                .map(|index| index as i64)
+                .unwrap_or(-1),
+        }
+    }
+}
+
 fn parse_decision(value: u32) -> Result<Decision, ()> {
     match value {
         FFI_DECISION_ALLOW => Ok(Decision::Allow),
@@ -82,6 +248,135 @@ fn parse_decision(value: u32) -> Result<Decision, ()> {
     }
 }
 
+fn parse_policy_defaults(policy: &FfiPolicySet) -> Result<PolicyDefaults, ()> {
+    let decision = parse_decision(policy.default_decision)?;
+    if policy.action_defaults_len == 0 {
+        return Ok(PolicyDefaults {
+            decision,
+            actions: BTreeMap::new(),
+        });
+    }
+    if policy.action_defaults_ptr.is_null() {
+        return Err(());
+    }
+
+    let slice = unsafe {
+        std::slice::from_raw_parts(policy.action_defaults_ptr, policy.action_defaults_len)
+    };
+    let mut actions = BTreeMap::new();
+    for entry in slice {
+        let action = str_from_ffi(entry.action)?.ok_or(())?;
+        actions.insert(action, parse_decision(entry.decision)?);
+    }
+
+    Ok(PolicyDefaults { decision, actions })
+}
+
+fn parse_policy_overrides(policy: &FfiPolicySet) -> Result<Vec<PolicyOverride>, ()> {
+    if policy.overrides_len == 0 {
+        return Ok(Vec::new());
+    }
+    if policy.overrides_ptr.is_null() {
+        return Err(());
+    }
+
+    let slice = unsafe { std::slice::from_raw_parts(policy.overrides_ptr, policy.overrides_len) };
+    slice
+        .iter()
+        .map(|entry| {
+            Ok(PolicyOverride {
+                actions: str_array_from_ffi(entry.actions)?,
+                except_actions: str_array_from_ffi(entry.except_actions)?,
+                decision: parse_decision(entry.decision)?,
+                expires_at: str_from_ffi(entry.expires_at)?.ok_or(())?,
+                reason: None,
+            })
+        })
+        .collect()
+}
+
+fn parse_severity(value: u32) -> Result<Severity, ()> {
+    match value {
+        0 => Ok(Severity::Low),
+        1 => Ok(Severity::Medium),
+        2 => Ok(Severity::High),
+        3 => Ok(Severity::Critical),
+        _ => Err(()),
+    }
+}
+
+fn parse_policy_risk_weights(policy: &FfiPolicySet) -> Result<Option<RiskWeights>, ()> {
+    if policy.risk_weights.is_null() {
+        return Ok(None);
+    }
+
+    let weights = unsafe { &*policy.risk_weights };
+    let mut finding_category_pct = BTreeMap::new();
+    if weights.finding_categories_len > 0 {
+        if weights.finding_categories_ptr.is_null() {
+            return Err(());
+        }
+        let slice = unsafe {
+            std::slice::from_raw_parts(weights.finding_categories_ptr, weights.finding_categories_len)
+        };
+        for entry in slice {
+            finding_category_pct.insert(str_from_ffi(entry.category)?.ok_or(())?, entry.pct);
+        }
+    }
+
+    let defaults = RiskWeights::default();
+    let pick = |value: u32, default: u32| if value == u32::MAX { default } else { value };
+    Ok(Some(RiskWeights {
+        jailbreak: pick(weights.jailbreak, defaults.jailbreak),
+        root: pick(weights.root, defaults.root),
+        debugger: pick(weights.debugger, defaults.debugger),
+        hooking: pick(weights.hooking, defaults.hooking),
+        proxy_detected: pick(weights.proxy_detected, defaults.proxy_detected),
+        attestation_fail: pick(weights.attestation_fail, defaults.attestation_fail),
+        attestation_unknown: pick(weights.attestation_unknown, defaults.attestation_unknown),
+        finding_low: pick(weights.finding_low, defaults.finding_low),
+        finding_medium: pick(weights.finding_medium, defaults.finding_medium),
+        finding_high: pick(weights.finding_high, defaults.finding_high),
+        finding_critical: pick(weights.finding_critical, defaults.finding_critical),
+        finding_category_pct,
+        decay_half_life_secs: None,
+    }))
+}
+
+fn parse_findings(ptr: *const FfiFinding, len: usize) -> Result<Vec<Finding>, ()> {
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+
+    if ptr.is_null() {
+        return Err(());
+    }
+
+    let slice = unsafe { std::slice::from_raw_parts(ptr, len) };
+    slice
+        .iter()
+        .map(|finding| {
+            Ok(Finding {
+                category: str_from_ffi(finding.category)?.ok_or(())?,
+                severity: parse_severity(finding.severity)?,
+            })
+        })
+        .collect()
+}
+
+fn risk_factor_to_ffi(factor: &RiskFactor) -> u32 {
+    match factor {
+        RiskFactor::Jailbreak => FFI_RISK_FACTOR_JAILBREAK,
+        RiskFactor::Root => FFI_RISK_FACTOR_ROOT,
+        RiskFactor::Debugger => FFI_RISK_FACTOR_DEBUGGER,
+        RiskFactor::Hooking => FFI_RISK_FACTOR_HOOKING,
+        RiskFactor::ProxyDetected => FFI_RISK_FACTOR_PROXY_DETECTED,
+        RiskFactor::AttestationFail => FFI_RISK_FACTOR_ATTESTATION_FAIL,
+        RiskFactor::AttestationUnknown => FFI_RISK_FACTOR_ATTESTATION_UNKNOWN,
+        RiskFactor::Finding { .. } => FFI_RISK_FACTOR_FINDING,
+    }
+}
+
 fn parse_optional_bool(value: i32) -> Result<Option<bool>, ()> {
     match value {
         -1 => Ok(None),
@@ -145,11 +440,24 @@ fn str_array_from_ffi(array: FfiStrArray) -> Result<Vec<String>, ()> {
 }
 
 fn parse_policy_conditions(conditions: FfiPolicyConditions) -> Result<PolicyConditions, ()> {
+    parse_policy_conditions_at(conditions, 1)
+}
+
+fn parse_policy_conditions_at(
+    conditions: FfiPolicyConditions,
+    depth: usize,
+) -> Result<PolicyConditions, ()> {
+    if depth > MAX_CONDITION_DEPTH {
+        return Err(());
+    }
+
     let required_attestation = parse_optional_attestation(conditions.attestation_status)?;
     let condition_app_version = str_from_ffi(conditions.app_version)?;
 
     Ok(PolicyConditions {
         attestation_status: required_attestation,
+        jailbreak: parse_optional_bool(conditions.jailbreak)?,
+        root: parse_optional_bool(conditions.root)?,
         debugger: parse_optional_bool(conditions.debugger)?,
         hooking: parse_optional_bool(conditions.hooking)?,
         proxy_detected: parse_optional_bool(conditions.proxy_detected)?,
@@ -162,9 +470,37 @@ fn parse_policy_conditions(conditions: FfiPolicyConditions) -> Result<PolicyCond
         } else {
             Some(conditions.risk_score_gte)
         },
+        all_of: parse_condition_groups(conditions.all_of_ptr, conditions.all_of_len, depth)?,
+        any_of: parse_condition_groups(conditions.any_of_ptr, conditions.any_of_len, depth)?,
+        not: if conditions.not_ptr.is_null() {
+            None
+        } else {
+            let negated = unsafe { *conditions.not_ptr };
+            Some(Box::new(parse_policy_conditions_at(negated, depth + 1)?))
+        },
     })
 }
 
+fn parse_condition_groups(
+    ptr: *const FfiPolicyConditions,
+    len: usize,
+    depth: usize,
+) -> Result<Vec<PolicyConditions>, ()> {
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+
+    if ptr.is_null() {
+        return Err(());
+    }
+
+    let slice = unsafe { std::slice::from_raw_parts(ptr, len) };
+    slice
+        .iter()
+        .map(|group| parse_policy_conditions_at(*group, depth + 1))
+        .collect()
+}
+
 fn parse_policy_rule(rule: &FfiPolicyRule) -> Result<PolicyRule, ()> {
     let action = str_from_ffi(rule.action).and_then(|value| value.ok_or(()))?;
     let decision = parse_decision(rule.decision)?;
@@ -230,6 +566,7 @@ pub extern "C" fn ps_evaluate_rule(
             provider: crate::domain::telemetry::AttestationProvider::None,
             status,
             timestamp: None,
+            challenge: None,
         }),
         Ok(None) => None,
         Err(_) => return FFI_DECISION_DENY,
@@ -252,6 +589,9 @@ pub extern "C" fn ps_evaluate_rule(
         app_version: app_version_value.unwrap_or_default(),
         env: "ffi".to_string(),
         rules: vec![rule],
+        defaults: PolicyDefaults::default(),
+        risk_weights: None,
+        overrides: Vec::new(),
     };
 
     let decision = PolicyEngine::evaluate(
@@ -274,6 +614,7 @@ pub extern "C" fn ps_evaluate_rule(
 }
 
 #[unsafe(no_mangle)]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn ps_evaluate_policy(
     policy: *const FfiPolicySet,
     action: FfiStr,
@@ -281,35 +622,85 @@ pub extern "C" fn ps_evaluate_policy(
     attestation_status: i32,
     risk_score: u32,
 ) -> u32 {
+    match explain_ffi_policy(policy, action, signals, attestation_status, risk_score) {
+        Ok(evaluation) => decision_to_ffi(&evaluation.decision),
+        Err(_) => FFI_DECISION_DENY,
+    }
+}
+
+/// Evaluates like `ps_evaluate_policy` and fills `out` with the reason for the
+/// decision. Returns 0 on success; on invalid input returns -1 and, when `out`
+/// is not null, fills it with a `DENY` that names no rule.
+#[unsafe(no_mangle)]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn ps_explain_policy(
+    policy: *const FfiPolicySet,
+    action: FfiStr,
+    signals: FfiIntegritySignals,
+    attestation_status: i32,
+    risk_score: u32,
+    out: *mut FfiEvaluation,
+) -> i32 {
+    if out.is_null() {
+        return -1;
+    }
+
+    let (status, explanation) =
+        match explain_ffi_policy(policy, action, signals, attestation_status, risk_score) {
+            Ok(evaluation) => (0, FfiEvaluation::from(&evaluation)),
+            Err(_) => (
+                -1,
+                FfiEvaluation {
+                    decision: FFI_DECISION_DENY,
+                    rule_index: -1,
+                    matched_conditions: 0,
+                    risk_score: RiskScore::new(risk_score).value(),
+                    default_applied: 0,
+                    override_index: -1,
+                },
+            ),
+        };
+
+    unsafe { out.write(explanation) };
+    status
+}
+
+fn explain_ffi_policy(
+    policy: *const FfiPolicySet,
+    action: FfiStr,
+    signals: FfiIntegritySignals,
+    attestation_status: i32,
+    risk_score: u32,
+) -> Result<Evaluation, ()> {
     if policy.is_null() {
-        return FFI_DECISION_DENY;
+        return Err(());
     }
 
     let action_name = match str_from_ffi(action).and_then(|value| value.ok_or(())) {
         Ok(value) => value,
-        Err(_) => return FFI_DECISION_DENY,
+        Err(_) => return Err(()),
     };
 
     let policy = unsafe { &*policy };
     let policy_id = match str_from_ffi(policy.policy_id) {
         Ok(value) => value.unwrap_or_default(),
-        Err(_) => return FFI_DECISION_DENY,
+        Err(_) => return Err(()),
     };
     let app_id = match str_from_ffi(policy.app_id) {
         Ok(value) => value.unwrap_or_default(),
-        Err(_) => return FFI_DECISION_DENY,
+        Err(_) => return Err(()),
     };
     let app_version = match str_from_ffi(policy.app_version) {
         Ok(value) => value.unwrap_or_default(),
-        Err(_) => return FFI_DECISION_DENY,
+        Err(_) => return Err(()),
     };
     let env = match str_from_ffi(policy.env) {
         Ok(value) => value.unwrap_or_default(),
-        Err(_) => return FFI_DECISION_DENY,
+        Err(_) => return Err(()),
     };
 
     if policy.rules_ptr.is_null() && policy.rules_len > 0 {
-        return FFI_DECISION_DENY;
+        return Err(());
     }
 
     let rules = if policy.rules_len == 0 {
@@ -320,7 +711,7 @@ pub extern "C" fn ps_evaluate_policy(
         for rule in slice {
             match parse_policy_rule(rule) {
                 Ok(value) => parsed.push(value),
-                Err(_) => return FFI_DECISION_DENY,
+                Err(_) => return Err(()),
             }
         }
         parsed
@@ -340,7 +731,7 @@ pub extern "C" fn ps_evaluate_policy(
             hooking,
             proxy_detected,
         },
-        _ => return FFI_DECISION_DENY,
+        _ => return Err(()),
     };
 
     let runtime_attestation = match parse_optional_attestation(attestation_status) {
@@ -348,20 +739,28 @@ pub extern "C" fn ps_evaluate_policy(
             provider: crate::domain::telemetry::AttestationProvider::None,
             status,
             timestamp: None,
+            challenge: None,
         }),
         Ok(None) => None,
-        Err(_) => return FFI_DECISION_DENY,
+        Err(_) => return Err(()),
     };
 
+    let defaults = parse_policy_defaults(policy)?;
+    let overrides = parse_policy_overrides(policy)?;
+    let risk_weights = parse_policy_risk_weights(policy)?;
+
     let policy_set = PolicySet {
         policy_id,
         app_id,
         app_version,
         env,
         rules,
+        defaults,
+        risk_weights,
+        overrides,
     };
 
-    let decision = PolicyEngine::evaluate(
+    Ok(PolicyEngine::explain(
         &policy_set,
         &ActionContext {
             name: action_name,
@@ -370,14 +769,110 @@ pub extern "C" fn ps_evaluate_policy(
         &signals,
         runtime_attestation.as_ref(),
         RiskScore::new(risk_score),
+    ))
+}
+
+/// Scores the signals, attestation and findings with the risk weights of
+/// `policy` (the default weights when `policy` is null or carries none) and
+/// writes one entry per contributing factor to `out` (room for `out_capacity`
+/// entries; `6 + findings_len` is always enough) and their count to `out_len`.
+/// Returns the score (0-100), or -1 on invalid input or when `out` is too
+/// small, in which case `out_len` holds the count needed.
+#[unsafe(no_mangle)]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn ps_risk_breakdown(
+    policy: *const FfiPolicySet,
+    signals: FfiIntegritySignals,
+    attestation_status: i32,
+    findings_ptr: *const FfiFinding,
+    findings_len: usize,
+    out: *mut FfiRiskContribution,
+    out_capacity: usize,
+    out_len: *mut usize,
+) -> i32 {
+    if out_len.is_null() || (out.is_null() && out_capacity > 0) {
+        return -1;
+    }
+
+    let signals = match (
+        parse_bool_flag(signals.jailbreak),
+        parse_bool_flag(signals.root),
+        parse_bool_flag(signals.debugger),
+        parse_bool_flag(signals.hooking),
+        parse_bool_flag(signals.proxy_detected),
+    ) {
+        (Ok(jailbreak), Ok(root), Ok(debugger), Ok(hooking), Ok(proxy_detected)) => IntegritySignals {
+            jailbreak,
+            root,
+            debugger,
+            hooking,
+            proxy_detected,
+        },
+        _ => return -1,
+    };
+
+    let runtime_attestation = match parse_optional_attestation(attestation_status) {
+        Ok(Some(status)) => Some(AttestationResult {
+            provider: crate::domain::telemetry::AttestationProvider::None,
+            status,
+            timestamp: None,
+            challenge: None,
+        }),
+        Ok(None) => None,
+        Err(_) => return -1,
+    };
+
+    let findings = match parse_findings(findings_ptr, findings_len) {
+        Ok(value) => value,
+        Err(_) => return -1,
+    };
+
+    let weights = if policy.is_null() {
+        None
+    } else {
+        match parse_policy_risk_weights(unsafe { &*policy }) {
+            Ok(value) => value,
+            Err(_) => return -1,
+        }
+    };
+    let breakdown = weights.unwrap_or_default().breakdown(
+        &signals,
+        runtime_attestation.as_ref(),
+        &findings,
     );
+    unsafe { out_len.write(breakdown.contributions.len()) };
+    if breakdown.contributions.len() > out_capacity {
+        return -1;
+    }
 
-    match decision {
-        Decision::Allow => FFI_DECISION_ALLOW,
-        Decision::StepUp => FFI_DECISION_STEP_UP,
-        Decision::Degrade => FFI_DECISION_DEGRADE,
-        Decision::Deny => FFI_DECISION_DENY,
+    // Findings are listed in input order after the signals, so the n-th finding
+    // contribution is finding n.
+    let mut next_finding = 0i64;
+    for (index, contribution) in breakdown.contributions.iter().enumerate() {
+        let RiskContribution {
+            factor,
+            weight,
+            points,
+            capped,
+        } = contribution;
+        let finding_index = match factor {
+            RiskFactor::Finding { .. } => {
+                next_finding += 1;
+                next_finding - 1
+            }
+            _ => -1,
+        };
+        let entry = FfiRiskContribution {
+            factor: risk_factor_to_ffi(factor),
+            finding_index,
+            weight: *weight,
+            points: *points,
+            capped: u8::from(*capped),
+        };
+        unsafe { out.add(index).write(entry) };
     }
+
+    breakdown.score.value() as i32
 }
 
 #[unsafe(no_mangle)]
@@ -423,3 +918,95 @@ pub extern "C" fn ps_pinning_is_allowed(pinset: FfiPinset, presented_hash: FfiSt
         0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ffi_str(value: &'static str) -> FfiStr {
+        FfiStr {
+            ptr: value.as_ptr(),
+            len: value.len(),
+        }
+    }
+
+    fn breakdown(policy: *const FfiPolicySet) -> (i32, Vec<FfiRiskContribution>) {
+        let signals = FfiIntegritySignals {
+            jailbreak: 0,
+            root: 0,
+            debugger: 1,
+            hooking: 0,
+            proxy_detected: 0,
+        };
+        let findings = [FfiFinding {
+            category: ffi_str("crypto"),
+            severity: 1,
+        }];
+        let mut out = [FfiRiskContribution {
+            factor: 0,
+            finding_index: -1,
+            weight: 0,
+            points: 0,
+            capped: 0,
+        }; 7];
+        let mut out_len = 0;
+        let score = ps_risk_breakdown(
+            policy,
+            signals,
+            -1,
+            findings.as_ptr(),
+            findings.len(),
+            out.as_mut_ptr(),
+            out.len(),
+            &mut out_len,
+        );
+        (score, out[..out_len].to_vec())
+    }
+
+    #[test]
+    fn risk_breakdown_uses_the_policy_weights_when_present() {
+        let categories = [FfiFindingCategoryWeight {
+            category: ffi_str("crypto"),
+            pct: 200,
+        }];
+        let weights = FfiRiskWeights {
+            jailbreak: u32::MAX,
+            root: u32::MAX,
+            debugger: 50,
+            hooking: u32::MAX,
+            proxy_detected: u32::MAX,
+            attestation_fail: u32::MAX,
+            attestation_unknown: u32::MAX,
+            finding_low: u32::MAX,
+            finding_medium: u32::MAX,
+            finding_high: u32::MAX,
+            finding_critical: u32::MAX,
+            finding_categories_ptr: categories.as_ptr(),
+            finding_categories_len: categories.len(),
+        };
+        let mut policy = FfiPolicySet {
+            policy_id: ffi_str("pol_1"),
+            app_id: ffi_str("fintech.mobile"),
+            app_version: ffi_str("1.0.0"),
+            env: ffi_str("prod"),
+            rules_ptr: std::ptr::null(),
+            rules_len: 0,
+            default_decision: FFI_DECISION_ALLOW,
+            action_defaults_ptr: std::ptr::null(),
+            action_defaults_len: 0,
+            overrides_ptr: std::ptr::null(),
+            overrides_len: 0,
+            risk_weights: &weights,
+        };
+
+        // Debugger at 50 plus a medium crypto finding at 200% of the default 10.
+        let (score, contributions) = breakdown(&policy);
+        assert_eq!(score, 70);
+        assert_eq!(contributions[0].weight, 50);
+        assert_eq!(contributions[1].points, 20);
+
+        policy.risk_weights = std::ptr::null();
+        assert_eq!(breakdown(&policy).0, 40);
+        assert_eq!(breakdown(std::ptr::null()).0, 40);
+    }
+}
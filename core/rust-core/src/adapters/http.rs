@@ -1,17 +1,24 @@
+use reqwest::StatusCode;
 use reqwest::blocking::Client;
 use serde::Serialize;
 
-use crate::domain::policy::PolicySet;
-use crate::domain::telemetry::{Platform, TelemetryAuth, TelemetryEnvelope};
-use crate::ports::{PolicyStore, PortError, TelemetrySink};
+use crate::domain::policy::SignedPolicy;
+use crate::domain::telemetry::{
+    AttestationChallenge, Platform, TelemetryAuth, TelemetryEnvelope,
+};
+use crate::ports::{DeliveryStatus, PolicyStore, PortError, TelemetrySink};
 use crate::adapters::serialization::{
-    validate_telemetry_event, PolicyDto, PolicyUpsertDto, PolicyUpsertResponse, TelemetryEventDto,
+    validate_telemetry_event, AttestationChallengeDto, AttestationChallengeRequestDto, PolicyDto, PolicyUpsertDto, PolicyUpsertResponse,
+    TelemetryBatchResultDto, TelemetryBatchStatusDto, TelemetryEventDto,
 };
 
 #[derive(Clone, Debug)]
 pub struct HttpConfig {
     pub base_url: String,
     pub api_token: Option<String>,
+    /// Sent as `device_id` on policy fetches so staged rollouts keep serving
+    /// this device the same policy version.
+    pub device_id: Option<String>,
 }
 
 #[derive(Clone)]
@@ -34,13 +41,14 @@ impl HttpTelemetryClient {
         app_version: &str,
         env: &str,
         platform: Platform,
-    ) -> Result<PolicySet, PortError> {
+    ) -> Result<SignedPolicy, PortError> {
         #[derive(Serialize)]
         struct PolicyQuery<'a> {
             app_id: &'a str,
             app_version: &'a str,
             env: &'a str,
             device_platform: &'a str,
+            device_id: Option<&'a str>,
         }
 
         let url = format!("{}/v1/policies/current", self.config.base_url.trim_end_matches('/'));
@@ -52,6 +60,7 @@ impl HttpTelemetryClient {
                 Platform::Ios => "ios",
                 Platform::Android => "android",
             },
+            device_id: self.config.device_id.as_deref(),
         };
 
         let mut request = self.client.get(url).query(&query);
@@ -103,6 +112,65 @@ impl HttpTelemetryClient {
             .map_err(|err| PortError::new(err.to_string()))
     }
 
+    /// Asks telemetry-ingestion for a single-use challenge to bind attestation
+    /// evidence for `action` of `session_id` to.
+    pub fn request_attestation_challenge(
+        &self,
+        app_id: &str,
+        session_id: &str,
+        action: &str,
+    ) -> Result<AttestationChallenge, PortError> {
+        let url = format!(
+            "{}/v1/attestation/challenge",
+            self.config.base_url.trim_end_matches('/')
+        );
+        let body = AttestationChallengeRequestDto {
+            app_id: app_id.to_string(),
+            session_id: session_id.to_string(),
+            action: action.to_string(),
+        };
+        let mut request = self.client.post(url).json(&body);
+        if let Some(token) = &self.config.api_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .map_err(|err| PortError::new(err.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(PortError::new(format!(
+                "attestation challenge failed: {}",
+                response.status()
+            )));
+        }
+
+        let challenge = response
+            .json::<AttestationChallengeDto>()
+            .map_err(|err| PortError::new(err.to_string()))?;
+
+        Ok(challenge.into())
+    }
+
+    /// Posts one validated event and returns the response status.
+    fn post_event(
+        &self,
+        event: &TelemetryEventDto,
+        auth: &TelemetryAuth,
+    ) -> Result<StatusCode, PortError> {
+        let url = format!("{}/v1/telemetry/events", self.config.base_url.trim_end_matches('/'));
+        let mut request = self.client.post(url).json(event);
+
+        if let Some(token) = self.auth_token(auth) {
+            request = request.bearer_auth(token);
+        }
+
+        request
+            .send()
+            .map(|response| response.status())
+            .map_err(|err| PortError::new(err.to_string()))
+    }
+
     fn auth_token(&self, auth: &TelemetryAuth) -> Option<String> {
         auth.api_token
             .clone()
@@ -116,10 +184,50 @@ impl TelemetrySink for HttpTelemetryClient {
             .map_err(|err| PortError::new(err.message))?;
         validate_telemetry_event(&event).map_err(|err| PortError::new(err.message))?;
 
-        let url = format!("{}/v1/telemetry/events", self.config.base_url.trim_end_matches('/'));
-        let mut request = self.client.post(url).json(&event);
+        let status = self.post_event(&event, &envelope.auth)?;
+        if !status.is_success() {
+            return Err(PortError::new(format!("telemetry send failed: {}", status)));
+        }
 
-        if let Some(token) = self.auth_token(&envelope.auth) {
+        Ok(())
+    }
+
+    /// Posts the envelopes to `/v1/telemetry/events:batch`. Events that fail local
+    /// validation are rejected without being sent; a backend without the batch
+    /// endpoint (404) is served one event at a time. Client errors other than
+    /// 408 and 429 reject the affected events so they leave the queue instead of
+    /// being retried forever.
+    fn send_batch(&self, envelopes: &[TelemetryEnvelope]) -> Result<Vec<DeliveryStatus>, PortError> {
+        let mut statuses = vec![DeliveryStatus::Retry("missing batch result".to_string()); envelopes.len()];
+        let mut events = Vec::with_capacity(envelopes.len());
+        let mut positions = Vec::with_capacity(envelopes.len());
+        for (position, envelope) in envelopes.iter().enumerate() {
+            let event = TelemetryEventDto::try_from(envelope.event.clone()).and_then(|event| {
+                validate_telemetry_event(&event)?;
+                Ok(event)
+            });
+            match event {
+                Ok(event) => {
+                    events.push(event);
+                    positions.push(position);
+                }
+                Err(err) => statuses[position] = DeliveryStatus::Rejected(err.message),
+            }
+        }
+        if events.is_empty() {
+            return Ok(statuses);
+        }
+
+        let url = format!(
+            "{}/v1/telemetry/events:batch",
+            self.config.base_url.trim_end_matches('/')
+        );
+        let mut request = self.client.post(url).json(&events);
+        let auth = envelopes
+            .iter()
+            .find_map(|envelope| envelope.auth.api_token.clone())
+            .or_else(|| self.config.api_token.clone());
+        if let Some(token) = auth {
             request = request.bearer_auth(token);
         }
 
@@ -127,14 +235,60 @@ impl TelemetrySink for HttpTelemetryClient {
             .send()
             .map_err(|err| PortError::new(err.to_string()))?;
 
+        if response.status() == StatusCode::NOT_FOUND {
+            for (&position, event) in positions.iter().zip(&events) {
+                statuses[position] = match self.post_event(event, &envelopes[position].auth) {
+                    Ok(status) if status.is_success() => DeliveryStatus::Delivered,
+                    Ok(status) => failure_status(status, "telemetry send failed"),
+                    Err(err) => DeliveryStatus::Retry(err.message),
+                };
+            }
+            return Ok(statuses);
+        }
         if !response.status().is_success() {
-            return Err(PortError::new(format!(
-                "telemetry send failed: {}",
-                response.status()
-            )));
+            return match failure_status(response.status(), "telemetry batch send failed") {
+                DeliveryStatus::Rejected(message) => {
+                    for &position in &positions {
+                        statuses[position] = DeliveryStatus::Rejected(message.clone());
+                    }
+                    Ok(statuses)
+                }
+                _ => Err(PortError::new(format!(
+                    "telemetry batch send failed: {}",
+                    response.status()
+                ))),
+            };
         }
 
-        Ok(())
+        let result = response
+            .json::<TelemetryBatchResultDto>()
+            .map_err(|err| PortError::new(err.to_string()))?;
+        for item in result.results {
+            if let Some(&position) = positions.get(item.index) {
+                statuses[position] = match item.status {
+                    TelemetryBatchStatusDto::Accepted => DeliveryStatus::Delivered,
+                    TelemetryBatchStatusDto::Rejected => {
+                        DeliveryStatus::Rejected(item.error.unwrap_or_default())
+                    }
+                };
+            }
+        }
+
+        Ok(statuses)
+    }
+}
+
+/// Delivery outcome of an unsuccessful response. Client errors fail the same
+/// way on every attempt, except timeouts (408) and rate limiting (429).
+fn failure_status(status: StatusCode, context: &str) -> DeliveryStatus {
+    let message = format!("{}: {}", context, status);
+    if status.is_client_error()
+        && status != StatusCode::REQUEST_TIMEOUT
+        && status != StatusCode::TOO_MANY_REQUESTS
+    {
+        DeliveryStatus::Rejected(message)
+    } else {
+        DeliveryStatus::Retry(message)
     }
 }
 
@@ -145,7 +299,126 @@ impl PolicyStore for HttpTelemetryClient {
         app_version: &str,
         env: &str,
         platform: Platform,
-    ) -> Result<PolicySet, PortError> {
+    ) -> Result<SignedPolicy, PortError> {
         self.fetch_policy_current(app_id, app_version, env, platform)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::telemetry::{
+        ActionContext, DeviceInfo, IntegritySignals, SigningPayloadVersion, TelemetryEvent,
+    };
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Answers each request with the next scripted status code.
+    fn serve(statuses: Vec<u16>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        thread::spawn(move || {
+            for status in statuses {
+                let (stream, _) = listener.accept().expect("accept");
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("read");
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = value.trim().parse().expect("length");
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).expect("body");
+                let response = format!(
+                    "HTTP/1.1 {} Scripted\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                reader
+                    .get_mut()
+                    .write_all(response.as_bytes())
+                    .expect("write");
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn client(base_url: String) -> HttpTelemetryClient {
+        HttpTelemetryClient::new(HttpConfig {
+            base_url,
+            api_token: None,
+            device_id: None,
+        })
+        .expect("client")
+    }
+
+    fn envelope(event_id: &str) -> TelemetryEnvelope {
+        TelemetryEnvelope::new(
+            TelemetryEvent {
+                event_id: event_id.to_string(),
+                app_id: "fintech.mobile".to_string(),
+                app_version: "1.0.0".to_string(),
+                env: "prod".to_string(),
+                device: DeviceInfo {
+                    platform: Platform::Ios,
+                    os_version: "17.0".to_string(),
+                    model: "iPhone".to_string(),
+                },
+                session: None,
+                signals: IntegritySignals {
+                    jailbreak: false,
+                    root: false,
+                    debugger: false,
+                    hooking: false,
+                    proxy_detected: false,
+                },
+                attestation: None,
+                action: ActionContext {
+                    name: "login".to_string(),
+                    context: None,
+                },
+                timestamp: Some("2026-02-06T21:00:00Z".to_string()),
+                signature: Some("ed25519:device-1:c2ln".to_string()),
+                payload_version: SigningPayloadVersion::V2,
+                risk_breakdown: None,
+            },
+            TelemetryAuth::default(),
+        )
+    }
+
+    #[test]
+    fn client_errors_reject_events_except_timeouts_and_rate_limits() {
+        // Batch endpoint missing, then one response per event.
+        let client = client(serve(vec![404, 409, 422, 429, 503, 202]));
+        let envelopes = ["evt_1", "evt_2", "evt_3", "evt_4", "evt_5"]
+            .map(envelope)
+            .to_vec();
+
+        let statuses = client.send_batch(&envelopes).expect("batch");
+        assert!(matches!(statuses[0], DeliveryStatus::Rejected(_)));
+        assert!(matches!(statuses[1], DeliveryStatus::Rejected(_)));
+        assert!(matches!(statuses[2], DeliveryStatus::Retry(_)));
+        assert!(matches!(statuses[3], DeliveryStatus::Retry(_)));
+        assert_eq!(statuses[4], DeliveryStatus::Delivered);
+    }
+
+    #[test]
+    fn rejected_batches_reject_every_event() {
+        let client = client(serve(vec![401, 503]));
+        let envelopes = vec![envelope("evt_1"), envelope("evt_2")];
+
+        let statuses = client.send_batch(&envelopes).expect("batch");
+        assert!(
+            statuses
+                .iter()
+                .all(|status| matches!(status, DeliveryStatus::Rejected(_)))
+        );
+
+        assert!(client.send_batch(&envelopes).is_err());
+    }
+}
@@ -1,6 +1,9 @@
 //! Adapters live here (FFI, HTTP, storage, crypto, etc.).
 //! Implementations will depend on platform targets and will be added incrementally.
 
+pub mod crypto;
 pub mod ffi;
 pub mod serialization;
 pub mod http;
+pub mod policy_cache;
+pub mod telemetry_queue;
@@ -0,0 +1,390 @@
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::Duration;
+
+use crate::adapters::serialization::{CachedPolicyDto, PolicyDto};
+use crate::app::policy_verification::{
+    parse_timestamp, verify_policy_signature, MAX_POLICY_FUTURE_SKEW_SECS,
+};
+use crate::domain::policy::SignedPolicy;
+use crate::domain::telemetry::Platform;
+use crate::ports::{Clock, PolicyStore, PortError, SignatureVerifier};
+
+/// `PolicyStore` decorator that keeps the last verified policy on disk.
+///
+/// Policies from `inner` are persisted to `path` together with the app, version,
+/// environment and platform they were fetched for. When `inner` fails the cached
+/// policy is re-verified and served if it was fetched for the same request and
+/// is not older than `max_staleness`; otherwise
+/// the bundled policy shipped with the app is served. Both fallbacks must carry
+/// a valid policy-service signature.
+pub struct CachedPolicyStore<PS, V, C>
+where
+    PS: PolicyStore,
+    V: SignatureVerifier,
+    C: Clock,
+{
+    inner: PS,
+    verifier: V,
+    clock: C,
+    path: Option<PathBuf>,
+    max_staleness: Option<Duration>,
+    bundled: Option<SignedPolicy>,
+}
+
+impl<PS, V, C> CachedPolicyStore<PS, V, C>
+where
+    PS: PolicyStore,
+    V: SignatureVerifier,
+    C: Clock,
+{
+    pub fn new(
+        inner: PS,
+        verifier: V,
+        clock: C,
+        path: Option<PathBuf>,
+        max_staleness: Option<Duration>,
+        bundled: Option<SignedPolicy>,
+    ) -> Self {
+        Self {
+            inner,
+            verifier,
+            clock,
+            path,
+            max_staleness,
+            bundled,
+        }
+    }
+
+    /// Parses a `PolicyDto` JSON document, e.g. one embedded with `include_str!`.
+    pub fn parse_bundled(json: &str) -> Result<SignedPolicy, PortError> {
+        serde_json::from_str::<PolicyDto>(json)
+            .map(Into::into)
+            .map_err(|err| PortError::new(format!("bundled policy is invalid: {}", err)))
+    }
+
+    fn store(
+        &self,
+        policy: &SignedPolicy,
+        app_id: &str,
+        app_version: &str,
+        env: &str,
+        platform: &Platform,
+    ) -> Result<(), PortError> {
+        let path = match &self.path {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        let cached = CachedPolicyDto {
+            app_id: app_id.to_string(),
+            app_version: app_version.to_string(),
+            env: env.to_string(),
+            device_platform: platform.as_str().to_string(),
+            cached_at: self.clock.now(),
+            policy: policy.clone().into(),
+        };
+        let json = serde_json::to_vec(&cached).map_err(|err| PortError::new(err.to_string()))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| PortError::new(err.to_string()))?;
+        }
+        // Write then rename so a crash never leaves a truncated cache behind.
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, json).map_err(|err| PortError::new(err.to_string()))?;
+        fs::rename(&tmp, path).map_err(|err| PortError::new(err.to_string()))
+    }
+
+    fn load_cached(
+        &self,
+        app_id: &str,
+        app_version: &str,
+        env: &str,
+        platform: &Platform,
+    ) -> Result<SignedPolicy, PortError> {
+        let path = self
+            .path
+            .as_ref()
+            .ok_or_else(|| PortError::new("no policy cache configured"))?;
+        let bytes = fs::read(path).map_err(|err| PortError::new(err.to_string()))?;
+        let cached = serde_json::from_slice::<CachedPolicyDto>(&bytes)
+            .map_err(|err| PortError::new(format!("policy cache is unreadable: {}", err)))?;
+
+        if cached.device_platform != platform.as_str()
+            || cached.app_id != app_id
+            || cached.app_version != app_version
+            || cached.env != env
+        {
+            return Err(PortError::new(
+                "cached policy belongs to another app configuration",
+            ));
+        }
+
+        let policy = SignedPolicy::from(cached.policy);
+        verify_policy_signature(&policy, &self.verifier)?;
+        self.check_staleness(&policy)?;
+        Ok(policy)
+    }
+
+    fn check_staleness(&self, policy: &SignedPolicy) -> Result<(), PortError> {
+        let max_staleness = match self.max_staleness {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        let issued_at = parse_timestamp(&policy.issued_at, "policy.issued_at")?;
+        let now = parse_timestamp(&self.clock.now(), "clock")?;
+        if issued_at - now > Duration::seconds(MAX_POLICY_FUTURE_SKEW_SECS) {
+            return Err(PortError::new(format!(
+                "cached policy issued at {} is in the future",
+                policy.issued_at
+            )));
+        }
+        if now - issued_at > max_staleness {
+            return Err(PortError::new(format!(
+                "cached policy issued at {} exceeds max staleness of {} seconds",
+                policy.issued_at,
+                max_staleness.num_seconds()
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn load_bundled(&self) -> Result<SignedPolicy, PortError> {
+        let policy = self
+            .bundled
+            .clone()
+            .ok_or_else(|| PortError::new("no bundled policy configured"))?;
+        verify_policy_signature(&policy, &self.verifier)?;
+        Ok(policy)
+    }
+}
+
+impl<PS, V, C> PolicyStore for CachedPolicyStore<PS, V, C>
+where
+    PS: PolicyStore,
+    V: SignatureVerifier,
+    C: Clock,
+{
+    fn get_policy(
+        &self,
+        app_id: &str,
+        app_version: &str,
+        env: &str,
+        platform: Platform,
+    ) -> Result<SignedPolicy, PortError> {
+        let fetch_err = match self
+            .inner
+            .get_policy(app_id, app_version, env, platform.clone())
+        {
+            Ok(policy) => {
+                // A failed cache write must not cost the caller a fresh policy.
+                let _ = self.store(&policy, app_id, app_version, env, &platform);
+                return Ok(policy);
+            }
+            Err(err) => err,
+        };
+
+        self.load_cached(app_id, app_version, env, &platform)
+            .or_else(|_| self.load_bundled())
+            .map_err(|_| fetch_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::crypto::{Ed25519Signer, Ed25519Verifier};
+    use crate::domain::policy::{
+        Decision, PolicyConditions, PolicyDefaults, PolicyRule, PolicySet,
+    };
+    use crate::ports::CryptoSigner;
+    use std::cell::Cell;
+
+    struct FixedClock(&'static str);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    /// Serves its policy until switched offline.
+    struct FlakyStore {
+        policy: SignedPolicy,
+        online: Cell<bool>,
+    }
+
+    impl PolicyStore for FlakyStore {
+        fn get_policy(
+            &self,
+            _app_id: &str,
+            _app_version: &str,
+            _env: &str,
+            _platform: Platform,
+        ) -> Result<SignedPolicy, PortError> {
+            if self.online.get() {
+                Ok(self.policy.clone())
+            } else {
+                Err(PortError::new("network unreachable"))
+            }
+        }
+    }
+
+    fn signed_policy(signer: &Ed25519Signer, policy_id: &str, issued_at: &str) -> SignedPolicy {
+        let policy = PolicySet {
+            policy_id: policy_id.to_string(),
+            app_id: "fintech.mobile".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "prod".to_string(),
+            rules: vec![PolicyRule {
+                action: "transfer".to_string(),
+                decision: Decision::Deny,
+                conditions: PolicyConditions {
+                    root: Some(true),
+                    ..PolicyConditions::default()
+                },
+            }],
+            defaults: PolicyDefaults::default(),
+            risk_weights: None,
+            overrides: Vec::new(),
+        };
+        let signature = signer
+            .sign(&policy.signing_payload(issued_at))
+            .expect("sign");
+        SignedPolicy {
+            policy,
+            signature,
+            issued_at: issued_at.to_string(),
+        }
+    }
+
+    fn cache_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "panther-policy-cache-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn store(
+        inner: FlakyStore,
+        signer: &Ed25519Signer,
+        path: PathBuf,
+        bundled: Option<SignedPolicy>,
+    ) -> CachedPolicyStore<FlakyStore, Ed25519Verifier, FixedClock> {
+        CachedPolicyStore::new(
+            inner,
+            Ed25519Verifier::new(vec![signer.public_key()]),
+            FixedClock("2026-02-06T22:00:00Z"),
+            Some(path),
+            Some(Duration::hours(24)),
+            bundled,
+        )
+    }
+
+    #[test]
+    fn serves_last_known_good_policy_when_offline() {
+        let signer = Ed25519Signer::generate("policy-service").expect("signer");
+        let policy = signed_policy(&signer, "pol_live", "2026-02-06T21:00:00Z");
+        let path = cache_path("offline");
+        let cached = store(
+            FlakyStore {
+                policy: policy.clone(),
+                online: Cell::new(true),
+            },
+            &signer,
+            path.clone(),
+            None,
+        );
+
+        cached
+            .get_policy("fintech.mobile", "1.0.0", "prod", Platform::Ios)
+            .expect("online fetch");
+        cached.inner.online.set(false);
+
+        let served = cached
+            .get_policy("fintech.mobile", "1.0.0", "prod", Platform::Ios)
+            .expect("cached policy");
+        assert_eq!(served, policy);
+
+        let other_platform =
+            cached.get_policy("fintech.mobile", "1.0.0", "prod", Platform::Android);
+        assert_eq!(other_platform.unwrap_err().message, "network unreachable");
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn serves_range_resolved_policy_for_the_request_it_was_fetched_for() {
+        let signer = Ed25519Signer::generate("policy-service").expect("signer");
+        let mut policy = signed_policy(&signer, "pol_range", "2026-02-06T21:00:00Z");
+        policy.policy.app_version = ">=1.0.0 <2.0.0".to_string();
+        policy.signature = signer.sign(&policy.signing_payload()).expect("sign");
+        let path = cache_path("range");
+        let cached = store(
+            FlakyStore {
+                policy: policy.clone(),
+                online: Cell::new(true),
+            },
+            &signer,
+            path.clone(),
+            None,
+        );
+
+        cached
+            .get_policy("fintech.mobile", "1.4.2", "prod", Platform::Ios)
+            .expect("online fetch");
+        cached.inner.online.set(false);
+
+        let served = cached
+            .get_policy("fintech.mobile", "1.4.2", "prod", Platform::Ios)
+            .expect("cached policy");
+        assert_eq!(served, policy);
+
+        let other_version = cached.get_policy("fintech.mobile", "1.5.0", "prod", Platform::Ios);
+        assert_eq!(other_version.unwrap_err().message, "network unreachable");
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn rejects_stale_or_tampered_cache_and_uses_bundled_policy() {
+        let signer = Ed25519Signer::generate("policy-service").expect("signer");
+        let stale = signed_policy(&signer, "pol_stale", "2026-02-01T21:00:00Z");
+        let bundled = signed_policy(&signer, "pol_bundled", "2026-01-01T00:00:00Z");
+        let path = cache_path("stale");
+        let cached = store(
+            FlakyStore {
+                policy: stale,
+                online: Cell::new(true),
+            },
+            &signer,
+            path.clone(),
+            Some(bundled.clone()),
+        );
+
+        cached
+            .get_policy("fintech.mobile", "1.0.0", "prod", Platform::Ios)
+            .expect("online fetch");
+        cached.inner.online.set(false);
+
+        let served = cached
+            .get_policy("fintech.mobile", "1.0.0", "prod", Platform::Ios)
+            .expect("bundled policy");
+        assert_eq!(served.policy.policy_id, "pol_bundled");
+
+        let mut tampered = signed_policy(&signer, "pol_fresh", "2026-02-06T21:00:00Z");
+        tampered.policy.rules[0].decision = Decision::Allow;
+        cached
+            .store(&tampered, "fintech.mobile", "1.0.0", "prod", &Platform::Ios)
+            .expect("write cache");
+        let served = cached
+            .get_policy("fintech.mobile", "1.0.0", "prod", Platform::Ios)
+            .expect("bundled policy");
+        assert_eq!(served.policy.policy_id, "pol_bundled");
+        let _ = fs::remove_file(path);
+    }
+}
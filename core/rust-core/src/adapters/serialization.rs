@@ -1,12 +1,23 @@
+use std::collections::BTreeMap;
+
+use chrono::DateTime;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
-use crate::domain::policy::{Decision, PolicyConditions, PolicyRule, PolicySet};
-use crate::domain::risk::{Finding, RiskScore, Severity};
+use crate::domain::policy::{
+    Decision, PolicyConditions, PolicyDefaults, PolicyOverride, PolicyRule, PolicySet,
+    SignedPolicy, MAX_CONDITION_DEPTH,
+};
+use crate::domain::policy_diff::{RuleChange, RuleChangeKind};
+use crate::domain::policy_lint::{LintKind, LintSeverity, PolicyLint};
+use crate::domain::risk::{
+    Finding, RiskBreakdown, RiskContribution, RiskFactor, RiskScore, RiskWeights, Severity,
+};
 use crate::domain::telemetry::{
-    ActionContext, AttestationProvider, AttestationResult, AttestationStatus, DeviceInfo,
-    IntegritySignals, Platform, SessionInfo, TelemetryEvent,
+    ActionContext, AttestationChallenge, AttestationProvider, AttestationResult,
+    AttestationStatus, DeviceInfo, IntegritySignals, Platform, SessionInfo, SigningPayloadVersion, TelemetryEvent,
 };
+use crate::domain::version::VersionRange;
 
 #[derive(Debug)]
 pub struct DtoError {
@@ -47,9 +58,12 @@ impl From<PlatformDto> for Platform {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum AttestationProviderDto {
+    // Aliases accept payloads stored before the names matched the contract.
+    #[serde(alias = "appattest")]
     AppAttest,
+    #[serde(alias = "playintegrity")]
     PlayIntegrity,
     None,
 }
@@ -261,6 +275,8 @@ pub struct AttestationResultDto {
     pub provider: AttestationProviderDto,
     pub result: AttestationStatusDto,
     pub timestamp: Option<String>,
+    /// Challenge from `/v1/attestation/challenge` the evidence was bound to.
+    pub challenge: Option<String>,
 }
 
 impl From<AttestationResult> for AttestationResultDto {
@@ -269,6 +285,7 @@ impl From<AttestationResult> for AttestationResultDto {
             provider: value.provider.into(),
             result: value.status.into(),
             timestamp: value.timestamp,
+            challenge: value.challenge,
         }
     }
 }
@@ -279,6 +296,7 @@ impl From<AttestationResultDto> for AttestationResult {
             provider: value.provider.into(),
             status: value.result.into(),
             timestamp: value.timestamp,
+            challenge: value.challenge,
         }
     }
 }
@@ -324,6 +342,9 @@ pub struct TelemetryEventDto {
     pub action: ActionContextDto,
     pub timestamp: String,
     pub signature: String,
+    /// Signing payload layout; absent means the legacy v1 payload.
+    pub payload_version: Option<u32>,
+    pub risk_breakdown: Option<RiskBreakdownDto>,
 }
 
 impl TryFrom<TelemetryEvent> for TelemetryEventDto {
@@ -349,6 +370,11 @@ impl TryFrom<TelemetryEvent> for TelemetryEventDto {
             action: value.action.into(),
             timestamp,
             signature,
+            payload_version: match value.payload_version {
+                SigningPayloadVersion::V1 => None,
+                version => Some(version.number()),
+            },
+            risk_breakdown: value.risk_breakdown.map(Into::into),
         })
     }
 }
@@ -367,31 +393,151 @@ impl From<TelemetryEventDto> for TelemetryEvent {
             action: value.action.into(),
             timestamp: Some(value.timestamp),
             signature: Some(value.signature),
+            // Unknown versions are rejected by `validate_telemetry_event`; falling back to
+            // the legacy layout here can only make verification fail, never succeed.
+            payload_version: value
+                .payload_version
+                .and_then(SigningPayloadVersion::from_number)
+                .unwrap_or(SigningPayloadVersion::V1),
+            risk_breakdown: value.risk_breakdown.map(Into::into),
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskFactorDto {
+    Jailbreak,
+    Root,
+    Debugger,
+    Hooking,
+    ProxyDetected,
+    AttestationFail,
+    AttestationUnknown,
+    Finding,
+}
+
+/// One factor of a `RiskBreakdownDto`; `category` and `severity` are set only
+/// for `finding`.
 #[skip_serializing_none]
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
+pub struct RiskContributionDto {
+    pub factor: RiskFactorDto,
+    pub category: Option<String>,
+    pub severity: Option<SeverityDto>,
+    pub weight: u32,
+    pub points: u32,
+    pub capped: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RiskBreakdownDto {
+    pub score: u32,
+    pub factors: Vec<RiskContributionDto>,
+}
+
+impl From<RiskContribution> for RiskContributionDto {
+    fn from(value: RiskContribution) -> Self {
+        let (factor, category, severity) = match value.factor {
+            RiskFactor::Jailbreak => (RiskFactorDto::Jailbreak, None, None),
+            RiskFactor::Root => (RiskFactorDto::Root, None, None),
+            RiskFactor::Debugger => (RiskFactorDto::Debugger, None, None),
+            RiskFactor::Hooking => (RiskFactorDto::Hooking, None, None),
+            RiskFactor::ProxyDetected => (RiskFactorDto::ProxyDetected, None, None),
+            RiskFactor::AttestationFail => (RiskFactorDto::AttestationFail, None, None),
+            RiskFactor::AttestationUnknown => (RiskFactorDto::AttestationUnknown, None, None),
+            RiskFactor::Finding { category, severity } => {
+                (RiskFactorDto::Finding, Some(category), Some(severity.into()))
+            }
+        };
+        Self {
+            factor,
+            category,
+            severity,
+            weight: value.weight,
+            points: value.points,
+            capped: value.capped,
+        }
+    }
+}
+
+impl From<RiskContributionDto> for RiskContribution {
+    fn from(value: RiskContributionDto) -> Self {
+        let factor = match value.factor {
+            RiskFactorDto::Jailbreak => RiskFactor::Jailbreak,
+            RiskFactorDto::Root => RiskFactor::Root,
+            RiskFactorDto::Debugger => RiskFactor::Debugger,
+            RiskFactorDto::Hooking => RiskFactor::Hooking,
+            RiskFactorDto::ProxyDetected => RiskFactor::ProxyDetected,
+            RiskFactorDto::AttestationFail => RiskFactor::AttestationFail,
+            RiskFactorDto::AttestationUnknown => RiskFactor::AttestationUnknown,
+            // Missing fields are rejected by `validate_telemetry_event`; filling them
+            // in here can only make verification fail, never succeed.
+            RiskFactorDto::Finding => RiskFactor::Finding {
+                category: value.category.unwrap_or_default(),
+                severity: value.severity.map(Into::into).unwrap_or(Severity::Low),
+            },
+        };
+        Self {
+            factor,
+            weight: value.weight,
+            points: value.points,
+            capped: value.capped,
+        }
+    }
+}
+
+impl From<RiskBreakdown> for RiskBreakdownDto {
+    fn from(value: RiskBreakdown) -> Self {
+        Self {
+            score: value.score.value(),
+            factors: value.contributions.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<RiskBreakdownDto> for RiskBreakdown {
+    fn from(value: RiskBreakdownDto) -> Self {
+        Self {
+            score: RiskScore::new(value.score),
+            contributions: value.factors.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields)]
 pub struct PolicyConditionsDto {
     pub attestation: Option<AttestationStatusDto>,
+    pub jailbreak: Option<bool>,
+    pub root: Option<bool>,
     pub debugger: Option<bool>,
     pub hooking: Option<bool>,
     pub proxy_detected: Option<bool>,
     pub app_version: Option<String>,
     pub risk_score_gte: Option<u32>,
+    pub all_of: Option<Vec<PolicyConditionsDto>>,
+    pub any_of: Option<Vec<PolicyConditionsDto>>,
+    pub not: Option<Box<PolicyConditionsDto>>,
 }
 
 impl From<PolicyConditions> for PolicyConditionsDto {
     fn from(value: PolicyConditions) -> Self {
         Self {
             attestation: value.attestation_status.map(Into::into),
+            jailbreak: value.jailbreak,
+            root: value.root,
             debugger: value.debugger,
             hooking: value.hooking,
             proxy_detected: value.proxy_detected,
             app_version: value.app_version,
             risk_score_gte: value.risk_score_gte,
+            all_of: conditions_to_dto(value.all_of),
+            any_of: conditions_to_dto(value.any_of),
+            not: value.not.map(|negated| Box::new((*negated).into())),
         }
     }
 }
@@ -400,15 +546,36 @@ impl From<PolicyConditionsDto> for PolicyConditions {
     fn from(value: PolicyConditionsDto) -> Self {
         Self {
             attestation_status: value.attestation.map(Into::into),
+            jailbreak: value.jailbreak,
+            root: value.root,
             debugger: value.debugger,
             hooking: value.hooking,
             proxy_detected: value.proxy_detected,
             app_version: value.app_version,
             risk_score_gte: value.risk_score_gte,
+            all_of: conditions_from_dto(value.all_of),
+            any_of: conditions_from_dto(value.any_of),
+            not: value.not.map(|negated| Box::new((*negated).into())),
         }
     }
 }
 
+fn conditions_to_dto(groups: Vec<PolicyConditions>) -> Option<Vec<PolicyConditionsDto>> {
+    if groups.is_empty() {
+        None
+    } else {
+        Some(groups.into_iter().map(Into::into).collect())
+    }
+}
+
+fn conditions_from_dto(groups: Option<Vec<PolicyConditionsDto>>) -> Vec<PolicyConditions> {
+    groups
+        .unwrap_or_default()
+        .into_iter()
+        .map(Into::into)
+        .collect()
+}
+
 #[skip_serializing_none]
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
@@ -447,10 +614,130 @@ pub struct PolicyDto {
     pub app_version: String,
     pub env: String,
     pub rules: Vec<PolicyRuleDto>,
+    /// Decision when no rule matches; `ALLOW` when omitted.
+    pub default_decision: Option<DecisionDto>,
+    /// Per-action decision when no rule matches, overriding `default_decision`.
+    pub action_defaults: Option<BTreeMap<String, DecisionDto>>,
+    pub risk_weights: Option<RiskWeightsDto>,
+    /// Active kill-switch overrides, evaluated before `rules`.
+    pub overrides: Option<Vec<PolicyOverrideDto>>,
     pub signature: String,
     pub issued_at: String,
 }
 
+/// Kill switch distributed in a policy. `actions` omitted applies it to every
+/// action except `except_actions`.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyOverrideDto {
+    pub actions: Option<Vec<String>>,
+    pub except_actions: Option<Vec<String>>,
+    pub decision: DecisionDto,
+    pub expires_at: String,
+    pub reason: Option<String>,
+}
+
+impl From<PolicyOverride> for PolicyOverrideDto {
+    fn from(value: PolicyOverride) -> Self {
+        Self {
+            actions: (!value.actions.is_empty()).then_some(value.actions),
+            except_actions: (!value.except_actions.is_empty()).then_some(value.except_actions),
+            decision: value.decision.into(),
+            expires_at: value.expires_at,
+            reason: value.reason,
+        }
+    }
+}
+
+impl From<PolicyOverrideDto> for PolicyOverride {
+    fn from(value: PolicyOverrideDto) -> Self {
+        Self {
+            actions: value.actions.unwrap_or_default(),
+            except_actions: value.except_actions.unwrap_or_default(),
+            decision: value.decision.into(),
+            expires_at: value.expires_at,
+            reason: value.reason,
+        }
+    }
+}
+
+/// Admin request setting a kill switch for an app and environment.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyOverrideRequestDto {
+    pub app_id: String,
+    pub env: String,
+    pub actions: Option<Vec<String>>,
+    pub except_actions: Option<Vec<String>>,
+    pub decision: DecisionDto,
+    pub expires_at: String,
+    pub reason: Option<String>,
+}
+
+/// Omitted weights fall back to `RiskWeights::default()`.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RiskWeightsDto {
+    pub jailbreak: Option<u32>,
+    pub root: Option<u32>,
+    pub debugger: Option<u32>,
+    pub hooking: Option<u32>,
+    pub proxy_detected: Option<u32>,
+    pub attestation_fail: Option<u32>,
+    pub attestation_unknown: Option<u32>,
+    pub finding_low: Option<u32>,
+    pub finding_medium: Option<u32>,
+    pub finding_high: Option<u32>,
+    pub finding_critical: Option<u32>,
+    pub finding_category_pct: Option<BTreeMap<String, u32>>,
+    pub decay_half_life_secs: Option<u32>,
+}
+
+impl From<RiskWeights> for RiskWeightsDto {
+    fn from(value: RiskWeights) -> Self {
+        Self {
+            jailbreak: Some(value.jailbreak),
+            root: Some(value.root),
+            debugger: Some(value.debugger),
+            hooking: Some(value.hooking),
+            proxy_detected: Some(value.proxy_detected),
+            attestation_fail: Some(value.attestation_fail),
+            attestation_unknown: Some(value.attestation_unknown),
+            finding_low: Some(value.finding_low),
+            finding_medium: Some(value.finding_medium),
+            finding_high: Some(value.finding_high),
+            finding_critical: Some(value.finding_critical),
+            finding_category_pct: (!value.finding_category_pct.is_empty())
+                .then_some(value.finding_category_pct),
+            decay_half_life_secs: value.decay_half_life_secs,
+        }
+    }
+}
+
+impl From<RiskWeightsDto> for RiskWeights {
+    fn from(value: RiskWeightsDto) -> Self {
+        let defaults = RiskWeights::default();
+        Self {
+            jailbreak: value.jailbreak.unwrap_or(defaults.jailbreak),
+            root: value.root.unwrap_or(defaults.root),
+            debugger: value.debugger.unwrap_or(defaults.debugger),
+            hooking: value.hooking.unwrap_or(defaults.hooking),
+            proxy_detected: value.proxy_detected.unwrap_or(defaults.proxy_detected),
+            attestation_fail: value.attestation_fail.unwrap_or(defaults.attestation_fail),
+            attestation_unknown: value.attestation_unknown.unwrap_or(defaults.attestation_unknown),
+            finding_low: value.finding_low.unwrap_or(defaults.finding_low),
+            finding_medium: value.finding_medium.unwrap_or(defaults.finding_medium),
+            finding_high: value.finding_high.unwrap_or(defaults.finding_high),
+            finding_critical: value.finding_critical.unwrap_or(defaults.finding_critical),
+            finding_category_pct: value.finding_category_pct.unwrap_or_default(),
+            decay_half_life_secs: value.decay_half_life_secs,
+        }
+    }
+}
+
 #[skip_serializing_none]
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
@@ -459,26 +746,225 @@ pub struct PolicyUpsertDto {
     pub policy: PolicyDto,
 }
 
+/// Candidate policy served to `percentage`% of devices (bucketed on device or
+/// session id) in place of the current policy of the same slot.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyRolloutDto {
+    pub device_platform: String,
+    pub percentage: u32,
+    pub policy: PolicyDto,
+}
+
+/// Policy slot (`app_id`, `app_version`, `env`, `device_platform`) of a rollout
+/// to promote or roll back.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyRolloutTargetDto {
+    pub app_id: String,
+    pub app_version: String,
+    pub env: String,
+    pub device_platform: String,
+}
+
+/// Restores the `policy_versions` entry keyed by `policy_id`, `issued_at` and
+/// `device_platform` as the current policy.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyRollbackDto {
+    pub policy_id: String,
+    pub issued_at: String,
+    pub device_platform: String,
+    pub actor: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleChangeKindDto {
+    Added,
+    Removed,
+    Reordered,
+    ConditionsChanged,
+    DecisionChanged,
+}
+
+impl From<RuleChangeKind> for RuleChangeKindDto {
+    fn from(value: RuleChangeKind) -> Self {
+        match value {
+            RuleChangeKind::Added => RuleChangeKindDto::Added,
+            RuleChangeKind::Removed => RuleChangeKindDto::Removed,
+            RuleChangeKind::Reordered => RuleChangeKindDto::Reordered,
+            RuleChangeKind::ConditionsChanged => RuleChangeKindDto::ConditionsChanged,
+            RuleChangeKind::DecisionChanged => RuleChangeKindDto::DecisionChanged,
+        }
+    }
+}
+
+/// One entry of a policy diff; see `domain::policy_diff::diff_rules`.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RuleChangeDto {
+    pub change: RuleChangeKindDto,
+    pub action: String,
+    pub from_index: Option<usize>,
+    pub to_index: Option<usize>,
+    pub before: Option<PolicyRuleDto>,
+    pub after: Option<PolicyRuleDto>,
+}
+
+impl From<RuleChange> for RuleChangeDto {
+    fn from(value: RuleChange) -> Self {
+        Self {
+            change: value.kind.into(),
+            action: value.action,
+            from_index: value.from_index,
+            to_index: value.to_index,
+            before: value.before.map(Into::into),
+            after: value.after.map(Into::into),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKindDto {
+    ShadowedRule,
+    DuplicateRule,
+    ContradictoryConditions,
+    UnknownAction,
+    ImpossibleRiskScore,
+}
+
+impl From<LintKind> for LintKindDto {
+    fn from(value: LintKind) -> Self {
+        match value {
+            LintKind::ShadowedRule => LintKindDto::ShadowedRule,
+            LintKind::DuplicateRule => LintKindDto::DuplicateRule,
+            LintKind::ContradictoryConditions => LintKindDto::ContradictoryConditions,
+            LintKind::UnknownAction => LintKindDto::UnknownAction,
+            LintKind::ImpossibleRiskScore => LintKindDto::ImpossibleRiskScore,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverityDto {
+    Error,
+    Warning,
+}
+
+impl From<LintSeverity> for LintSeverityDto {
+    fn from(value: LintSeverity) -> Self {
+        match value {
+            LintSeverity::Error => LintSeverityDto::Error,
+            LintSeverity::Warning => LintSeverityDto::Warning,
+        }
+    }
+}
+
+/// One finding of the policy linter; see `domain::policy_lint::lint_policy`.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyLintDto {
+    pub kind: LintKindDto,
+    pub severity: LintSeverityDto,
+    pub action: String,
+    pub rule_index: Option<usize>,
+    pub related_index: Option<usize>,
+    pub message: String,
+}
+
+impl From<PolicyLint> for PolicyLintDto {
+    fn from(value: PolicyLint) -> Self {
+        Self {
+            kind: value.kind.into(),
+            severity: value.severity.into(),
+            action: value.action,
+            rule_index: value.rule_index,
+            related_index: value.related_index,
+            message: value.message,
+        }
+    }
+}
+
+/// On-disk form of the last verified policy kept by the SDK policy cache. The
+/// cache is keyed by the request that fetched the policy, not by the policy's
+/// own `app_id`/`app_version`, which may be a range or the org default.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CachedPolicyDto {
+    pub app_id: String,
+    pub app_version: String,
+    pub env: String,
+    pub device_platform: String,
+    pub cached_at: String,
+    pub policy: PolicyDto,
+}
+
 #[skip_serializing_none]
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
 pub struct PolicyUpsertResponse {
     pub status: String,
     pub stored_at: String,
+    /// Lint findings that did not block the upsert.
+    pub warnings: Option<Vec<PolicyLintDto>>,
 }
 
 impl PolicyDto {
     pub fn new(policy: PolicySet, signature: String, issued_at: String) -> Self {
+        let defaults = policy.defaults;
         Self {
             policy_id: policy.policy_id,
             app_id: policy.app_id,
             app_version: policy.app_version,
             env: policy.env,
             rules: policy.rules.into_iter().map(Into::into).collect(),
+            default_decision: (defaults.decision != Decision::Allow)
+                .then(|| defaults.decision.into()),
+            action_defaults: (!defaults.actions.is_empty()).then(|| {
+                defaults
+                    .actions
+                    .into_iter()
+                    .map(|(action, decision)| (action, decision.into()))
+                    .collect()
+            }),
+            risk_weights: policy.risk_weights.map(Into::into),
+            overrides: (!policy.overrides.is_empty())
+                .then(|| policy.overrides.into_iter().map(Into::into).collect()),
             signature,
             issued_at,
         }
     }
+
+    /// Bytes the policy-service signs; see `PolicySet::signing_payload`.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        PolicySet::from(self.clone()).signing_payload(&self.issued_at)
+    }
+}
+
+impl From<SignedPolicy> for PolicyDto {
+    fn from(value: SignedPolicy) -> Self {
+        PolicyDto::new(value.policy, value.signature, value.issued_at)
+    }
+}
+
+impl From<PolicyDto> for SignedPolicy {
+    fn from(value: PolicyDto) -> Self {
+        Self {
+            signature: value.signature.clone(),
+            issued_at: value.issued_at.clone(),
+            policy: value.into(),
+        }
+    }
 }
 
 impl From<PolicyDto> for PolicySet {
@@ -489,6 +975,22 @@ impl From<PolicyDto> for PolicySet {
             app_version: value.app_version,
             env: value.env,
             rules: value.rules.into_iter().map(Into::into).collect(),
+            defaults: PolicyDefaults {
+                decision: value.default_decision.map(Into::into).unwrap_or(Decision::Allow),
+                actions: value
+                    .action_defaults
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(action, decision)| (action, decision.into()))
+                    .collect(),
+            },
+            risk_weights: value.risk_weights.map(Into::into),
+            overrides: value
+                .overrides
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
         }
     }
 }
@@ -551,6 +1053,15 @@ pub struct ReportUploadDto {
     pub timestamp: String,
 }
 
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AppKeyDto {
+    pub app_id: String,
+    pub key_id: String,
+    pub public_key: String,
+}
+
 #[skip_serializing_none]
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
@@ -565,17 +1076,128 @@ pub struct StatusAccepted {
     pub status: String,
 }
 
-pub fn compute_risk(findings: &[FindingDto]) -> Result<RiskScore, DtoError> {
-    let mut score: u32 = 0;
-    for finding in findings {
-        score += match finding.severity {
-            SeverityDto::Low => 5,
-            SeverityDto::Medium => 10,
-            SeverityDto::High => 20,
-            SeverityDto::Critical => 30,
-        };
+/// Risk inputs registered for an app version and env: the policy served there, as signed by
+/// the policy-service, and the findings of its latest report. `/v1/telemetry/risk` scores with
+/// the policy's `risk_weights`, the same ones the SDK uses, never with weights sent unsigned.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AppRiskProfileDto {
+    pub policy: PolicyDto,
+    pub findings: Option<Vec<FindingDto>>,
+}
+
+/// Asks telemetry-ingestion to score a device from its stored, signed events.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RiskScoreRequestDto {
+    pub app_id: String,
+    pub app_version: String,
+    pub env: String,
+    pub session_id: Option<String>,
+    pub user_id_hash: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RiskScoreResponseDto {
+    pub risk_score: u32,
+    pub events_considered: usize,
+    pub computed_at: String,
+}
+
+/// Asks for a single-use attestation challenge bound to one action of a session.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AttestationChallengeRequestDto {
+    pub app_id: String,
+    pub session_id: String,
+    pub action: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AttestationChallengeDto {
+    pub challenge: String,
+    pub expires_at: String,
+}
+
+impl From<AttestationChallengeDto> for AttestationChallenge {
+    fn from(value: AttestationChallengeDto) -> Self {
+        Self {
+            challenge: value.challenge,
+            expires_at: value.expires_at,
+        }
     }
-    Ok(RiskScore::new(score))
+}
+
+/// Attestation evidence for the backend to verify. App Attest sends `key_id`
+/// with either the one-time `attestation` object or a per-request `assertion`;
+/// Play Integrity sends the encrypted `token`. `challenge` is the value from
+/// `/v1/attestation/challenge` the evidence was bound to on the device.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AttestationVerifyRequestDto {
+    pub app_id: String,
+    pub provider: AttestationProviderDto,
+    pub challenge: String,
+    /// Base64 App Attest key identifier.
+    pub key_id: Option<String>,
+    /// Base64 CBOR attestation object from `attestKey`.
+    pub attestation: Option<String>,
+    /// Base64 CBOR assertion from `generateAssertion`.
+    pub assertion: Option<String>,
+    /// Play Integrity token (compact JWE).
+    pub token: Option<String>,
+}
+
+/// Server-issued attestation result. `reason` explains a `fail`.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AttestationVerdictDto {
+    pub attestation: AttestationResultDto,
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TelemetryBatchStatusDto {
+    Accepted,
+    Rejected,
+}
+
+/// Outcome for one event of a batch; `index` is its position in the request.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TelemetryBatchItemDto {
+    pub index: usize,
+    pub event_id: Option<String>,
+    pub status: TelemetryBatchStatusDto,
+    pub error: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TelemetryBatchResultDto {
+    pub accepted: usize,
+    pub rejected: usize,
+    pub results: Vec<TelemetryBatchItemDto>,
+}
+
+/// Scores report findings with the default `RiskWeights`.
+pub fn compute_risk(findings: &[FindingDto]) -> Result<RiskScore, DtoError> {
+    let findings = findings
+        .iter()
+        .cloned()
+        .map(Finding::from)
+        .collect::<Vec<_>>();
+    Ok(RiskWeights::default().score_findings(&findings))
 }
 
 pub fn validate_telemetry_event(dto: &TelemetryEventDto) -> Result<(), DtoError> {
@@ -588,6 +1210,57 @@ pub fn validate_telemetry_event(dto: &TelemetryEventDto) -> Result<(), DtoError>
     validate_non_empty("action.name", &dto.action.name)?;
     validate_non_empty("timestamp", &dto.timestamp)?;
     validate_non_empty("signature", &dto.signature)?;
+    if let Some(version) = dto.payload_version
+        && SigningPayloadVersion::from_number(version).is_none()
+    {
+        return Err(DtoError::new(format!(
+            "payload_version {} is not supported",
+            version
+        )));
+    }
+    // Older layouts do not sign these fields, so they would travel unauthenticated.
+    let signs_v3_fields = dto.payload_version.unwrap_or(1) >= SigningPayloadVersion::V3.number();
+    if let Some(challenge) = dto
+        .attestation
+        .as_ref()
+        .and_then(|attestation| attestation.challenge.as_deref())
+    {
+        if !signs_v3_fields {
+            return Err(DtoError::new("attestation.challenge requires payload_version 3"));
+        }
+        validate_non_empty("attestation.challenge", challenge)?;
+    }
+    if let Some(breakdown) = &dto.risk_breakdown {
+        if !signs_v3_fields {
+            return Err(DtoError::new("risk_breakdown requires payload_version 3"));
+        }
+        validate_risk_breakdown(breakdown)?;
+    }
+    Ok(())
+}
+
+fn validate_risk_breakdown(dto: &RiskBreakdownDto) -> Result<(), DtoError> {
+    if dto.score > 100 {
+        return Err(DtoError::new("risk_breakdown.score must be at most 100"));
+    }
+    let mut total = 0u32;
+    for contribution in &dto.factors {
+        let is_finding = contribution.factor == RiskFactorDto::Finding;
+        if is_finding != (contribution.category.is_some() && contribution.severity.is_some()) {
+            return Err(DtoError::new(
+                "risk_breakdown.factors: category and severity are required for findings only",
+            ));
+        }
+        if let Some(category) = &contribution.category {
+            validate_non_empty("risk_breakdown.factors.category", category)?;
+        }
+        total = total.saturating_add(contribution.points);
+    }
+    if !dto.factors.is_empty() && total != dto.score {
+        return Err(DtoError::new(
+            "risk_breakdown.factors points must add up to risk_breakdown.score",
+        ));
+    }
     Ok(())
 }
 
@@ -601,9 +1274,227 @@ pub fn validate_policy(dto: &PolicyDto) -> Result<(), DtoError> {
     if dto.rules.is_empty() {
         return Err(DtoError::new("policy.rules must not be empty"));
     }
+    if let Some(action_defaults) = &dto.action_defaults {
+        for action in action_defaults.keys() {
+            validate_non_empty("policy.action_defaults.action", action)?;
+        }
+    }
+    if let Some(weights) = &dto.risk_weights {
+        validate_risk_weights(weights)?;
+    }
+    for policy_override in dto.overrides.iter().flatten() {
+        validate_override_scope(
+            policy_override.actions.as_deref(),
+            policy_override.except_actions.as_deref(),
+        )?;
+        validate_rfc3339("policy.override.expires_at", &policy_override.expires_at)?;
+    }
     for rule in &dto.rules {
         validate_non_empty("policy.rule.action", &rule.action)?;
+        if let Some(conditions) = &rule.conditions {
+            validate_conditions(conditions, 1)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_policy_rollout(dto: &PolicyRolloutDto) -> Result<(), DtoError> {
+    validate_non_empty("device_platform", &dto.device_platform)?;
+    if !(1..=100).contains(&dto.percentage) {
+        return Err(DtoError::new("percentage must be between 1 and 100"));
+    }
+    validate_policy(&dto.policy)
+}
+
+pub fn validate_policy_rollout_target(dto: &PolicyRolloutTargetDto) -> Result<(), DtoError> {
+    validate_non_empty("app_id", &dto.app_id)?;
+    validate_non_empty("app_version", &dto.app_version)?;
+    validate_non_empty("env", &dto.env)?;
+    validate_non_empty("device_platform", &dto.device_platform)
+}
+
+pub fn validate_policy_rollback(dto: &PolicyRollbackDto) -> Result<(), DtoError> {
+    validate_non_empty("policy_id", &dto.policy_id)?;
+    validate_non_empty("issued_at", &dto.issued_at)?;
+    validate_non_empty("device_platform", &dto.device_platform)?;
+    validate_non_empty("actor", &dto.actor)?;
+    validate_non_empty("reason", &dto.reason)
+}
+
+/// Kill switches only tighten decisions, so `ALLOW` is rejected.
+pub fn validate_policy_override_request(dto: &PolicyOverrideRequestDto) -> Result<(), DtoError> {
+    validate_non_empty("app_id", &dto.app_id)?;
+    validate_non_empty("env", &dto.env)?;
+    validate_override_scope(dto.actions.as_deref(), dto.except_actions.as_deref())?;
+    validate_rfc3339("expires_at", &dto.expires_at)?;
+    if dto.decision == DecisionDto::Allow {
+        return Err(DtoError::new("decision must not be ALLOW"));
+    }
+    if let Some(reason) = &dto.reason {
+        validate_non_empty("reason", reason)?;
+    }
+    Ok(())
+}
+
+fn validate_override_scope(
+    actions: Option<&[String]>,
+    except_actions: Option<&[String]>,
+) -> Result<(), DtoError> {
+    if let Some(actions) = actions {
+        if actions.is_empty() {
+            return Err(DtoError::new("override.actions must not be empty"));
+        }
+        for action in actions {
+            validate_non_empty("override.action", action)?;
+        }
+    }
+    for action in except_actions.into_iter().flatten() {
+        validate_non_empty("override.except_action", action)?;
+    }
+    Ok(())
+}
+
+fn validate_rfc3339(field: &str, value: &str) -> Result<(), DtoError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|_| ())
+        .map_err(|_| DtoError::new(format!("{field} must be an RFC 3339 timestamp")))
+}
+
+pub fn validate_risk_weights(dto: &RiskWeightsDto) -> Result<(), DtoError> {
+    let weights = [
+        ("jailbreak", dto.jailbreak),
+        ("root", dto.root),
+        ("debugger", dto.debugger),
+        ("hooking", dto.hooking),
+        ("proxy_detected", dto.proxy_detected),
+        ("attestation_fail", dto.attestation_fail),
+        ("attestation_unknown", dto.attestation_unknown),
+        ("finding_low", dto.finding_low),
+        ("finding_medium", dto.finding_medium),
+        ("finding_high", dto.finding_high),
+        ("finding_critical", dto.finding_critical),
+    ];
+    for (field, weight) in weights {
+        if weight.is_some_and(|weight| weight > 100) {
+            return Err(DtoError::new(format!(
+                "risk_weights.{} must be at most 100",
+                field
+            )));
+        }
     }
+    for (category, pct) in dto.finding_category_pct.iter().flatten() {
+        validate_non_empty("risk_weights.finding_category_pct.category", category)?;
+        if *pct > 1000 {
+            return Err(DtoError::new(format!(
+                "risk_weights.finding_category_pct.{} must be at most 1000",
+                category
+            )));
+        }
+    }
+    if dto.decay_half_life_secs == Some(0) {
+        return Err(DtoError::new("risk_weights.decay_half_life_secs must be positive"));
+    }
+    Ok(())
+}
+
+pub fn validate_app_risk_profile(dto: &AppRiskProfileDto) -> Result<(), DtoError> {
+    validate_policy(&dto.policy)?;
+    for finding in dto.findings.iter().flatten() {
+        validate_non_empty("findings.category", &finding.category)?;
+    }
+    Ok(())
+}
+
+pub fn validate_risk_score_request(dto: &RiskScoreRequestDto) -> Result<(), DtoError> {
+    validate_non_empty("app_id", &dto.app_id)?;
+    validate_non_empty("app_version", &dto.app_version)?;
+    validate_non_empty("env", &dto.env)?;
+    if dto.session_id.is_none() && dto.user_id_hash.is_none() {
+        return Err(DtoError::new("session_id or user_id_hash is required"));
+    }
+    Ok(())
+}
+
+pub fn validate_attestation_challenge_request(
+    dto: &AttestationChallengeRequestDto,
+) -> Result<(), DtoError> {
+    validate_non_empty("app_id", &dto.app_id)?;
+    validate_non_empty("session_id", &dto.session_id)?;
+    validate_non_empty("action", &dto.action)?;
+    Ok(())
+}
+
+pub fn validate_attestation_verify_request(
+    dto: &AttestationVerifyRequestDto,
+) -> Result<(), DtoError> {
+    validate_non_empty("app_id", &dto.app_id)?;
+    validate_non_empty("challenge", &dto.challenge)?;
+    match dto.provider {
+        AttestationProviderDto::AppAttest => {
+            validate_non_empty("key_id", dto.key_id.as_deref().unwrap_or_default())?;
+            if dto.attestation.is_some() == dto.assertion.is_some() {
+                return Err(DtoError::new(
+                    "app_attest requires exactly one of attestation or assertion",
+                ));
+            }
+            if dto.token.is_some() {
+                return Err(DtoError::new("token is only valid for play_integrity"));
+            }
+        }
+        AttestationProviderDto::PlayIntegrity => {
+            validate_non_empty("token", dto.token.as_deref().unwrap_or_default())?;
+            if dto.key_id.is_some() || dto.attestation.is_some() || dto.assertion.is_some() {
+                return Err(DtoError::new(
+                    "key_id, attestation and assertion are only valid for app_attest",
+                ));
+            }
+        }
+        AttestationProviderDto::None => {
+            return Err(DtoError::new("provider must be app_attest or play_integrity"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_conditions(dto: &PolicyConditionsDto, depth: usize) -> Result<(), DtoError> {
+    if depth > MAX_CONDITION_DEPTH {
+        return Err(DtoError::new(format!(
+            "policy.rule.conditions nesting exceeds {} levels",
+            MAX_CONDITION_DEPTH
+        )));
+    }
+
+    if let Some(range) = &dto.app_version {
+        VersionRange::parse(range).map_err(|err| {
+            DtoError::new(format!("policy.rule.conditions.app_version: {}", err.message))
+        })?;
+    }
+
+    for (field, groups) in [("all_of", &dto.all_of), ("any_of", &dto.any_of)] {
+        if let Some(groups) = groups {
+            if groups.is_empty() {
+                return Err(DtoError::new(format!(
+                    "policy.rule.conditions.{} must not be empty",
+                    field
+                )));
+            }
+            for group in groups {
+                validate_conditions(group, depth + 1)?;
+            }
+        }
+    }
+
+    if let Some(negated) = &dto.not {
+        validate_conditions(negated, depth + 1)?;
+    }
+
+    Ok(())
+}
+
+pub fn validate_app_key(dto: &AppKeyDto) -> Result<(), DtoError> {
+    validate_non_empty("app_id", &dto.app_id)?;
+    validate_non_empty("key_id", &dto.key_id)?;
+    validate_non_empty("public_key", &dto.public_key)?;
     Ok(())
 }
 
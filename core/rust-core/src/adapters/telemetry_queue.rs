@@ -0,0 +1,482 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rand_core::{OsRng, RngCore};
+
+use crate::adapters::serialization::TelemetryEventDto;
+use crate::domain::telemetry::{TelemetryAuth, TelemetryEnvelope};
+use crate::ports::{DeliveryStatus, PortError, TelemetrySink};
+
+#[derive(Clone, Debug)]
+pub struct TelemetryQueueConfig {
+    /// JSON-lines file holding events that have not been delivered yet.
+    pub path: PathBuf,
+    /// Disk budget for the queue; the oldest events are evicted beyond it.
+    pub max_bytes: u64,
+    pub batch_size: usize,
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl TelemetryQueueConfig {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes: 1024 * 1024,
+            batch_size: 50,
+            base_backoff: Duration::from_secs(2),
+            max_backoff: Duration::from_secs(300),
+        }
+    }
+}
+
+#[derive(Default)]
+struct BackoffState {
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+/// `TelemetrySink` wrapper that makes delivery durable.
+///
+/// `send` appends the event to an on-disk queue and then flushes it in batches
+/// through `inner`. Delivered and rejected events leave the queue; transient
+/// failures stay queued and postpone the next flush with exponential backoff
+/// and jitter. The queue is re-read on every flush, so events written before a
+/// restart are sent by the next process. Queued events are sent with the
+/// inner sink's configured credentials, never with per-envelope tokens, so no
+/// token is written to disk.
+pub struct QueuedTelemetrySink<TS>
+where
+    TS: TelemetrySink,
+{
+    inner: TS,
+    config: Option<TelemetryQueueConfig>,
+    state: Mutex<BackoffState>,
+}
+
+impl<TS> QueuedTelemetrySink<TS>
+where
+    TS: TelemetrySink,
+{
+    /// Without a config every envelope goes straight to `inner`.
+    pub fn new(inner: TS, config: Option<TelemetryQueueConfig>) -> Self {
+        Self {
+            inner,
+            config,
+            state: Mutex::new(BackoffState::default()),
+        }
+    }
+
+    /// Number of events waiting in the queue.
+    pub fn pending(&self) -> Result<usize, PortError> {
+        match &self.config {
+            Some(config) => {
+                let _guard = self.lock()?;
+                Ok(read_lines(config)?.len())
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// Sends queued events until the queue is empty or a batch needs a retry.
+    /// Does nothing while backing off. Returns the number of events left.
+    pub fn flush(&self) -> Result<usize, PortError> {
+        let config = match &self.config {
+            Some(value) => value,
+            None => return Ok(0),
+        };
+        let mut state = self.lock()?;
+        if let Some(retry_at) = state.retry_at
+            && Instant::now() < retry_at
+        {
+            return Ok(read_lines(config)?.len());
+        }
+
+        loop {
+            let lines = read_lines(config)?;
+            if lines.is_empty() {
+                *state = BackoffState::default();
+                return Ok(0);
+            }
+
+            let batch_len = lines.len().min(config.batch_size.max(1));
+            let parsed = lines[..batch_len]
+                .iter()
+                .map(|line| {
+                    serde_json::from_str::<TelemetryEventDto>(line)
+                        .ok()
+                        .map(|dto| TelemetryEnvelope::new(dto.into(), TelemetryAuth::default()))
+                })
+                .collect::<Vec<_>>();
+            let envelopes = parsed.iter().flatten().cloned().collect::<Vec<_>>();
+
+            let statuses = match self.inner.send_batch(&envelopes) {
+                Ok(statuses) if statuses.len() == envelopes.len() => statuses,
+                Ok(_) => {
+                    self.schedule_retry(&mut state, config);
+                    return Err(PortError::new(
+                        "telemetry sink returned a partial batch result",
+                    ));
+                }
+                Err(err) => {
+                    self.schedule_retry(&mut state, config);
+                    return Err(err);
+                }
+            };
+
+            // Unreadable lines have no status and are dropped with the batch.
+            let mut statuses = statuses.into_iter();
+            let mut remaining = Vec::with_capacity(lines.len());
+            let mut retry = false;
+            for (line, envelope) in lines[..batch_len].iter().zip(&parsed) {
+                if envelope.is_none() {
+                    continue;
+                }
+                if let Some(DeliveryStatus::Retry(_)) = statuses.next() {
+                    retry = true;
+                    remaining.push(line.clone());
+                }
+            }
+            remaining.extend(lines[batch_len..].iter().cloned());
+            write_lines(config, &remaining)?;
+
+            if retry {
+                self.schedule_retry(&mut state, config);
+                return Ok(remaining.len());
+            }
+            *state = BackoffState::default();
+        }
+    }
+
+    fn enqueue(
+        &self,
+        config: &TelemetryQueueConfig,
+        envelope: &TelemetryEnvelope,
+    ) -> Result<(), PortError> {
+        let dto = TelemetryEventDto::try_from(envelope.event.clone())
+            .map_err(|err| PortError::new(err.message))?;
+        let line = serde_json::to_string(&dto).map_err(|err| PortError::new(err.to_string()))?;
+        if line_size(&line) > config.max_bytes {
+            return Err(PortError::new(
+                "telemetry event exceeds the queue size limit",
+            ));
+        }
+
+        let _guard = self.lock()?;
+        let mut lines = read_lines(config)?;
+        lines.push(line);
+
+        let mut size = lines.iter().map(|line| line_size(line)).sum::<u64>();
+        let mut evicted = 0;
+        while size > config.max_bytes {
+            size -= line_size(&lines[evicted]);
+            evicted += 1;
+        }
+        write_lines(config, &lines[evicted..])
+    }
+
+    fn schedule_retry(&self, state: &mut BackoffState, config: &TelemetryQueueConfig) {
+        state.failures = state.failures.saturating_add(1);
+        state.retry_at = Some(Instant::now() + backoff_delay(config, state.failures));
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, BackoffState>, PortError> {
+        self.state
+            .lock()
+            .map_err(|_| PortError::new("telemetry queue lock poisoned"))
+    }
+}
+
+impl<TS> TelemetrySink for QueuedTelemetrySink<TS>
+where
+    TS: TelemetrySink,
+{
+    fn send(&self, envelope: &TelemetryEnvelope) -> Result<(), PortError> {
+        let config = match &self.config {
+            Some(value) => value,
+            None => return self.inner.send(envelope),
+        };
+
+        if self.enqueue(config, envelope).is_err() {
+            return self.inner.send(envelope);
+        }
+        // The event is durable at this point; a failed flush is retried later.
+        let _ = self.flush();
+        Ok(())
+    }
+}
+
+/// Exponential backoff with "equal jitter": a random delay between half and
+/// all of `base * 2^(failures - 1)`, capped at `max_backoff`.
+fn backoff_delay(config: &TelemetryQueueConfig, failures: u32) -> Duration {
+    let exponent = failures.saturating_sub(1).min(31);
+    let ceiling = config
+        .base_backoff
+        .saturating_mul(1u32 << exponent)
+        .min(config.max_backoff);
+    let half = ceiling / 2;
+    let jitter_ms = match u64::try_from(half.as_millis()) {
+        Ok(0) | Err(_) => 0,
+        Ok(ms) => OsRng.next_u64() % (ms + 1),
+    };
+    half + Duration::from_millis(jitter_ms)
+}
+
+fn line_size(line: &str) -> u64 {
+    line.len() as u64 + 1
+}
+
+fn read_lines(config: &TelemetryQueueConfig) -> Result<Vec<String>, PortError> {
+    match fs::read_to_string(&config.path) {
+        Ok(contents) => Ok(contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::to_string)
+            .collect()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(PortError::new(err.to_string())),
+    }
+}
+
+fn write_lines(config: &TelemetryQueueConfig, lines: &[String]) -> Result<(), PortError> {
+    if let Some(parent) = config.path.parent() {
+        fs::create_dir_all(parent).map_err(|err| PortError::new(err.to_string()))?;
+    }
+    let mut contents = String::new();
+    for line in lines {
+        contents.push_str(line);
+        contents.push('\n');
+    }
+    let tmp = config.path.with_extension("tmp");
+    fs::write(&tmp, contents).map_err(|err| PortError::new(err.to_string()))?;
+    fs::rename(&tmp, &config.path).map_err(|err| PortError::new(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::telemetry::{
+        ActionContext, DeviceInfo, IntegritySignals, Platform, SigningPayloadVersion,
+        TelemetryEvent,
+    };
+
+    /// Records batches and answers with the scripted statuses (Delivered when
+    /// the script runs out), or fails every call when offline.
+    struct ScriptedSink {
+        online: bool,
+        script: Mutex<Vec<DeliveryStatus>>,
+        batches: Mutex<Vec<Vec<String>>>,
+    }
+
+    impl ScriptedSink {
+        fn new(online: bool, script: Vec<DeliveryStatus>) -> Self {
+            Self {
+                online,
+                script: Mutex::new(script),
+                batches: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn batches(&self) -> Vec<Vec<String>> {
+            self.batches.lock().unwrap().clone()
+        }
+    }
+
+    impl TelemetrySink for ScriptedSink {
+        fn send(&self, _envelope: &TelemetryEnvelope) -> Result<(), PortError> {
+            unreachable!("queued sink always sends batches")
+        }
+
+        fn send_batch(
+            &self,
+            envelopes: &[TelemetryEnvelope],
+        ) -> Result<Vec<DeliveryStatus>, PortError> {
+            if !self.online {
+                return Err(PortError::new("network unreachable"));
+            }
+            self.batches
+                .lock()
+                .unwrap()
+                .push(envelopes.iter().map(|e| e.event.event_id.clone()).collect());
+            let mut script = self.script.lock().unwrap();
+            Ok(envelopes
+                .iter()
+                .map(|_| {
+                    if script.is_empty() {
+                        DeliveryStatus::Delivered
+                    } else {
+                        script.remove(0)
+                    }
+                })
+                .collect())
+        }
+    }
+
+    fn envelope(event_id: &str) -> TelemetryEnvelope {
+        TelemetryEnvelope::new(
+            TelemetryEvent {
+                event_id: event_id.to_string(),
+                app_id: "fintech.mobile".to_string(),
+                app_version: "1.0.0".to_string(),
+                env: "prod".to_string(),
+                device: DeviceInfo {
+                    platform: Platform::Ios,
+                    os_version: "17.0".to_string(),
+                    model: "iPhone".to_string(),
+                },
+                session: None,
+                signals: IntegritySignals {
+                    jailbreak: false,
+                    root: false,
+                    debugger: false,
+                    hooking: false,
+                    proxy_detected: false,
+                },
+                attestation: None,
+                action: ActionContext {
+                    name: "login".to_string(),
+                    context: None,
+                },
+                timestamp: Some("2026-02-06T21:00:00Z".to_string()),
+                signature: Some("ed25519:device-1:c2ln".to_string()),
+                payload_version: SigningPayloadVersion::V2,
+                risk_breakdown: None,
+            },
+            TelemetryAuth::default(),
+        )
+    }
+
+    fn config(name: &str) -> TelemetryQueueConfig {
+        let path = std::env::temp_dir().join(format!(
+            "panther-telemetry-queue-{}-{}.jsonl",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        TelemetryQueueConfig {
+            batch_size: 2,
+            ..TelemetryQueueConfig::new(path)
+        }
+    }
+
+    #[test]
+    fn queued_events_survive_restart_and_flush_in_batches() {
+        let config = config("restart");
+        let offline =
+            QueuedTelemetrySink::new(ScriptedSink::new(false, Vec::new()), Some(config.clone()));
+        for id in ["evt_1", "evt_2", "evt_3"] {
+            offline.send(&envelope(id)).expect("queued");
+        }
+        assert_eq!(offline.pending().expect("pending"), 3);
+        drop(offline);
+
+        let online =
+            QueuedTelemetrySink::new(ScriptedSink::new(true, Vec::new()), Some(config.clone()));
+        assert_eq!(online.flush().expect("flush"), 0);
+        assert_eq!(
+            online.inner.batches(),
+            vec![
+                vec!["evt_1".to_string(), "evt_2".to_string()],
+                vec!["evt_3".to_string()],
+            ]
+        );
+        let _ = fs::remove_file(config.path);
+    }
+
+    #[test]
+    fn drops_rejected_events_and_backs_off_on_retry() {
+        let config = config("retry");
+        let sink = QueuedTelemetrySink::new(
+            ScriptedSink::new(
+                true,
+                vec![
+                    DeliveryStatus::Rejected("bad signature".to_string()),
+                    DeliveryStatus::Retry("503".to_string()),
+                ],
+            ),
+            Some(config.clone()),
+        );
+        sink.enqueue(&config, &envelope("evt_bad"))
+            .expect("enqueue");
+        sink.enqueue(&config, &envelope("evt_later"))
+            .expect("enqueue");
+
+        assert_eq!(sink.flush().expect("flush"), 1);
+        // Backing off: the next flush does not reach the sink.
+        assert_eq!(sink.flush().expect("flush"), 1);
+        assert_eq!(sink.inner.batches().len(), 1);
+        let _ = fs::remove_file(config.path);
+    }
+
+    #[test]
+    fn permanently_rejected_events_do_not_stall_the_queue() {
+        let config = config("poison");
+        let sink = QueuedTelemetrySink::new(
+            ScriptedSink::new(
+                true,
+                vec![
+                    DeliveryStatus::Rejected("telemetry send failed: 409 Conflict".to_string());
+                    3
+                ],
+            ),
+            Some(config.clone()),
+        );
+        for id in ["evt_dup_1", "evt_dup_2", "evt_dup_3"] {
+            sink.enqueue(&config, &envelope(id)).expect("enqueue");
+        }
+
+        assert_eq!(sink.flush().expect("flush"), 0);
+        assert_eq!(sink.inner.batches().len(), 2);
+
+        // No backoff was scheduled, so the next event goes out immediately.
+        sink.send(&envelope("evt_fresh")).expect("send");
+        assert_eq!(sink.pending().expect("pending"), 0);
+        assert_eq!(
+            sink.inner.batches().last(),
+            Some(&vec!["evt_fresh".to_string()])
+        );
+        let _ = fs::remove_file(config.path);
+    }
+
+    #[test]
+    fn evicts_oldest_events_beyond_disk_budget() {
+        let mut config = config("evict");
+        let line = serde_json::to_string(
+            &TelemetryEventDto::try_from(envelope("evt_0").event).expect("dto"),
+        )
+        .expect("json");
+        config.max_bytes = 2 * line_size(&line);
+        let sink =
+            QueuedTelemetrySink::new(ScriptedSink::new(false, Vec::new()), Some(config.clone()));
+
+        for id in ["evt_0", "evt_1", "evt_2"] {
+            sink.enqueue(&config, &envelope(id)).expect("enqueue");
+        }
+        let ids = read_lines(&config)
+            .expect("read")
+            .iter()
+            .map(|line| {
+                serde_json::from_str::<TelemetryEventDto>(line)
+                    .expect("dto")
+                    .event_id
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["evt_1".to_string(), "evt_2".to_string()]);
+        let _ = fs::remove_file(config.path);
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_within_cap() {
+        let config = TelemetryQueueConfig::new("unused");
+        for failures in 1..=12 {
+            let delay = backoff_delay(&config, failures);
+            let ceiling = config
+                .base_backoff
+                .saturating_mul(1 << (failures - 1))
+                .min(config.max_backoff);
+            assert!(delay >= ceiling / 2 && delay <= ceiling);
+        }
+    }
+}
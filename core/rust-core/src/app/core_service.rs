@@ -1,4 +1,7 @@
-use crate::domain::policy::{Decision, PolicyEngine, PolicySet};
+use chrono::Utc;
+
+use crate::app::policy_verification::parse_timestamp;
+use crate::domain::policy::{Decision, Evaluation, PolicyEngine, PolicySet};
 use crate::domain::risk::Finding;
 use crate::domain::telemetry::{
     ActionContext, AttestationResult, IntegritySignals, Platform, TelemetryAuth,
@@ -41,6 +44,10 @@ where
         }
     }
 
+    pub fn telemetry_sink(&self) -> &TS {
+        &self.telemetry
+    }
+
     pub fn emit_telemetry(
         &self,
         mut event: TelemetryEvent,
@@ -48,7 +55,7 @@ where
     ) -> Result<TelemetryEnvelope, PortError> {
         event.timestamp = Some(self.clock.now());
         let payload = event.signing_payload();
-        let signature = self.signer.sign(payload.as_bytes())?;
+        let signature = self.signer.sign(&payload)?;
         event.signature = Some(signature);
         let envelope = TelemetryEnvelope::new(event, auth);
         self.telemetry.send(&envelope)?;
@@ -64,6 +71,7 @@ where
     ) -> Result<PolicySet, PortError> {
         self.policy_store
             .get_policy(app_id, app_version, env, platform)
+            .map(|signed| signed.policy)
     }
 
     pub fn decide_action(
@@ -74,7 +82,116 @@ where
         attestation: Option<&AttestationResult>,
         findings: &[Finding],
     ) -> Decision {
-        let risk_score = self.risk_scorer.score(signals, attestation, findings);
-        PolicyEngine::evaluate(policy, ctx, signals, attestation, risk_score)
+        self.explain_action(policy, ctx, signals, attestation, findings)
+            .decision
+    }
+
+    /// Like [`CoreService::decide_action`], also reporting the rule that fired,
+    /// the conditions it matched and the computed risk score with its
+    /// breakdown. Risk weights carried by the policy take precedence over the
+    /// configured scorer. Overrides expire against the configured clock, or the
+    /// system time when the clock reading does not parse.
+    pub fn explain_action(
+        &self,
+        policy: &PolicySet,
+        ctx: &ActionContext,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+        findings: &[Finding],
+    ) -> Evaluation {
+        let now = parse_timestamp(&self.clock.now(), "clock").unwrap_or_else(|_| Utc::now());
+        let breakdown = match &policy.risk_weights {
+            Some(weights) => weights.breakdown(signals, attestation, findings),
+            None => self.risk_scorer.breakdown(signals, attestation, findings),
+        };
+        let mut evaluation =
+            PolicyEngine::explain_at(policy, ctx, signals, attestation, breakdown.score, now);
+        evaluation.risk_breakdown = breakdown;
+        evaluation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::policy::{PolicyDefaults, PolicyOverride, SignedPolicy};
+    use crate::sdk::WeightedRiskScorer;
+
+    struct FixedClock(&'static str);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    struct Unused;
+
+    impl TelemetrySink for Unused {
+        fn send(&self, _envelope: &TelemetryEnvelope) -> Result<(), PortError> {
+            Err(PortError::new("unused"))
+        }
+    }
+
+    impl PolicyStore for Unused {
+        fn get_policy(
+            &self,
+            _app_id: &str,
+            _app_version: &str,
+            _env: &str,
+            _platform: Platform,
+        ) -> Result<SignedPolicy, PortError> {
+            Err(PortError::new("unused"))
+        }
+    }
+
+    impl CryptoSigner for Unused {
+        fn sign(&self, _payload: &[u8]) -> Result<String, PortError> {
+            Err(PortError::new("unused"))
+        }
+    }
+
+    fn decide(clock: &'static str) -> Decision {
+        let policy = PolicySet {
+            policy_id: "pol_1".to_string(),
+            app_id: "fintech.mobile".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "prod".to_string(),
+            rules: Vec::new(),
+            defaults: PolicyDefaults::default(),
+            risk_weights: None,
+            overrides: vec![PolicyOverride {
+                actions: Vec::new(),
+                except_actions: Vec::new(),
+                decision: Decision::Deny,
+                expires_at: "2026-02-06T21:10:00Z".to_string(),
+                reason: None,
+            }],
+        };
+        let core = CoreService::new(
+            Unused,
+            Unused,
+            FixedClock(clock),
+            Unused,
+            WeightedRiskScorer::default(),
+        );
+        let signals = IntegritySignals {
+            jailbreak: false,
+            root: false,
+            debugger: false,
+            hooking: false,
+            proxy_detected: false,
+        };
+        let ctx = ActionContext {
+            name: "transfer".to_string(),
+            context: None,
+        };
+        core.decide_action(&policy, &ctx, &signals, None, &[])
+    }
+
+    #[test]
+    fn overrides_expire_against_the_configured_clock() {
+        assert_eq!(decide("2026-02-06T21:05:00Z"), Decision::Deny);
+        assert_eq!(decide("2026-02-06T21:15:00Z"), Decision::Allow);
     }
 }
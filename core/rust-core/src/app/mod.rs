@@ -1 +1,2 @@
 pub mod core_service;
+pub mod policy_verification;
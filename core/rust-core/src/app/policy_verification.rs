@@ -0,0 +1,244 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::domain::policy::SignedPolicy;
+use crate::domain::telemetry::Platform;
+use crate::ports::{Clock, PolicyStore, PortError, SignatureVerifier};
+
+/// Checks the policy-service signature of a policy, independently of its age.
+/// A `panther.policy.v1` signature is still accepted for policies that layout
+/// fully covers, while policy-services are upgraded to v2.
+pub fn verify_policy_signature<V: SignatureVerifier>(
+    policy: &SignedPolicy,
+    verifier: &V,
+) -> Result<(), PortError> {
+    let err = match verifier.verify(&policy.signing_payload(), &policy.signature) {
+        Ok(()) => return Ok(()),
+        Err(err) => err,
+    };
+    if let Some(legacy) = policy.legacy_signing_payload()
+        && verifier.verify(&legacy, &policy.signature).is_ok()
+    {
+        return Ok(());
+    }
+    Err(PortError::new(format!("policy signature rejected: {}", err.message)))
+}
+
+/// How far in the future `issued_at` may be, for clock skew between the
+/// policy-service and the device, before a policy is rejected.
+pub(crate) const MAX_POLICY_FUTURE_SKEW_SECS: i64 = 5 * 60;
+
+/// `PolicyStore` decorator that only hands out policies signed by the
+/// policy-service and issued within `max_age`.
+pub struct VerifyingPolicyStore<PS, V, C>
+where
+    PS: PolicyStore,
+    V: SignatureVerifier,
+    C: Clock,
+{
+    inner: PS,
+    verifier: V,
+    clock: C,
+    max_age: Option<Duration>,
+}
+
+impl<PS, V, C> VerifyingPolicyStore<PS, V, C>
+where
+    PS: PolicyStore,
+    V: SignatureVerifier,
+    C: Clock,
+{
+    pub fn new(inner: PS, verifier: V, clock: C, max_age: Option<Duration>) -> Self {
+        Self {
+            inner,
+            verifier,
+            clock,
+            max_age,
+        }
+    }
+
+    fn check_freshness(&self, policy: &SignedPolicy) -> Result<(), PortError> {
+        let max_age = match self.max_age {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        let issued_at = parse_timestamp(&policy.issued_at, "policy.issued_at")?;
+        let now = parse_timestamp(&self.clock.now(), "clock")?;
+        if issued_at - now > Duration::seconds(MAX_POLICY_FUTURE_SKEW_SECS) {
+            return Err(PortError::new(format!(
+                "policy issued at {} is in the future",
+                policy.issued_at
+            )));
+        }
+        if now - issued_at > max_age {
+            return Err(PortError::new(format!(
+                "policy issued at {} is older than {} seconds",
+                policy.issued_at,
+                max_age.num_seconds()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl<PS, V, C> PolicyStore for VerifyingPolicyStore<PS, V, C>
+where
+    PS: PolicyStore,
+    V: SignatureVerifier,
+    C: Clock,
+{
+    fn get_policy(
+        &self,
+        app_id: &str,
+        app_version: &str,
+        env: &str,
+        platform: Platform,
+    ) -> Result<SignedPolicy, PortError> {
+        let policy = self.inner.get_policy(app_id, app_version, env, platform)?;
+        verify_policy_signature(&policy, &self.verifier)?;
+        self.check_freshness(&policy)?;
+        Ok(policy)
+    }
+}
+
+pub(crate) fn parse_timestamp(value: &str, field: &str) -> Result<DateTime<Utc>, PortError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| PortError::new(format!("{} is not a valid RFC 3339 timestamp", field)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::crypto::{Ed25519Signer, Ed25519Verifier};
+    use crate::domain::policy::{Decision, PolicyConditions, PolicyDefaults, PolicyRule, PolicySet};
+    use crate::ports::CryptoSigner;
+
+    struct FixedClock(&'static str);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    struct StaticStore(SignedPolicy);
+
+    impl PolicyStore for StaticStore {
+        fn get_policy(
+            &self,
+            _app_id: &str,
+            _app_version: &str,
+            _env: &str,
+            _platform: Platform,
+        ) -> Result<SignedPolicy, PortError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn signed_policy(signer: &Ed25519Signer, issued_at: &str) -> SignedPolicy {
+        let policy = PolicySet {
+            policy_id: "pol_1".to_string(),
+            app_id: "fintech.mobile".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "prod".to_string(),
+            rules: vec![PolicyRule {
+                action: "transfer".to_string(),
+                decision: Decision::Deny,
+                conditions: PolicyConditions {
+                    proxy_detected: Some(true),
+                    ..PolicyConditions::default()
+                },
+            }],
+            defaults: PolicyDefaults::default(),
+            risk_weights: None,
+            overrides: Vec::new(),
+        };
+        let signature = signer
+            .sign(&policy.signing_payload(issued_at))
+            .expect("sign");
+        SignedPolicy {
+            policy,
+            signature,
+            issued_at: issued_at.to_string(),
+        }
+    }
+
+    fn store(
+        policy: SignedPolicy,
+        signer: &Ed25519Signer,
+    ) -> VerifyingPolicyStore<StaticStore, Ed25519Verifier, FixedClock> {
+        VerifyingPolicyStore::new(
+            StaticStore(policy),
+            Ed25519Verifier::new(vec![signer.public_key()]),
+            FixedClock("2026-02-06T22:00:00Z"),
+            Some(Duration::hours(24)),
+        )
+    }
+
+    #[test]
+    fn returns_policies_with_valid_signature() {
+        let signer = Ed25519Signer::generate("policy-service").expect("signer");
+        let policy = signed_policy(&signer, "2026-02-06T21:00:00Z");
+
+        let fetched = store(policy.clone(), &signer)
+            .get_policy("fintech.mobile", "1.0.0", "prod", Platform::Ios)
+            .expect("verified policy");
+        assert_eq!(fetched, policy);
+    }
+
+    #[test]
+    fn rejects_downgraded_rules() {
+        let signer = Ed25519Signer::generate("policy-service").expect("signer");
+        let mut policy = signed_policy(&signer, "2026-02-06T21:00:00Z");
+        policy.policy.rules[0].decision = Decision::Allow;
+
+        let result = store(policy, &signer).get_policy("fintech.mobile", "1.0.0", "prod", Platform::Ios);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn accepts_legacy_v1_signatures_only_for_policies_they_cover() {
+        let signer = Ed25519Signer::generate("policy-service").expect("signer");
+        let mut policy = signed_policy(&signer, "2026-02-06T21:00:00Z");
+        let legacy = policy.legacy_signing_payload().expect("v1 layout");
+        assert_ne!(legacy, policy.signing_payload());
+        policy.signature = signer.sign(&legacy).expect("sign");
+        store(policy.clone(), &signer)
+            .get_policy("fintech.mobile", "1.0.0", "prod", Platform::Ios)
+            .expect("v1 signature during rollout");
+
+        // Defaults are not in the v1 layout, so a v1 signature cannot vouch for them.
+        policy.policy.defaults = PolicyDefaults::fail_closed();
+        assert!(policy.legacy_signing_payload().is_none());
+        let result = store(policy, &signer).get_policy("fintech.mobile", "1.0.0", "prod", Platform::Ios);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rejects_stale_policies() {
+        let signer = Ed25519Signer::generate("policy-service").expect("signer");
+        let policy = signed_policy(&signer, "2026-02-01T21:00:00Z");
+
+        let err = store(policy, &signer)
+            .get_policy("fintech.mobile", "1.0.0", "prod", Platform::Ios)
+            .unwrap_err();
+        assert!(err.message.contains("older than"));
+    }
+
+    #[test]
+    fn rejects_policies_issued_in_the_future() {
+        let signer = Ed25519Signer::generate("policy-service").expect("signer");
+        let skewed = signed_policy(&signer, "2026-02-06T22:04:00Z");
+        store(skewed, &signer)
+            .get_policy("fintech.mobile", "1.0.0", "prod", Platform::Ios)
+            .expect("within the skew window");
+
+        let policy = signed_policy(&signer, "2026-02-07T22:00:00Z");
+        let err = store(policy, &signer)
+            .get_policy("fintech.mobile", "1.0.0", "prod", Platform::Ios)
+            .unwrap_err();
+        assert!(err.message.contains("in the future"));
+    }
+}
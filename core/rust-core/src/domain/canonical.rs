@@ -0,0 +1,71 @@
+/// Deterministic, length-prefixed binary encoding used to build signing payloads.
+///
+/// Every string is written as a big-endian `u32` length followed by its UTF-8
+/// bytes and every optional value is preceded by a presence byte, so two
+/// different field layouts can never produce the same byte sequence.
+pub struct CanonicalEncoder {
+    buf: Vec<u8>,
+}
+
+impl CanonicalEncoder {
+    pub fn new(tag: &str) -> Self {
+        let mut encoder = Self { buf: Vec::new() };
+        encoder.str(tag);
+        encoder
+    }
+
+    pub fn str(&mut self, value: &str) -> &mut Self {
+        self.u32(value.len() as u32);
+        self.buf.extend_from_slice(value.as_bytes());
+        self
+    }
+
+    pub fn opt_str(&mut self, value: Option<&str>) -> &mut Self {
+        match value {
+            Some(value) => {
+                self.present(true);
+                self.str(value)
+            }
+            None => self.present(false),
+        }
+    }
+
+    pub fn bool(&mut self, value: bool) -> &mut Self {
+        self.buf.push(u8::from(value));
+        self
+    }
+
+    pub fn opt_bool(&mut self, value: Option<bool>) -> &mut Self {
+        match value {
+            Some(value) => {
+                self.present(true);
+                self.bool(value)
+            }
+            None => self.present(false),
+        }
+    }
+
+    pub fn u32(&mut self, value: u32) -> &mut Self {
+        self.buf.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    pub fn opt_u32(&mut self, value: Option<u32>) -> &mut Self {
+        match value {
+            Some(value) => {
+                self.present(true);
+                self.u32(value)
+            }
+            None => self.present(false),
+        }
+    }
+
+    /// Marks whether an optional nested structure follows.
+    pub fn present(&mut self, value: bool) -> &mut Self {
+        self.bool(value)
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
@@ -1,4 +1,8 @@
+pub mod canonical;
 pub mod policy;
+pub mod policy_diff;
+pub mod policy_lint;
 pub mod risk;
 pub mod telemetry;
 pub mod pinning;
+pub mod version;
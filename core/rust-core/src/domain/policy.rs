@@ -1,5 +1,12 @@
-use crate::domain::risk::RiskScore;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::canonical::CanonicalEncoder;
+use crate::domain::risk::{RiskBreakdown, RiskScore, RiskWeights};
 use crate::domain::telemetry::{ActionContext, AttestationResult, AttestationStatus, IntegritySignals};
+use crate::domain::version::app_version_matches;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Decision {
@@ -9,88 +16,285 @@ pub enum Decision {
     Deny,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+impl Decision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Decision::Allow => "ALLOW",
+            Decision::StepUp => "STEP_UP",
+            Decision::Degrade => "DEGRADE",
+            Decision::Deny => "DENY",
+        }
+    }
+
+    /// Orders decisions from `Allow` (0) to `Deny` (3).
+    fn strictness(&self) -> u8 {
+        match self {
+            Decision::Allow => 0,
+            Decision::StepUp => 1,
+            Decision::Degrade => 2,
+            Decision::Deny => 3,
+        }
+    }
+}
+
+/// Maximum nesting of `all_of` / `any_of` / `not` accepted from the wire or FFI.
+pub const MAX_CONDITION_DEPTH: usize = 8;
+
+/// Leaf condition of a rule, as reported by [`PolicyEngine::explain`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConditionKind {
+    Attestation,
+    Jailbreak,
+    Root,
+    Debugger,
+    Hooking,
+    ProxyDetected,
+    AppVersion,
+    RiskScore,
+}
+
+impl ConditionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConditionKind::Attestation => "attestation",
+            ConditionKind::Jailbreak => "jailbreak",
+            ConditionKind::Root => "root",
+            ConditionKind::Debugger => "debugger",
+            ConditionKind::Hooking => "hooking",
+            ConditionKind::ProxyDetected => "proxy_detected",
+            ConditionKind::AppVersion => "app_version",
+            ConditionKind::RiskScore => "risk_score_gte",
+        }
+    }
+}
+
+/// Conditions of a rule. The flat fields are an implicit AND (the shorthand used
+/// by most rules); `all_of`, `any_of` and `not` compose nested condition groups
+/// and are ANDed with the flat fields.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct PolicyConditions {
     pub attestation_status: Option<AttestationStatus>,
+    pub jailbreak: Option<bool>,
+    pub root: Option<bool>,
     pub debugger: Option<bool>,
     pub hooking: Option<bool>,
     pub proxy_detected: Option<bool>,
+    /// Version range such as `>=1.2.0 <2.0.0` or `^1.4`; a bare version is an exact match.
     pub app_version: Option<String>,
     pub risk_score_gte: Option<u32>,
+    pub all_of: Vec<PolicyConditions>,
+    pub any_of: Vec<PolicyConditions>,
+    pub not: Option<Box<PolicyConditions>>,
 }
 
-impl Default for PolicyConditions {
-    fn default() -> Self {
-        Self {
-            attestation_status: None,
-            debugger: None,
-            hooking: None,
-            proxy_detected: None,
-            app_version: None,
-            risk_score_gte: None,
-        }
+impl PolicyConditions {
+    pub fn matches(
+        &self,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+        risk_score: RiskScore,
+        app_version: &str,
+    ) -> bool {
+        self.collect_matches(signals, attestation, risk_score, app_version, &mut Vec::new())
     }
-}
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct PolicyRule {
-    pub action: String,
-    pub decision: Decision,
-    pub conditions: PolicyConditions,
-}
+    /// Same as [`PolicyConditions::matches`], returning the leaf conditions that
+    /// held in a positive position. Conditions inside a `not` group are not
+    /// reported since they matched by being false.
+    pub fn matched_conditions(
+        &self,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+        risk_score: RiskScore,
+        app_version: &str,
+    ) -> Option<Vec<ConditionKind>> {
+        let mut matched = Vec::new();
+        self.collect_matches(signals, attestation, risk_score, app_version, &mut matched)
+            .then_some(matched)
+    }
 
-impl PolicyRule {
-    pub fn matches(
+    fn collect_matches(
         &self,
-        ctx: &ActionContext,
         signals: &IntegritySignals,
         attestation: Option<&AttestationResult>,
         risk_score: RiskScore,
         app_version: &str,
+        matched: &mut Vec<ConditionKind>,
     ) -> bool {
-        if self.action != ctx.name {
-            return false;
-        }
+        let mut record = |kind: ConditionKind| {
+            if !matched.contains(&kind) {
+                matched.push(kind);
+            }
+        };
 
-        if let Some(required) = &self.conditions.attestation_status {
+        if let Some(required) = &self.attestation_status {
             match attestation {
-                Some(att) if &att.status == required => {}
+                Some(att) if &att.status == required => record(ConditionKind::Attestation),
                 _ => return false,
             }
         }
 
-        if let Some(required) = self.conditions.debugger {
-            if signals.debugger != required {
-                return false;
+        for (kind, required, actual) in [
+            (ConditionKind::Jailbreak, self.jailbreak, signals.jailbreak),
+            (ConditionKind::Root, self.root, signals.root),
+            (ConditionKind::Debugger, self.debugger, signals.debugger),
+            (ConditionKind::Hooking, self.hooking, signals.hooking),
+            (ConditionKind::ProxyDetected, self.proxy_detected, signals.proxy_detected),
+        ] {
+            if let Some(required) = required {
+                if actual != required {
+                    return false;
+                }
+                record(kind);
             }
         }
 
-        if let Some(required) = self.conditions.hooking {
-            if signals.hooking != required {
+        if let Some(required) = &self.app_version {
+            if !app_version_matches(required, app_version) {
                 return false;
             }
+            record(ConditionKind::AppVersion);
         }
 
-        if let Some(required) = self.conditions.proxy_detected {
-            if signals.proxy_detected != required {
+        if let Some(min_score) = self.risk_score_gte {
+            if risk_score.value() < min_score {
                 return false;
             }
+            record(ConditionKind::RiskScore);
         }
 
-        if let Some(required) = &self.conditions.app_version {
-            if app_version != required {
+        for group in &self.all_of {
+            if !group.collect_matches(signals, attestation, risk_score, app_version, matched) {
                 return false;
             }
         }
 
-        if let Some(min_score) = self.conditions.risk_score_gte {
-            if risk_score.value() < min_score {
-                return false;
+        if !self.any_of.is_empty() {
+            let matched_group = self.any_of.iter().find_map(|group| {
+                group.matched_conditions(signals, attestation, risk_score, app_version)
+            });
+            match matched_group {
+                Some(kinds) => {
+                    for kind in kinds {
+                        if !matched.contains(&kind) {
+                            matched.push(kind);
+                        }
+                    }
+                }
+                None => return false,
             }
         }
 
+        if let Some(negated) = &self.not
+            && negated.matches(signals, attestation, risk_score, app_version)
+        {
+            return false;
+        }
+
         true
     }
+
+    /// Nesting depth of the condition tree; a flat condition set has depth 1.
+    pub fn depth(&self) -> usize {
+        let children = self
+            .all_of
+            .iter()
+            .chain(self.any_of.iter())
+            .chain(self.not.as_deref())
+            .map(PolicyConditions::depth)
+            .max()
+            .unwrap_or(0);
+        children + 1
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyRule {
+    pub action: String,
+    pub decision: Decision,
+    pub conditions: PolicyConditions,
+}
+
+impl PolicyRule {
+    pub fn matches(
+        &self,
+        ctx: &ActionContext,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+        risk_score: RiskScore,
+        app_version: &str,
+    ) -> bool {
+        if self.action != ctx.name {
+            return false;
+        }
+
+        self.conditions
+            .matches(signals, attestation, risk_score, app_version)
+    }
+}
+
+/// Decision returned when no rule matches: the per-action default if one is
+/// set, otherwise the policy-wide one. The default is `Allow` (fail-open).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyDefaults {
+    pub decision: Decision,
+    pub actions: BTreeMap<String, Decision>,
+}
+
+impl Default for PolicyDefaults {
+    fn default() -> Self {
+        Self {
+            decision: Decision::Allow,
+            actions: BTreeMap::new(),
+        }
+    }
+}
+
+impl PolicyDefaults {
+    /// Denies every action that no rule matched.
+    pub fn fail_closed() -> Self {
+        Self {
+            decision: Decision::Deny,
+            actions: BTreeMap::new(),
+        }
+    }
+
+    pub fn for_action(&self, action: &str) -> Decision {
+        self.actions
+            .get(action)
+            .unwrap_or(&self.decision)
+            .clone()
+    }
+}
+
+/// Incident-response override (kill switch) that forces a decision ahead of
+/// the rules until it expires, e.g. denying every write while the backend is
+/// read-only or disabling a single feature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyOverride {
+    /// Actions the override applies to; empty applies it to every action.
+    pub actions: Vec<String>,
+    /// Actions left to the normal rules, such as reads in read-only mode.
+    pub except_actions: Vec<String>,
+    pub decision: Decision,
+    /// RFC 3339 timestamp after which the override no longer applies.
+    pub expires_at: String,
+    pub reason: Option<String>,
+}
+
+impl PolicyOverride {
+    pub fn applies_to(&self, action: &str) -> bool {
+        let in_scope = self.actions.is_empty() || self.actions.iter().any(|name| name == action);
+        in_scope && !self.except_actions.iter().any(|name| name == action)
+    }
+
+    /// An expiry that does not parse never activates the override; the
+    /// policy-service rejects such overrides before signing.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        DateTime::parse_from_rfc3339(&self.expires_at)
+            .map(|expires_at| expires_at > now)
+            .unwrap_or(false)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -100,6 +304,194 @@ pub struct PolicySet {
     pub app_version: String,
     pub env: String,
     pub rules: Vec<PolicyRule>,
+    pub defaults: PolicyDefaults,
+    /// Weights for computing the risk score this policy is evaluated against;
+    /// `None` leaves scoring to the caller's `RiskScorer`.
+    pub risk_weights: Option<RiskWeights>,
+    /// Kill-switch overrides evaluated before `rules`.
+    pub overrides: Vec<PolicyOverride>,
+}
+
+impl PolicySet {
+    /// Canonical bytes covered by the policy-service signature. `issued_at` is part of
+    /// the payload so a stale policy cannot be replayed with a fresh timestamp.
+    pub fn signing_payload(&self, issued_at: &str) -> Vec<u8> {
+        let mut encoder = CanonicalEncoder::new("panther.policy.v2");
+        encoder
+            .str(&self.policy_id)
+            .str(&self.app_id)
+            .str(&self.app_version)
+            .str(&self.env)
+            .str(issued_at)
+            .u32(self.rules.len() as u32);
+
+        for rule in &self.rules {
+            encoder.str(&rule.action).str(rule.decision.as_str());
+            encode_conditions(&mut encoder, &rule.conditions);
+        }
+
+        encoder
+            .str(self.defaults.decision.as_str())
+            .u32(self.defaults.actions.len() as u32);
+        for (action, decision) in &self.defaults.actions {
+            encoder.str(action).str(decision.as_str());
+        }
+
+        match &self.risk_weights {
+            Some(weights) => {
+                encoder
+                    .present(true)
+                    .u32(weights.jailbreak)
+                    .u32(weights.root)
+                    .u32(weights.debugger)
+                    .u32(weights.hooking)
+                    .u32(weights.proxy_detected)
+                    .u32(weights.attestation_fail)
+                    .u32(weights.attestation_unknown)
+                    .u32(weights.finding_low)
+                    .u32(weights.finding_medium)
+                    .u32(weights.finding_high)
+                    .u32(weights.finding_critical)
+                    .opt_u32(weights.decay_half_life_secs)
+                    .u32(weights.finding_category_pct.len() as u32);
+                for (category, pct) in &weights.finding_category_pct {
+                    encoder.str(category).u32(*pct);
+                }
+            }
+            None => {
+                encoder.present(false);
+            }
+        }
+
+        encoder.u32(self.overrides.len() as u32);
+        for policy_override in &self.overrides {
+            encoder.u32(policy_override.actions.len() as u32);
+            for action in &policy_override.actions {
+                encoder.str(action);
+            }
+            encoder.u32(policy_override.except_actions.len() as u32);
+            for action in &policy_override.except_actions {
+                encoder.str(action);
+            }
+            encoder
+                .str(policy_override.decision.as_str())
+                .str(&policy_override.expires_at)
+                .opt_str(policy_override.reason.as_deref());
+        }
+
+        encoder.finish()
+    }
+
+    /// `panther.policy.v1` bytes, signed by policy-services that predate condition
+    /// groups, `jailbreak`/`root`, defaults, risk weights and overrides. Accepted
+    /// during the rollout of v2 only for policies using none of them, so nothing
+    /// the legacy layout leaves out goes unsigned; `None` otherwise.
+    pub fn legacy_signing_payload(&self, issued_at: &str) -> Option<Vec<u8>> {
+        let legacy_rules = self.rules.iter().all(|rule| {
+            let conditions = &rule.conditions;
+            conditions.jailbreak.is_none()
+                && conditions.root.is_none()
+                && conditions.all_of.is_empty()
+                && conditions.any_of.is_empty()
+                && conditions.not.is_none()
+        });
+        if !legacy_rules
+            || self.defaults != PolicyDefaults::default()
+            || self.risk_weights.is_some()
+            || !self.overrides.is_empty()
+        {
+            return None;
+        }
+
+        let mut encoder = CanonicalEncoder::new("panther.policy.v1");
+        encoder
+            .str(&self.policy_id)
+            .str(&self.app_id)
+            .str(&self.app_version)
+            .str(&self.env)
+            .str(issued_at)
+            .u32(self.rules.len() as u32);
+        for rule in &self.rules {
+            let conditions = &rule.conditions;
+            encoder
+                .str(&rule.action)
+                .str(rule.decision.as_str())
+                .opt_str(conditions.attestation_status.as_ref().map(AttestationStatus::as_str))
+                .opt_bool(conditions.debugger)
+                .opt_bool(conditions.hooking)
+                .opt_bool(conditions.proxy_detected)
+                .opt_str(conditions.app_version.as_deref())
+                .opt_u32(conditions.risk_score_gte);
+        }
+        Some(encoder.finish())
+    }
+}
+
+fn encode_conditions(encoder: &mut CanonicalEncoder, conditions: &PolicyConditions) {
+    encoder
+        .opt_str(conditions.attestation_status.as_ref().map(AttestationStatus::as_str))
+        .opt_bool(conditions.jailbreak)
+        .opt_bool(conditions.root)
+        .opt_bool(conditions.debugger)
+        .opt_bool(conditions.hooking)
+        .opt_bool(conditions.proxy_detected)
+        .opt_str(conditions.app_version.as_deref())
+        .opt_u32(conditions.risk_score_gte);
+
+    encoder.u32(conditions.all_of.len() as u32);
+    for group in &conditions.all_of {
+        encode_conditions(encoder, group);
+    }
+    encoder.u32(conditions.any_of.len() as u32);
+    for group in &conditions.any_of {
+        encode_conditions(encoder, group);
+    }
+    match &conditions.not {
+        Some(negated) => {
+            encoder.present(true);
+            encode_conditions(encoder, negated);
+        }
+        None => {
+            encoder.present(false);
+        }
+    }
+}
+
+/// A policy as distributed by the policy-service, before or after its signature
+/// has been checked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedPolicy {
+    pub policy: PolicySet,
+    pub signature: String,
+    pub issued_at: String,
+}
+
+impl SignedPolicy {
+    pub fn signing_payload(&self) -> Vec<u8> {
+        self.policy.signing_payload(&self.issued_at)
+    }
+
+    pub fn legacy_signing_payload(&self) -> Option<Vec<u8>> {
+        self.policy.legacy_signing_payload(&self.issued_at)
+    }
+}
+
+/// Outcome of a policy evaluation with the reason behind it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Evaluation {
+    pub decision: Decision,
+    /// Index in `PolicySet::rules` of the rule that fired.
+    pub rule_index: Option<usize>,
+    pub matched_conditions: Vec<ConditionKind>,
+    pub risk_score: RiskScore,
+    /// What contributed to `risk_score`; opaque when the caller passed a bare
+    /// score to [`PolicyEngine::explain`].
+    pub risk_breakdown: RiskBreakdown,
+    /// True when no rule matched and the policy default was returned.
+    pub default_applied: bool,
+    /// Index in `PolicySet::overrides` of the kill switch that forced the
+    /// decision; rules are not consulted when set.
+    pub override_index: Option<usize>,
 }
 
 pub struct PolicyEngine;
@@ -112,12 +504,79 @@ impl PolicyEngine {
         attestation: Option<&AttestationResult>,
         risk_score: RiskScore,
     ) -> Decision {
-        for rule in &policy.rules {
-            if rule.matches(ctx, signals, attestation, risk_score, &policy.app_version) {
-                return rule.decision.clone();
+        Self::explain(policy, ctx, signals, attestation, risk_score).decision
+    }
+
+    pub fn explain(
+        policy: &PolicySet,
+        ctx: &ActionContext,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+        risk_score: RiskScore,
+    ) -> Evaluation {
+        Self::explain_at(policy, ctx, signals, attestation, risk_score, Utc::now())
+    }
+
+    /// Same as [`PolicyEngine::explain`] with overrides checked for expiry
+    /// against `now`. When several active overrides apply, targeted or
+    /// app-wide, the strictest decision wins; ties go to the first listed.
+    pub fn explain_at(
+        policy: &PolicySet,
+        ctx: &ActionContext,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+        risk_score: RiskScore,
+        now: DateTime<Utc>,
+    ) -> Evaluation {
+        let forced = policy
+            .overrides
+            .iter()
+            .enumerate()
+            .filter(|(_, candidate)| candidate.is_active(now) && candidate.applies_to(&ctx.name))
+            .max_by_key(|(index, candidate)| (candidate.decision.strictness(), Reverse(*index)));
+        if let Some((index, policy_override)) = forced {
+            return Evaluation {
+                decision: policy_override.decision.clone(),
+                rule_index: None,
+                matched_conditions: Vec::new(),
+                risk_score,
+                risk_breakdown: RiskBreakdown::opaque(risk_score),
+                default_applied: false,
+                override_index: Some(index),
+            };
+        }
+
+        for (index, rule) in policy.rules.iter().enumerate() {
+            if rule.action != ctx.name {
+                continue;
+            }
+            if let Some(matched_conditions) = rule.conditions.matched_conditions(
+                signals,
+                attestation,
+                risk_score,
+                &policy.app_version,
+            ) {
+                return Evaluation {
+                    decision: rule.decision.clone(),
+                    rule_index: Some(index),
+                    matched_conditions,
+                    risk_score,
+                    risk_breakdown: RiskBreakdown::opaque(risk_score),
+                    default_applied: false,
+                    override_index: None,
+                };
             }
         }
-        Decision::Allow
+
+        Evaluation {
+            decision: policy.defaults.for_action(&ctx.name),
+            rule_index: None,
+            matched_conditions: Vec::new(),
+            risk_score,
+            risk_breakdown: RiskBreakdown::opaque(risk_score),
+            default_applied: true,
+            override_index: None,
+        }
     }
 }
 
@@ -149,6 +608,7 @@ mod tests {
             provider: AttestationProvider::AppAttest,
             status,
             timestamp: None,
+            challenge: None,
         }
     }
 
@@ -173,6 +633,38 @@ mod tests {
         assert!(!rule.matches(&ctx, &signals, None, RiskScore::new(5), "1.0.0"));
     }
 
+    #[test]
+    fn rule_matches_on_root_and_jailbreak_signals() {
+        let rule = PolicyRule {
+            action: "transfer".to_string(),
+            decision: Decision::Deny,
+            conditions: PolicyConditions {
+                any_of: vec![
+                    PolicyConditions {
+                        root: Some(true),
+                        ..PolicyConditions::default()
+                    },
+                    PolicyConditions {
+                        jailbreak: Some(true),
+                        ..PolicyConditions::default()
+                    },
+                ],
+                ..PolicyConditions::default()
+            },
+        };
+
+        let ctx = action("transfer");
+        let mut signals = base_signals();
+        assert!(!rule.matches(&ctx, &signals, None, RiskScore::new(0), "1.0.0"));
+
+        signals.root = true;
+        assert!(rule.matches(&ctx, &signals, None, RiskScore::new(0), "1.0.0"));
+
+        signals.root = false;
+        signals.jailbreak = true;
+        assert!(rule.matches(&ctx, &signals, None, RiskScore::new(0), "1.0.0"));
+    }
+
     #[test]
     fn rule_matches_attestation_and_risk_score() {
         let rule = PolicyRule {
@@ -193,6 +685,85 @@ mod tests {
         assert!(!rule.matches(&ctx, &signals, Some(&att), RiskScore::new(50), "1.0.0"));
     }
 
+    #[test]
+    fn condition_tree_combines_all_any_and_not() {
+        // deny if (debugger OR hooking) AND NOT attestation pass
+        let rule = PolicyRule {
+            action: "transfer".to_string(),
+            decision: Decision::Deny,
+            conditions: PolicyConditions {
+                any_of: vec![
+                    PolicyConditions {
+                        debugger: Some(true),
+                        ..PolicyConditions::default()
+                    },
+                    PolicyConditions {
+                        hooking: Some(true),
+                        ..PolicyConditions::default()
+                    },
+                ],
+                not: Some(Box::new(PolicyConditions {
+                    attestation_status: Some(AttestationStatus::Pass),
+                    ..PolicyConditions::default()
+                })),
+                ..PolicyConditions::default()
+            },
+        };
+
+        let ctx = action("transfer");
+        let mut signals = base_signals();
+        let pass = attestation(AttestationStatus::Pass);
+        let fail = attestation(AttestationStatus::Fail);
+
+        assert!(!rule.matches(&ctx, &signals, Some(&fail), RiskScore::new(0), "1.0.0"));
+
+        signals.hooking = true;
+        assert!(rule.matches(&ctx, &signals, Some(&fail), RiskScore::new(0), "1.0.0"));
+        assert!(rule.matches(&ctx, &signals, None, RiskScore::new(0), "1.0.0"));
+        assert!(!rule.matches(&ctx, &signals, Some(&pass), RiskScore::new(0), "1.0.0"));
+    }
+
+    #[test]
+    fn flat_conditions_are_anded_with_all_of_groups() {
+        let conditions = PolicyConditions {
+            risk_score_gte: Some(50),
+            all_of: vec![PolicyConditions {
+                proxy_detected: Some(true),
+                ..PolicyConditions::default()
+            }],
+            ..PolicyConditions::default()
+        };
+
+        let mut signals = base_signals();
+        signals.proxy_detected = true;
+        assert!(conditions.matches(&signals, None, RiskScore::new(60), "1.0.0"));
+        assert!(!conditions.matches(&signals, None, RiskScore::new(40), "1.0.0"));
+
+        signals.proxy_detected = false;
+        assert!(!conditions.matches(&signals, None, RiskScore::new(60), "1.0.0"));
+        assert_eq!(conditions.depth(), 2);
+    }
+
+    #[test]
+    fn app_version_condition_accepts_ranges() {
+        let conditions = PolicyConditions {
+            app_version: Some(">=1.2.0 <2.0.0".to_string()),
+            ..PolicyConditions::default()
+        };
+
+        let signals = base_signals();
+        assert!(conditions.matches(&signals, None, RiskScore::new(0), "1.4.3"));
+        assert!(!conditions.matches(&signals, None, RiskScore::new(0), "1.1.9"));
+        assert!(!conditions.matches(&signals, None, RiskScore::new(0), "2.0.0"));
+
+        let exact = PolicyConditions {
+            app_version: Some("1.0.0".to_string()),
+            ..PolicyConditions::default()
+        };
+        assert!(exact.matches(&signals, None, RiskScore::new(0), "1.0.0"));
+        assert!(!exact.matches(&signals, None, RiskScore::new(0), "1.0.1"));
+    }
+
     #[test]
     fn policy_engine_returns_first_matching_rule() {
         let policy = PolicySet {
@@ -200,6 +771,9 @@ mod tests {
             app_id: "fintech.mobile".to_string(),
             app_version: "1.0.0".to_string(),
             env: "local".to_string(),
+            defaults: PolicyDefaults::default(),
+            risk_weights: None,
+            overrides: Vec::new(),
             rules: vec![
                 PolicyRule {
                     action: "view_card".to_string(),
@@ -225,6 +799,71 @@ mod tests {
         assert_eq!(decision, Decision::Deny);
     }
 
+    #[test]
+    fn explain_reports_rule_index_and_matched_conditions() {
+        let policy = PolicySet {
+            policy_id: "policy".to_string(),
+            app_id: "fintech.mobile".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "local".to_string(),
+            defaults: PolicyDefaults::default(),
+            risk_weights: None,
+            overrides: Vec::new(),
+            rules: vec![
+                PolicyRule {
+                    action: "transfer".to_string(),
+                    decision: Decision::StepUp,
+                    conditions: PolicyConditions {
+                        debugger: Some(true),
+                        ..PolicyConditions::default()
+                    },
+                },
+                PolicyRule {
+                    action: "transfer".to_string(),
+                    decision: Decision::Deny,
+                    conditions: PolicyConditions {
+                        risk_score_gte: Some(70),
+                        any_of: vec![
+                            PolicyConditions {
+                                root: Some(true),
+                                ..PolicyConditions::default()
+                            },
+                            PolicyConditions {
+                                proxy_detected: Some(true),
+                                ..PolicyConditions::default()
+                            },
+                        ],
+                        not: Some(Box::new(PolicyConditions {
+                            hooking: Some(true),
+                            ..PolicyConditions::default()
+                        })),
+                        ..PolicyConditions::default()
+                    },
+                },
+            ],
+        };
+
+        let ctx = action("transfer");
+        let mut signals = base_signals();
+        signals.proxy_detected = true;
+
+        let evaluation = PolicyEngine::explain(&policy, &ctx, &signals, None, RiskScore::new(80));
+        assert_eq!(evaluation.decision, Decision::Deny);
+        assert_eq!(evaluation.rule_index, Some(1));
+        assert_eq!(
+            evaluation.matched_conditions,
+            vec![ConditionKind::RiskScore, ConditionKind::ProxyDetected]
+        );
+        assert_eq!(evaluation.risk_score, RiskScore::new(80));
+        assert!(!evaluation.default_applied);
+
+        let evaluation = PolicyEngine::explain(&policy, &ctx, &signals, None, RiskScore::new(10));
+        assert_eq!(evaluation.decision, Decision::Allow);
+        assert_eq!(evaluation.rule_index, None);
+        assert!(evaluation.matched_conditions.is_empty());
+        assert!(evaluation.default_applied);
+    }
+
     #[test]
     fn policy_engine_defaults_to_allow_when_no_match() {
         let policy = PolicySet {
@@ -232,6 +871,9 @@ mod tests {
             app_id: "fintech.mobile".to_string(),
             app_version: "1.0.0".to_string(),
             env: "local".to_string(),
+            defaults: PolicyDefaults::default(),
+            risk_weights: None,
+            overrides: Vec::new(),
             rules: vec![PolicyRule {
                 action: "transfer".to_string(),
                 decision: Decision::Deny,
@@ -248,4 +890,166 @@ mod tests {
         let decision = PolicyEngine::evaluate(&policy, &ctx, &signals, None, RiskScore::new(10));
         assert_eq!(decision, Decision::Allow);
     }
+
+    #[test]
+    fn policy_defaults_apply_per_action_before_policy_wide() {
+        let mut policy = PolicySet {
+            policy_id: "policy".to_string(),
+            app_id: "fintech.mobile".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "local".to_string(),
+            defaults: PolicyDefaults::default(),
+            risk_weights: None,
+            overrides: Vec::new(),
+            rules: Vec::new(),
+        };
+        let unsigned_defaults = policy.signing_payload("2026-02-06T21:00:00Z");
+
+        policy.defaults = PolicyDefaults::fail_closed();
+        policy
+            .defaults
+            .actions
+            .insert("view_card".to_string(), Decision::Degrade);
+        assert_ne!(policy.signing_payload("2026-02-06T21:00:00Z"), unsigned_defaults);
+
+        let signals = base_signals();
+        let transfer =
+            PolicyEngine::explain(&policy, &action("transfer"), &signals, None, RiskScore::new(0));
+        assert_eq!(transfer.decision, Decision::Deny);
+        assert!(transfer.default_applied);
+
+        let view_card =
+            PolicyEngine::explain(&policy, &action("view_card"), &signals, None, RiskScore::new(0));
+        assert_eq!(view_card.decision, Decision::Degrade);
+    }
+
+    fn kill_switch(actions: &[&str], decision: Decision, expires_at: &str) -> PolicyOverride {
+        PolicyOverride {
+            actions: actions.iter().map(|action| action.to_string()).collect(),
+            except_actions: Vec::new(),
+            decision,
+            expires_at: expires_at.to_string(),
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn active_overrides_win_over_rules_until_they_expire() {
+        let mut policy = PolicySet {
+            policy_id: "policy".to_string(),
+            app_id: "fintech.mobile".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "local".to_string(),
+            defaults: PolicyDefaults::default(),
+            risk_weights: None,
+            overrides: Vec::new(),
+            rules: vec![PolicyRule {
+                action: "transfer".to_string(),
+                decision: Decision::Allow,
+                conditions: PolicyConditions::default(),
+            }],
+        };
+        let unsigned_overrides = policy.signing_payload("2026-02-06T21:00:00Z");
+
+        let mut read_only = kill_switch(&[], Decision::Deny, "2026-02-07T00:00:00Z");
+        read_only.except_actions = vec!["view_card".to_string()];
+        policy.overrides = vec![
+            read_only,
+            kill_switch(&["transfer"], Decision::StepUp, "2026-02-07T00:00:00Z"),
+        ];
+        assert_ne!(policy.signing_payload("2026-02-06T21:00:00Z"), unsigned_overrides);
+
+        let signals = base_signals();
+        let during: DateTime<Utc> = "2026-02-06T22:00:00Z".parse().unwrap();
+        let explain = |name: &str, now| {
+            PolicyEngine::explain_at(&policy, &action(name), &signals, None, RiskScore::new(0), now)
+        };
+
+        let transfer = explain("transfer", during);
+        assert_eq!(transfer.decision, Decision::Deny);
+        assert_eq!(transfer.override_index, Some(0));
+        assert_eq!(transfer.rule_index, None);
+        assert!(!transfer.default_applied);
+
+        let login = explain("login", during);
+        assert_eq!(login.decision, Decision::Deny);
+        assert_eq!(login.override_index, Some(0));
+
+        let view_card = explain("view_card", during);
+        assert_eq!(view_card.decision, Decision::Allow);
+        assert_eq!(view_card.override_index, None);
+        assert!(view_card.default_applied);
+
+        let after = explain("transfer", "2026-02-07T00:00:00Z".parse().unwrap());
+        assert_eq!(after.decision, Decision::Allow);
+        assert_eq!(after.rule_index, Some(0));
+        assert_eq!(after.override_index, None);
+    }
+
+    #[test]
+    fn the_strictest_active_override_wins() {
+        let mut policy = PolicySet {
+            policy_id: "policy".to_string(),
+            app_id: "fintech.mobile".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "local".to_string(),
+            defaults: PolicyDefaults::default(),
+            risk_weights: None,
+            overrides: vec![
+                kill_switch(&["transfer"], Decision::StepUp, "2026-02-07T00:00:00Z"),
+                kill_switch(&[], Decision::Deny, "2026-02-07T00:00:00Z"),
+                kill_switch(&["transfer"], Decision::Degrade, "2026-02-07T00:00:00Z"),
+            ],
+            rules: Vec::new(),
+        };
+        let signals = base_signals();
+        let during: DateTime<Utc> = "2026-02-06T22:00:00Z".parse().unwrap();
+
+        let transfer =
+            PolicyEngine::explain_at(&policy, &action("transfer"), &signals, None, RiskScore::new(0), during);
+        assert_eq!(transfer.decision, Decision::Deny);
+        assert_eq!(transfer.override_index, Some(1));
+
+        policy.overrides[1].decision = Decision::StepUp;
+        let transfer =
+            PolicyEngine::explain_at(&policy, &action("transfer"), &signals, None, RiskScore::new(0), during);
+        assert_eq!(transfer.decision, Decision::Degrade);
+        assert_eq!(transfer.override_index, Some(2));
+
+        let login =
+            PolicyEngine::explain_at(&policy, &action("login"), &signals, None, RiskScore::new(0), during);
+        assert_eq!(login.decision, Decision::StepUp);
+        assert_eq!(login.override_index, Some(1));
+    }
+
+    #[test]
+    fn risk_weights_are_covered_by_the_signature() {
+        let mut policy = PolicySet {
+            policy_id: "policy".to_string(),
+            app_id: "fintech.mobile".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "local".to_string(),
+            defaults: PolicyDefaults::default(),
+            risk_weights: None,
+            overrides: Vec::new(),
+            rules: Vec::new(),
+        };
+        let unweighted = policy.signing_payload("2026-02-06T21:00:00Z");
+
+        policy.risk_weights = Some(RiskWeights::default());
+        let weighted = policy.signing_payload("2026-02-06T21:00:00Z");
+        assert_ne!(weighted, unweighted);
+
+        policy.risk_weights = Some(RiskWeights {
+            debugger: 0,
+            ..RiskWeights::default()
+        });
+        assert_ne!(policy.signing_payload("2026-02-06T21:00:00Z"), weighted);
+
+        policy.defaults = PolicyDefaults::fail_closed();
+        policy.risk_weights = None;
+        let defaults_only = policy.signing_payload("2026-02-06T21:00:00Z");
+        policy.risk_weights = Some(RiskWeights::default());
+        assert_ne!(policy.signing_payload("2026-02-06T21:00:00Z"), defaults_only);
+    }
 }
@@ -0,0 +1,233 @@
+use crate::domain::policy::PolicyRule;
+
+/// How a rule differs between two versions of a policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleChangeKind {
+    Added,
+    Removed,
+    /// The rule moved relative to the other rules for its action. Rules only
+    /// compete with rules of the same action, and the first match wins, so
+    /// this can change decisions even though the rule itself is unchanged.
+    Reordered,
+    /// Same action and decision, different conditions.
+    ConditionsChanged,
+    /// Same action and conditions, different decision.
+    DecisionChanged,
+}
+
+impl RuleChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleChangeKind::Added => "added",
+            RuleChangeKind::Removed => "removed",
+            RuleChangeKind::Reordered => "reordered",
+            RuleChangeKind::ConditionsChanged => "conditions_changed",
+            RuleChangeKind::DecisionChanged => "decision_changed",
+        }
+    }
+}
+
+/// One difference between the rule lists of two policy versions. Indexes
+/// are positions in the respective `PolicySet::rules`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleChange {
+    pub kind: RuleChangeKind,
+    pub action: String,
+    pub from_index: Option<usize>,
+    pub to_index: Option<usize>,
+    pub before: Option<PolicyRule>,
+    pub after: Option<PolicyRule>,
+}
+
+/// Rule-level diff from `from` to `to`.
+///
+/// Rules have no identity of their own, so they are paired in three passes,
+/// each in list order: identical rules, then rules of the same action with the
+/// same conditions (a decision change), then rules of the same action with the
+/// same decision (a conditions change). Unpaired rules are removed or added. A
+/// paired rule is also reported as reordered when its rank among the paired
+/// rules of its action differs between the versions.
+pub fn diff_rules(from: &[PolicyRule], to: &[PolicyRule]) -> Vec<RuleChange> {
+    let mut pairs: Vec<(usize, usize)> = Vec::new();
+    let mut from_paired = vec![false; from.len()];
+    let mut to_paired = vec![false; to.len()];
+
+    let passes: [fn(&PolicyRule, &PolicyRule) -> bool; 3] = [
+        |a, b| a == b,
+        |a, b| a.action == b.action && a.conditions == b.conditions,
+        |a, b| a.action == b.action && a.decision == b.decision,
+    ];
+    for same in passes {
+        for (from_index, rule) in from.iter().enumerate() {
+            if from_paired[from_index] {
+                continue;
+            }
+            let candidate = to
+                .iter()
+                .enumerate()
+                .find(|(to_index, other)| !to_paired[*to_index] && same(rule, other));
+            if let Some((to_index, _)) = candidate {
+                from_paired[from_index] = true;
+                to_paired[to_index] = true;
+                pairs.push((from_index, to_index));
+            }
+        }
+    }
+
+    let mut changes = Vec::new();
+    for &(from_index, to_index) in &pairs {
+        let (before, after) = (&from[from_index], &to[to_index]);
+        let kind = if before.decision != after.decision {
+            Some(RuleChangeKind::DecisionChanged)
+        } else if before.conditions != after.conditions {
+            Some(RuleChangeKind::ConditionsChanged)
+        } else {
+            None
+        };
+        let rank = |index: usize, by_to: bool| {
+            pairs
+                .iter()
+                .filter(|(f, t)| {
+                    from[*f].action == before.action && if by_to { *t < index } else { *f < index }
+                })
+                .count()
+        };
+        let moved = rank(from_index, false) != rank(to_index, true);
+
+        for kind in kind
+            .into_iter()
+            .chain(moved.then_some(RuleChangeKind::Reordered))
+        {
+            changes.push(RuleChange {
+                kind,
+                action: before.action.clone(),
+                from_index: Some(from_index),
+                to_index: Some(to_index),
+                before: Some(before.clone()),
+                after: Some(after.clone()),
+            });
+        }
+    }
+
+    for (from_index, rule) in from.iter().enumerate() {
+        if !from_paired[from_index] {
+            changes.push(RuleChange {
+                kind: RuleChangeKind::Removed,
+                action: rule.action.clone(),
+                from_index: Some(from_index),
+                to_index: None,
+                before: Some(rule.clone()),
+                after: None,
+            });
+        }
+    }
+    for (to_index, rule) in to.iter().enumerate() {
+        if !to_paired[to_index] {
+            changes.push(RuleChange {
+                kind: RuleChangeKind::Added,
+                action: rule.action.clone(),
+                from_index: None,
+                to_index: Some(to_index),
+                before: None,
+                after: Some(rule.clone()),
+            });
+        }
+    }
+
+    // By position in `to` (removals at their old position, before additions).
+    changes.sort_by_key(|change| {
+        (
+            change.to_index.or(change.from_index),
+            change.to_index.is_some(),
+        )
+    });
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::policy::{Decision, PolicyConditions};
+
+    fn rule(action: &str, decision: Decision, debugger: Option<bool>) -> PolicyRule {
+        PolicyRule {
+            action: action.to_string(),
+            decision,
+            conditions: PolicyConditions {
+                debugger,
+                ..PolicyConditions::default()
+            },
+        }
+    }
+
+    fn kinds(changes: &[RuleChange]) -> Vec<(RuleChangeKind, Option<usize>, Option<usize>)> {
+        changes
+            .iter()
+            .map(|change| (change.kind, change.from_index, change.to_index))
+            .collect()
+    }
+
+    #[test]
+    fn identical_rules_have_no_changes() {
+        let rules = vec![
+            rule("login", Decision::StepUp, Some(true)),
+            rule("transfer", Decision::Deny, None),
+        ];
+        assert!(diff_rules(&rules, &rules).is_empty());
+    }
+
+    #[test]
+    fn pairs_rules_by_identity_then_conditions_then_decision() {
+        let from = vec![
+            rule("login", Decision::StepUp, Some(true)),
+            rule("transfer", Decision::StepUp, Some(true)),
+            rule("view_card", Decision::Degrade, Some(true)),
+            rule("change_password", Decision::Deny, None),
+        ];
+        let to = vec![
+            rule("login", Decision::StepUp, Some(true)),
+            rule("transfer", Decision::Deny, Some(true)),
+            rule("view_card", Decision::Degrade, Some(false)),
+            rule("add_beneficiary", Decision::StepUp, None),
+        ];
+
+        let changes = diff_rules(&from, &to);
+        assert_eq!(
+            kinds(&changes),
+            vec![
+                (RuleChangeKind::DecisionChanged, Some(1), Some(1)),
+                (RuleChangeKind::ConditionsChanged, Some(2), Some(2)),
+                (RuleChangeKind::Removed, Some(3), None),
+                (RuleChangeKind::Added, None, Some(3)),
+            ]
+        );
+        assert_eq!(
+            changes[0].before.as_ref().map(|rule| &rule.decision),
+            Some(&Decision::StepUp)
+        );
+        assert_eq!(
+            changes[0].after.as_ref().map(|rule| &rule.decision),
+            Some(&Decision::Deny)
+        );
+    }
+
+    #[test]
+    fn reports_reordering_only_within_an_action() {
+        let catch_all = rule("transfer", Decision::Allow, None);
+        let strict = rule("transfer", Decision::Deny, Some(true));
+        let login = rule("login", Decision::StepUp, Some(true));
+
+        let from = vec![strict.clone(), catch_all.clone(), login.clone()];
+        let to = vec![login.clone(), catch_all.clone(), strict.clone()];
+        assert_eq!(
+            kinds(&diff_rules(&from, &to)),
+            vec![
+                (RuleChangeKind::Reordered, Some(1), Some(1)),
+                (RuleChangeKind::Reordered, Some(0), Some(2)),
+            ]
+        );
+
+        let to = vec![login, strict, catch_all];
+        assert!(diff_rules(&from, &to).is_empty());
+    }
+}
@@ -0,0 +1,589 @@
+use std::collections::BTreeSet;
+
+use crate::domain::policy::{PolicyConditions, PolicyRule, PolicySet};
+use crate::domain::risk::MAX_RISK_SCORE;
+use crate::domain::telemetry::AttestationStatus;
+use crate::domain::version::{Version, VersionRange};
+
+/// Sensitive actions the SDKs gate out of the box. Apps with their own
+/// actions pass them alongside these to [`lint_policy`].
+pub const KNOWN_ACTIONS: &[&str] = &[
+    "login",
+    "refresh_token",
+    "transfer",
+    "pix",
+    "add_beneficiary",
+    "view_card",
+    "change_password",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintSeverity {
+    /// The policy does not do what it says; the rule can never decide anything.
+    Error,
+    /// Redundant or suspicious, but evaluation is unaffected.
+    Warning,
+}
+
+impl LintSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintSeverity::Error => "error",
+            LintSeverity::Warning => "warning",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintKind {
+    /// An earlier rule for the same action matches whenever this one does.
+    ShadowedRule,
+    /// Same action, decision and conditions as an earlier rule.
+    DuplicateRule,
+    /// The conditions can never hold together (e.g. `debugger: true` and
+    /// `not: {debugger: true}`).
+    ContradictoryConditions,
+    /// The action is neither a known action nor one passed by the caller.
+    UnknownAction,
+    /// `risk_score_gte` is above the maximum risk score.
+    ImpossibleRiskScore,
+}
+
+impl LintKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintKind::ShadowedRule => "shadowed_rule",
+            LintKind::DuplicateRule => "duplicate_rule",
+            LintKind::ContradictoryConditions => "contradictory_conditions",
+            LintKind::UnknownAction => "unknown_action",
+            LintKind::ImpossibleRiskScore => "impossible_risk_score",
+        }
+    }
+}
+
+/// One problem found by [`lint_policy`]. `rule_index` is the position in
+/// `PolicySet::rules`; it is `None` for actions named only in the defaults or
+/// overrides. `related_index` is the earlier rule that shadows or duplicates it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyLint {
+    pub kind: LintKind,
+    pub severity: LintSeverity,
+    pub action: String,
+    pub rule_index: Option<usize>,
+    pub related_index: Option<usize>,
+    pub message: String,
+}
+
+/// Static checks for mistakes `PolicyEngine::evaluate` would silently accept.
+///
+/// Rules are first-match per action, so a rule is reported as shadowed when
+/// an earlier rule for the same action matches every context it matches. The
+/// analysis is conservative: it only reports what it can prove from the
+/// condition tree, so a clean result does not mean every rule is reachable.
+/// Findings are ordered by rule position.
+pub fn lint_policy(policy: &PolicySet, extra_actions: &[&str]) -> Vec<PolicyLint> {
+    let is_known =
+        |action: &str| KNOWN_ACTIONS.contains(&action) || extra_actions.contains(&action);
+    let mut lints = Vec::new();
+    let mut unknown_reported = BTreeSet::new();
+
+    for (index, rule) in policy.rules.iter().enumerate() {
+        if !is_known(&rule.action) && unknown_reported.insert(rule.action.as_str()) {
+            lints.push(unknown_action(&rule.action, Some(index)));
+        }
+
+        if let Some(score) = max_risk_score_gte(&rule.conditions)
+            && score > MAX_RISK_SCORE
+        {
+            lints.push(lint(
+                LintKind::ImpossibleRiskScore,
+                LintSeverity::Error,
+                rule,
+                index,
+                None,
+                format!(
+                    "risk_score_gte {score} is above the maximum risk score of {MAX_RISK_SCORE}"
+                ),
+            ));
+            continue;
+        }
+
+        if !is_satisfiable(&rule.conditions) {
+            lints.push(lint(
+                LintKind::ContradictoryConditions,
+                LintSeverity::Error,
+                rule,
+                index,
+                None,
+                "conditions contradict each other, so the rule never matches".to_string(),
+            ));
+            continue;
+        }
+
+        let earlier = policy.rules[..index]
+            .iter()
+            .enumerate()
+            .filter(|(_, other)| other.action == rule.action && is_satisfiable(&other.conditions))
+            .find(|(_, other)| covers(&other.conditions, &rule.conditions));
+        if let Some((earlier_index, other)) = earlier {
+            let finding = if other == rule {
+                lint(
+                    LintKind::DuplicateRule,
+                    LintSeverity::Warning,
+                    rule,
+                    index,
+                    Some(earlier_index),
+                    format!("duplicates rule {earlier_index}"),
+                )
+            } else if other.decision == rule.decision {
+                lint(
+                    LintKind::ShadowedRule,
+                    LintSeverity::Warning,
+                    rule,
+                    index,
+                    Some(earlier_index),
+                    format!(
+                        "never matches: rule {earlier_index} comes first with the same decision"
+                    ),
+                )
+            } else {
+                lint(
+                    LintKind::ShadowedRule,
+                    LintSeverity::Error,
+                    rule,
+                    index,
+                    Some(earlier_index),
+                    format!(
+                        "never matches: rule {earlier_index} comes first and returns {} instead of {}",
+                        other.decision.as_str(),
+                        rule.decision.as_str()
+                    ),
+                )
+            };
+            lints.push(finding);
+        }
+    }
+
+    let named_elsewhere = policy.defaults.actions.keys().chain(
+        policy
+            .overrides
+            .iter()
+            .flat_map(|o| o.actions.iter().chain(&o.except_actions)),
+    );
+    for action in named_elsewhere {
+        if !is_known(action) && unknown_reported.insert(action.as_str()) {
+            lints.push(unknown_action(action, None));
+        }
+    }
+
+    lints
+}
+
+fn lint(
+    kind: LintKind,
+    severity: LintSeverity,
+    rule: &PolicyRule,
+    index: usize,
+    related_index: Option<usize>,
+    message: String,
+) -> PolicyLint {
+    PolicyLint {
+        kind,
+        severity,
+        action: rule.action.clone(),
+        rule_index: Some(index),
+        related_index,
+        message,
+    }
+}
+
+fn unknown_action(action: &str, rule_index: Option<usize>) -> PolicyLint {
+    PolicyLint {
+        kind: LintKind::UnknownAction,
+        severity: LintSeverity::Warning,
+        action: action.to_string(),
+        rule_index,
+        related_index: None,
+        message: format!("unknown action {action:?}"),
+    }
+}
+
+fn max_risk_score_gte(conditions: &PolicyConditions) -> Option<u32> {
+    conditions
+        .all_of
+        .iter()
+        .chain(conditions.any_of.iter())
+        .chain(conditions.not.as_deref())
+        .filter_map(max_risk_score_gte)
+        .chain(conditions.risk_score_gte)
+        .max()
+}
+
+/// Leaf conditions that must all hold, with the `any_of` and `not` groups
+/// found along the way. `all_of` groups are flattened into it.
+#[derive(Clone, Default)]
+struct Facts<'a> {
+    attestation_status: Option<&'a AttestationStatus>,
+    flags: [Option<bool>; 5],
+    app_versions: Vec<&'a str>,
+    risk_score_gte: Option<u32>,
+    any_of: Vec<&'a [PolicyConditions]>,
+    not: Vec<&'a PolicyConditions>,
+}
+
+fn flags(conditions: &PolicyConditions) -> [Option<bool>; 5] {
+    [
+        conditions.jailbreak,
+        conditions.root,
+        conditions.debugger,
+        conditions.hooking,
+        conditions.proxy_detected,
+    ]
+}
+
+impl<'a> Facts<'a> {
+    /// Adds `conditions` to the conjunction; returns false on a direct conflict.
+    fn add(&mut self, conditions: &'a PolicyConditions) -> bool {
+        if let Some(status) = &conditions.attestation_status {
+            match self.attestation_status {
+                Some(existing) if existing != status => return false,
+                _ => self.attestation_status = Some(status),
+            }
+        }
+        for (known, required) in self.flags.iter_mut().zip(flags(conditions)) {
+            if let Some(required) = required {
+                match known {
+                    Some(existing) if *existing != required => return false,
+                    _ => *known = Some(required),
+                }
+            }
+        }
+        if let Some(range) = &conditions.app_version {
+            if self
+                .app_versions
+                .iter()
+                .any(|other| exact_versions_differ(other, range))
+            {
+                return false;
+            }
+            self.app_versions.push(range);
+        }
+        if let Some(score) = conditions.risk_score_gte {
+            self.risk_score_gte = self.risk_score_gte.max(Some(score));
+        }
+        if !conditions.any_of.is_empty() {
+            self.any_of.push(&conditions.any_of);
+        }
+        if let Some(negated) = &conditions.not {
+            self.not.push(negated);
+        }
+        conditions.all_of.iter().all(|group| self.add(group))
+    }
+
+    /// Whether every context satisfying these facts also satisfies `conditions`.
+    fn imply(&self, conditions: &PolicyConditions) -> bool {
+        if conditions
+            .attestation_status
+            .as_ref()
+            .is_some_and(|status| self.attestation_status != Some(status))
+        {
+            return false;
+        }
+        if self
+            .flags
+            .iter()
+            .zip(flags(conditions))
+            .any(|(known, required)| required.is_some() && *known != required)
+        {
+            return false;
+        }
+        if conditions
+            .app_version
+            .as_deref()
+            .is_some_and(|range| !self.app_versions.contains(&range))
+        {
+            return false;
+        }
+        if conditions
+            .risk_score_gte
+            .is_some_and(|score| self.risk_score_gte.is_none_or(|known| known < score))
+        {
+            return false;
+        }
+        let any_of_holds = conditions.any_of.is_empty()
+            || self.any_of.contains(&conditions.any_of.as_slice())
+            || conditions.any_of.iter().any(|group| self.imply(group));
+        let not_holds = conditions
+            .not
+            .as_deref()
+            .is_none_or(|negated| self.not.contains(&negated));
+        any_of_holds && not_holds && conditions.all_of.iter().all(|group| self.imply(group))
+    }
+
+    /// False only when the facts provably never hold together.
+    fn is_satisfiable(&self) -> bool {
+        if self
+            .risk_score_gte
+            .is_some_and(|score| score > MAX_RISK_SCORE)
+        {
+            return false;
+        }
+        if self.not.iter().any(|negated| self.imply(negated)) {
+            return false;
+        }
+        self.any_of.iter().all(|groups| {
+            groups.iter().any(|group| {
+                // Only the leaves are carried into the branch; dropping the
+                // outer groups can only make the branch look satisfiable.
+                let mut branch = Facts {
+                    any_of: Vec::new(),
+                    not: Vec::new(),
+                    ..self.clone()
+                };
+                branch.add(group) && branch.is_satisfiable()
+            })
+        })
+    }
+}
+
+fn exact_versions_differ(a: &str, b: &str) -> bool {
+    let exact = |range: &str| {
+        VersionRange::parse(range)
+            .ok()
+            .filter(VersionRange::is_exact)
+            .and_then(|_| Version::parse(range.trim().trim_start_matches('=')).ok())
+    };
+    matches!((exact(a), exact(b)), (Some(a), Some(b)) if a != b)
+}
+
+fn is_satisfiable(conditions: &PolicyConditions) -> bool {
+    let mut facts = Facts::default();
+    facts.add(conditions) && facts.is_satisfiable()
+}
+
+/// Whether `general` matches every context `specific` matches.
+fn covers(general: &PolicyConditions, specific: &PolicyConditions) -> bool {
+    let mut facts = Facts::default();
+    facts.add(specific) && facts.imply(general)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::policy::{Decision, PolicyDefaults};
+
+    fn rule(action: &str, decision: Decision, conditions: PolicyConditions) -> PolicyRule {
+        PolicyRule {
+            action: action.to_string(),
+            decision,
+            conditions,
+        }
+    }
+
+    fn policy(rules: Vec<PolicyRule>) -> PolicySet {
+        PolicySet {
+            policy_id: "pol_lint".to_string(),
+            app_id: "fintech.mobile".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "test".to_string(),
+            rules,
+            defaults: PolicyDefaults::default(),
+            risk_weights: None,
+            overrides: Vec::new(),
+        }
+    }
+
+    fn debugger(value: bool) -> PolicyConditions {
+        PolicyConditions {
+            debugger: Some(value),
+            ..PolicyConditions::default()
+        }
+    }
+
+    fn findings(
+        lints: &[PolicyLint],
+    ) -> Vec<(LintKind, LintSeverity, Option<usize>, Option<usize>)> {
+        lints
+            .iter()
+            .map(|lint| {
+                (
+                    lint.kind,
+                    lint.severity,
+                    lint.rule_index,
+                    lint.related_index,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_rules_shadowed_by_broader_earlier_rules() {
+        let strict = PolicyConditions {
+            debugger: Some(true),
+            risk_score_gte: Some(80),
+            ..PolicyConditions::default()
+        };
+        let lints = lint_policy(
+            &policy(vec![
+                rule("transfer", Decision::StepUp, PolicyConditions::default()),
+                rule("transfer", Decision::Deny, debugger(true)),
+                rule("login", Decision::StepUp, debugger(true)),
+                rule("login", Decision::StepUp, strict.clone()),
+                rule("login", Decision::Deny, debugger(false)),
+                rule("login", Decision::StepUp, debugger(true)),
+            ]),
+            &[],
+        );
+
+        assert_eq!(
+            findings(&lints),
+            vec![
+                (
+                    LintKind::ShadowedRule,
+                    LintSeverity::Error,
+                    Some(1),
+                    Some(0)
+                ),
+                (
+                    LintKind::ShadowedRule,
+                    LintSeverity::Warning,
+                    Some(3),
+                    Some(2)
+                ),
+                (
+                    LintKind::DuplicateRule,
+                    LintSeverity::Warning,
+                    Some(5),
+                    Some(2)
+                ),
+            ]
+        );
+
+        let lints = lint_policy(
+            &policy(vec![
+                rule("login", Decision::Deny, strict),
+                rule("login", Decision::StepUp, debugger(true)),
+            ]),
+            &[],
+        );
+        assert!(lints.is_empty());
+    }
+
+    #[test]
+    fn reports_contradictions_and_impossible_risk_scores() {
+        let nested = PolicyConditions {
+            debugger: Some(true),
+            all_of: vec![PolicyConditions {
+                any_of: vec![
+                    debugger(false),
+                    PolicyConditions {
+                        not: Some(Box::new(PolicyConditions::default())),
+                        ..PolicyConditions::default()
+                    },
+                ],
+                ..PolicyConditions::default()
+            }],
+            ..PolicyConditions::default()
+        };
+        let negated = PolicyConditions {
+            hooking: Some(true),
+            not: Some(Box::new(PolicyConditions {
+                hooking: Some(true),
+                ..PolicyConditions::default()
+            })),
+            ..PolicyConditions::default()
+        };
+        let versions = PolicyConditions {
+            app_version: Some("1.0.0".to_string()),
+            all_of: vec![PolicyConditions {
+                app_version: Some("=1.1.0".to_string()),
+                ..PolicyConditions::default()
+            }],
+            ..PolicyConditions::default()
+        };
+        let risky = PolicyConditions {
+            not: Some(Box::new(PolicyConditions {
+                risk_score_gte: Some(150),
+                ..PolicyConditions::default()
+            })),
+            ..PolicyConditions::default()
+        };
+        let satisfiable = PolicyConditions {
+            debugger: Some(true),
+            any_of: vec![
+                debugger(false),
+                PolicyConditions {
+                    hooking: Some(true),
+                    ..PolicyConditions::default()
+                },
+            ],
+            ..PolicyConditions::default()
+        };
+
+        let lints = lint_policy(
+            &policy(vec![
+                rule("login", Decision::Deny, nested),
+                rule("login", Decision::Deny, negated),
+                rule("login", Decision::Deny, versions),
+                rule("transfer", Decision::Deny, risky),
+                rule("transfer", Decision::Deny, satisfiable),
+            ]),
+            &[],
+        );
+
+        assert_eq!(
+            findings(&lints),
+            vec![
+                (
+                    LintKind::ContradictoryConditions,
+                    LintSeverity::Error,
+                    Some(0),
+                    None
+                ),
+                (
+                    LintKind::ContradictoryConditions,
+                    LintSeverity::Error,
+                    Some(1),
+                    None
+                ),
+                (
+                    LintKind::ContradictoryConditions,
+                    LintSeverity::Error,
+                    Some(2),
+                    None
+                ),
+                (
+                    LintKind::ImpossibleRiskScore,
+                    LintSeverity::Error,
+                    Some(3),
+                    None
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_each_unknown_action_once() {
+        let mut set = policy(vec![
+            rule("login", Decision::StepUp, debugger(true)),
+            rule("tranfser", Decision::Deny, debugger(true)),
+            rule("tranfser", Decision::Deny, debugger(false)),
+            rule("open_account", Decision::StepUp, debugger(true)),
+        ]);
+        set.defaults
+            .actions
+            .insert("view_cards".to_string(), Decision::Deny);
+
+        let lints = lint_policy(&set, &["open_account"]);
+        let unknown = lints
+            .iter()
+            .map(|lint| (lint.action.as_str(), lint.rule_index))
+            .collect::<Vec<_>>();
+        assert_eq!(unknown, vec![("tranfser", Some(1)), ("view_cards", None)]);
+        assert!(
+            lints
+                .iter()
+                .all(|lint| lint.kind == LintKind::UnknownAction)
+        );
+    }
+}
@@ -1,9 +1,15 @@
+use std::collections::BTreeMap;
+
+use crate::domain::telemetry::{AttestationResult, AttestationStatus, IntegritySignals};
+
+pub const MAX_RISK_SCORE: u32 = 100;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct RiskScore(u32);
 
 impl RiskScore {
     pub fn new(score: u32) -> Self {
-        Self(score.min(100))
+        Self(score.min(MAX_RISK_SCORE))
     }
 
     pub fn value(self) -> u32 {
@@ -24,3 +30,514 @@ pub enum Severity {
     High,
     Critical,
 }
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+/// The risk model shared by the SDK scorers, the backend and report uploads:
+/// points each risk factor adds to a score, capped at 100. Distributed inside
+/// the signed policy so the SDK and the backend score devices the same way.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RiskWeights {
+    pub jailbreak: u32,
+    pub root: u32,
+    pub debugger: u32,
+    pub hooking: u32,
+    pub proxy_detected: u32,
+    pub attestation_fail: u32,
+    pub attestation_unknown: u32,
+    pub finding_low: u32,
+    pub finding_medium: u32,
+    pub finding_high: u32,
+    pub finding_critical: u32,
+    /// Percentage applied to a finding's severity points by category (e.g.
+    /// `"crypto": 150`). Categories not listed count at 100%.
+    pub finding_category_pct: BTreeMap<String, u32>,
+    /// Signal observations lose half their points every `decay_half_life_secs`;
+    /// `None` keeps them at full weight regardless of age.
+    pub decay_half_life_secs: Option<u32>,
+}
+
+impl Default for RiskWeights {
+    fn default() -> Self {
+        Self {
+            jailbreak: 40,
+            root: 40,
+            debugger: 30,
+            hooking: 30,
+            proxy_detected: 20,
+            attestation_fail: 30,
+            attestation_unknown: 0,
+            finding_low: 5,
+            finding_medium: 10,
+            finding_high: 20,
+            finding_critical: 30,
+            finding_category_pct: BTreeMap::new(),
+            decay_half_life_secs: None,
+        }
+    }
+}
+
+/// Device state seen `age_secs` ago, e.g. one stored telemetry event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RiskObservation {
+    pub signals: IntegritySignals,
+    pub attestation: Option<AttestationResult>,
+    pub age_secs: u64,
+}
+
+/// A factor that can add points to a risk score.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RiskFactor {
+    Jailbreak,
+    Root,
+    Debugger,
+    Hooking,
+    ProxyDetected,
+    AttestationFail,
+    AttestationUnknown,
+    Finding { category: String, severity: Severity },
+}
+
+impl RiskFactor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskFactor::Jailbreak => "jailbreak",
+            RiskFactor::Root => "root",
+            RiskFactor::Debugger => "debugger",
+            RiskFactor::Hooking => "hooking",
+            RiskFactor::ProxyDetected => "proxy_detected",
+            RiskFactor::AttestationFail => "attestation_fail",
+            RiskFactor::AttestationUnknown => "attestation_unknown",
+            RiskFactor::Finding { .. } => "finding",
+        }
+    }
+}
+
+/// One factor's share of a score: the configured `weight`, the `points` it
+/// added to the final score and whether the 100 cap cut those points short.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RiskContribution {
+    pub factor: RiskFactor,
+    pub weight: u32,
+    pub points: u32,
+    pub capped: bool,
+}
+
+/// Why a device got its score. The contribution points add up to `score`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RiskBreakdown {
+    pub score: RiskScore,
+    pub contributions: Vec<RiskContribution>,
+}
+
+impl RiskBreakdown {
+    /// Breakdown of a score from a scorer that does not explain itself.
+    pub fn opaque(score: RiskScore) -> Self {
+        Self {
+            score,
+            contributions: Vec::new(),
+        }
+    }
+
+    /// Applies the cap in order: once the total reaches 100, later factors are
+    /// counted only up to what is left.
+    fn from_factors(factors: Vec<(RiskFactor, u32, u32)>) -> Self {
+        let mut remaining = MAX_RISK_SCORE;
+        let contributions = factors
+            .into_iter()
+            .map(|(factor, weight, points)| {
+                let counted = points.min(remaining);
+                remaining -= counted;
+                RiskContribution {
+                    factor,
+                    weight,
+                    points: counted,
+                    capped: counted < points,
+                }
+            })
+            .collect::<Vec<_>>();
+        let total = contributions
+            .iter()
+            .fold(0u32, |total, contribution| total + contribution.points);
+        Self {
+            score: RiskScore::new(total),
+            contributions,
+        }
+    }
+}
+
+impl RiskWeights {
+    pub fn score(
+        &self,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+        findings: &[Finding],
+    ) -> RiskScore {
+        self.breakdown(signals, attestation, findings).score
+    }
+
+    /// Scores like [`RiskWeights::score`] and lists what contributed, signals
+    /// first, then attestation, then findings in order.
+    pub fn breakdown(
+        &self,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+        findings: &[Finding],
+    ) -> RiskBreakdown {
+        let mut factors = self.signal_factors(signals, attestation);
+        factors.extend(self.finding_factors(findings));
+        RiskBreakdown::from_factors(factors)
+    }
+
+    /// Scores findings alone, e.g. a pipeline report.
+    pub fn score_findings(&self, findings: &[Finding]) -> RiskScore {
+        RiskBreakdown::from_factors(self.finding_factors(findings)).score
+    }
+
+    /// Scores a device from its history: the highest decayed observation plus
+    /// the findings, so repeated events do not stack but old ones fade out.
+    pub fn score_history(&self, observations: &[RiskObservation], findings: &[Finding]) -> RiskScore {
+        let signal_points = observations
+            .iter()
+            .map(|observation| {
+                let points = self
+                    .signal_factors(&observation.signals, observation.attestation.as_ref())
+                    .iter()
+                    .fold(0u32, |total, (_, _, points)| total.saturating_add(*points));
+                (f64::from(points) * self.decay_factor(observation.age_secs)).round() as u32
+            })
+            .max()
+            .unwrap_or(0);
+        let finding_points = self
+            .finding_factors(findings)
+            .iter()
+            .fold(0u32, |total, (_, _, points)| total.saturating_add(*points));
+        RiskScore::new(signal_points.saturating_add(finding_points))
+    }
+
+    /// `(factor, weight, points)`. Jailbreak and root, and debugger and hooking,
+    /// are two views of one compromise: raised together they count once, at the
+    /// higher weight, and the other signal is listed with no points.
+    fn signal_factors(
+        &self,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+    ) -> Vec<(RiskFactor, u32, u32)> {
+        let flags = [
+            (signals.jailbreak, RiskFactor::Jailbreak, self.jailbreak, 0),
+            (signals.root, RiskFactor::Root, self.root, 0),
+            (signals.debugger, RiskFactor::Debugger, self.debugger, 1),
+            (signals.hooking, RiskFactor::Hooking, self.hooking, 1),
+            (signals.proxy_detected, RiskFactor::ProxyDetected, self.proxy_detected, 2),
+        ];
+        let mut group_max = [0u32; 3];
+        for (_, _, weight, group) in flags.iter().filter(|(raised, ..)| *raised) {
+            group_max[*group] = group_max[*group].max(*weight);
+        }
+        let mut counted = [false; 3];
+        let mut factors = flags
+            .into_iter()
+            .filter(|(raised, ..)| *raised)
+            .map(|(_, factor, weight, group)| {
+                let points = if !counted[group] && weight == group_max[group] {
+                    counted[group] = true;
+                    weight
+                } else {
+                    0
+                };
+                (factor, weight, points)
+            })
+            .collect::<Vec<_>>();
+
+        match attestation.map(|result| &result.status) {
+            Some(AttestationStatus::Fail) => factors.push((
+                RiskFactor::AttestationFail,
+                self.attestation_fail,
+                self.attestation_fail,
+            )),
+            Some(AttestationStatus::Unknown) => factors.push((
+                RiskFactor::AttestationUnknown,
+                self.attestation_unknown,
+                self.attestation_unknown,
+            )),
+            Some(AttestationStatus::Pass) | None => {}
+        }
+        factors
+    }
+
+    /// `(factor, severity weight, points after the category percentage)`.
+    fn finding_factors(&self, findings: &[Finding]) -> Vec<(RiskFactor, u32, u32)> {
+        findings
+            .iter()
+            .map(|finding| {
+                let weight = match finding.severity {
+                    Severity::Low => self.finding_low,
+                    Severity::Medium => self.finding_medium,
+                    Severity::High => self.finding_high,
+                    Severity::Critical => self.finding_critical,
+                };
+                let pct = self
+                    .finding_category_pct
+                    .get(&finding.category)
+                    .copied()
+                    .unwrap_or(100);
+                let factor = RiskFactor::Finding {
+                    category: finding.category.clone(),
+                    severity: finding.severity.clone(),
+                };
+                (factor, weight, weight.saturating_mul(pct) / 100)
+            })
+            .collect()
+    }
+
+    fn decay_factor(&self, age_secs: u64) -> f64 {
+        match self.decay_half_life_secs {
+            Some(half_life) if half_life > 0 => 0.5f64.powf(age_secs as f64 / f64::from(half_life)),
+            _ => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::telemetry::AttestationProvider;
+
+    fn signals() -> IntegritySignals {
+        IntegritySignals {
+            jailbreak: false,
+            root: false,
+            debugger: false,
+            hooking: false,
+            proxy_detected: false,
+        }
+    }
+
+    #[test]
+    fn weights_sum_signals_attestation_and_findings() {
+        let weights = RiskWeights::default();
+        let mut hooked = signals();
+        hooked.hooking = true;
+        hooked.proxy_detected = true;
+        let failed = AttestationResult {
+            provider: AttestationProvider::AppAttest,
+            status: AttestationStatus::Fail,
+            timestamp: None,
+            challenge: None,
+        };
+        let findings = vec![Finding {
+            category: "storage".to_string(),
+            severity: Severity::Medium,
+        }];
+
+        assert_eq!(weights.score(&hooked, None, &[]).value(), 50);
+        assert_eq!(weights.score(&hooked, Some(&failed), &findings).value(), 90);
+        hooked.root = true;
+        assert_eq!(weights.score(&hooked, Some(&failed), &findings).value(), 100);
+    }
+
+    fn finding(category: &str, severity: Severity) -> Finding {
+        Finding {
+            category: category.to_string(),
+            severity,
+        }
+    }
+
+    #[test]
+    fn default_weights_pin_expected_scores() {
+        let weights = RiskWeights::default();
+        let raised = [
+            (IntegritySignals { jailbreak: true, ..signals() }, 40),
+            (IntegritySignals { root: true, ..signals() }, 40),
+            (IntegritySignals { debugger: true, ..signals() }, 30),
+            (IntegritySignals { hooking: true, ..signals() }, 30),
+            (IntegritySignals { proxy_detected: true, ..signals() }, 20),
+        ];
+        for (raised, expected) in raised {
+            assert_eq!(weights.score(&raised, None, &[]).value(), expected);
+        }
+        // Paired signals count once, as in the scores SDKs computed before weights.
+        let paired = [
+            (IntegritySignals { jailbreak: true, root: true, ..signals() }, 40),
+            (IntegritySignals { debugger: true, hooking: true, ..signals() }, 30),
+            (
+                IntegritySignals {
+                    jailbreak: true,
+                    root: true,
+                    debugger: true,
+                    hooking: true,
+                    proxy_detected: true,
+                },
+                90,
+            ),
+        ];
+        for (raised, expected) in paired {
+            assert_eq!(weights.score(&raised, None, &[]).value(), expected);
+        }
+
+        let severities = [
+            (Severity::Low, 5),
+            (Severity::Medium, 10),
+            (Severity::High, 20),
+            (Severity::Critical, 30),
+        ];
+        for (severity, expected) in severities {
+            let findings = [finding("storage", severity)];
+            assert_eq!(weights.score_findings(&findings).value(), expected);
+        }
+
+        let unknown = AttestationResult {
+            provider: AttestationProvider::PlayIntegrity,
+            status: AttestationStatus::Unknown,
+            timestamp: None,
+            challenge: None,
+        };
+        assert_eq!(weights.score(&signals(), Some(&unknown), &[]).value(), 0);
+        let many = vec![finding("storage", Severity::Critical); 5];
+        assert_eq!(weights.score_findings(&many).value(), 100);
+    }
+
+    #[test]
+    fn category_percentages_scale_finding_points() {
+        let mut weights = RiskWeights::default();
+        weights.finding_category_pct.insert("crypto".to_string(), 150);
+        weights.finding_category_pct.insert("logging".to_string(), 0);
+
+        let findings = [
+            finding("crypto", Severity::High),
+            finding("logging", Severity::Critical),
+            finding("network", Severity::Low),
+        ];
+        assert_eq!(weights.score_findings(&findings).value(), 35);
+    }
+
+    #[test]
+    fn breakdown_explains_a_capped_score() {
+        let mut weights = RiskWeights::default();
+        weights.finding_category_pct.insert("crypto".to_string(), 150);
+        let signals = IntegritySignals {
+            jailbreak: true,
+            hooking: true,
+            ..signals()
+        };
+        let failed = AttestationResult {
+            provider: AttestationProvider::AppAttest,
+            status: AttestationStatus::Fail,
+            timestamp: None,
+            challenge: None,
+        };
+        let findings = [finding("crypto", Severity::High)];
+
+        let breakdown = weights.breakdown(&signals, Some(&failed), &findings);
+        assert_eq!(breakdown.score.value(), 100);
+        assert_eq!(
+            breakdown.contributions,
+            vec![
+                RiskContribution {
+                    factor: RiskFactor::Jailbreak,
+                    weight: 40,
+                    points: 40,
+                    capped: false,
+                },
+                RiskContribution {
+                    factor: RiskFactor::Hooking,
+                    weight: 30,
+                    points: 30,
+                    capped: false,
+                },
+                RiskContribution {
+                    factor: RiskFactor::AttestationFail,
+                    weight: 30,
+                    points: 30,
+                    capped: false,
+                },
+                RiskContribution {
+                    factor: RiskFactor::Finding {
+                        category: "crypto".to_string(),
+                        severity: Severity::High,
+                    },
+                    weight: 20,
+                    points: 0,
+                    capped: true,
+                },
+            ]
+        );
+        assert_eq!(
+            weights.score(&signals, Some(&failed), &findings),
+            breakdown.score
+        );
+    }
+
+    #[test]
+    fn paired_signals_count_once_at_the_higher_weight() {
+        let weights = RiskWeights {
+            root: 60,
+            ..RiskWeights::default()
+        };
+        let compromised = IntegritySignals {
+            jailbreak: true,
+            root: true,
+            ..signals()
+        };
+
+        let breakdown = weights.breakdown(&compromised, None, &[]);
+        assert_eq!(breakdown.score.value(), 60);
+        assert_eq!(
+            breakdown.contributions,
+            vec![
+                RiskContribution {
+                    factor: RiskFactor::Jailbreak,
+                    weight: 40,
+                    points: 0,
+                    capped: false,
+                },
+                RiskContribution {
+                    factor: RiskFactor::Root,
+                    weight: 60,
+                    points: 60,
+                    capped: false,
+                },
+            ]
+        );
+        let observation = RiskObservation {
+            signals: compromised,
+            attestation: None,
+            age_secs: 0,
+        };
+        assert_eq!(weights.score_history(&[observation], &[]).value(), 60);
+    }
+
+    #[test]
+    fn history_decays_old_observations() {
+        let weights = RiskWeights {
+            decay_half_life_secs: Some(3600),
+            ..RiskWeights::default()
+        };
+        let mut debugged = signals();
+        debugged.debugger = true;
+        let observation = |age_secs| RiskObservation {
+            signals: debugged.clone(),
+            attestation: None,
+            age_secs,
+        };
+
+        assert_eq!(weights.score_history(&[observation(0)], &[]).value(), 30);
+        assert_eq!(weights.score_history(&[observation(3600)], &[]).value(), 15);
+        assert_eq!(
+            weights
+                .score_history(&[observation(7200), observation(3600)], &[])
+                .value(),
+            15
+        );
+        assert_eq!(weights.score_history(&[], &[]).value(), 0);
+    }
+}
@@ -1,3 +1,6 @@
+use crate::domain::canonical::CanonicalEncoder;
+use crate::domain::risk::{RiskBreakdown, RiskFactor};
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DeviceInfo {
     pub platform: Platform,
@@ -45,6 +48,17 @@ pub struct AttestationResult {
     pub provider: AttestationProvider,
     pub status: AttestationStatus,
     pub timestamp: Option<String>,
+    /// Server-issued challenge the evidence was bound to, see
+    /// [`AttestationChallenge`].
+    pub challenge: Option<String>,
+}
+
+/// Single-use nonce issued by `POST /v1/attestation/challenge` for one action
+/// of a session. The backend rejects it once used or past `expires_at`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttestationChallenge {
+    pub challenge: String,
+    pub expires_at: String,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -53,6 +67,39 @@ pub struct ActionContext {
     pub context: Option<String>,
 }
 
+/// Layout of the bytes covered by `TelemetryEvent::signature`.
+///
+/// `V1` is the legacy `event_id:app_id:app_version:env:action` string kept so the
+/// backend can still verify events from SDKs that predate `V2`. `V3` adds the risk
+/// breakdown and the attestation challenge, which `V2` does not cover.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SigningPayloadVersion {
+    V1,
+    V2,
+    V3,
+}
+
+impl SigningPayloadVersion {
+    pub const CURRENT: SigningPayloadVersion = SigningPayloadVersion::V3;
+
+    pub fn number(self) -> u32 {
+        match self {
+            SigningPayloadVersion::V1 => 1,
+            SigningPayloadVersion::V2 => 2,
+            SigningPayloadVersion::V3 => 3,
+        }
+    }
+
+    pub fn from_number(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(SigningPayloadVersion::V1),
+            2 => Some(SigningPayloadVersion::V2),
+            3 => Some(SigningPayloadVersion::V3),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TelemetryEvent {
     pub event_id: String,
@@ -66,6 +113,9 @@ pub struct TelemetryEvent {
     pub action: ActionContext,
     pub timestamp: Option<String>,
     pub signature: Option<String>,
+    pub payload_version: SigningPayloadVersion,
+    /// How the risk score behind the decision was computed, for auditing.
+    pub risk_breakdown: Option<RiskBreakdown>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
@@ -86,11 +136,281 @@ impl TelemetryEnvelope {
 }
 
 impl TelemetryEvent {
-    pub fn signing_payload(&self) -> String {
-        // Placeholder for canonical serialization.
+    /// Bytes covered by the event signature, laid out per `payload_version`.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        match self.payload_version {
+            SigningPayloadVersion::V1 => self.legacy_signing_payload().into_bytes(),
+            SigningPayloadVersion::V2 => self.canonical_encoder("panther.telemetry.v2").finish(),
+            SigningPayloadVersion::V3 => {
+                let mut encoder = self.canonical_encoder("panther.telemetry.v3");
+                self.encode_v3_fields(&mut encoder);
+                encoder.finish()
+            }
+        }
+    }
+
+    fn legacy_signing_payload(&self) -> String {
         format!(
             "{}:{}:{}:{}:{}",
             self.event_id, self.app_id, self.app_version, self.env, self.action.name
         )
     }
+
+    /// Every field except `signature`, the risk breakdown and the attestation
+    /// challenge: the whole `V2` layout, which `V3` extends.
+    fn canonical_encoder(&self, tag: &str) -> CanonicalEncoder {
+        let mut encoder = CanonicalEncoder::new(tag);
+        encoder
+            .str(&self.event_id)
+            .str(&self.app_id)
+            .str(&self.app_version)
+            .str(&self.env);
+
+        encoder
+            .str(self.device.platform.as_str())
+            .str(&self.device.os_version)
+            .str(&self.device.model);
+
+        match &self.session {
+            Some(session) => {
+                encoder
+                    .present(true)
+                    .str(&session.session_id)
+                    .opt_str(session.user_id_hash.as_deref());
+            }
+            None => {
+                encoder.present(false);
+            }
+        }
+
+        encoder
+            .bool(self.signals.jailbreak)
+            .bool(self.signals.root)
+            .bool(self.signals.debugger)
+            .bool(self.signals.hooking)
+            .bool(self.signals.proxy_detected);
+
+        match &self.attestation {
+            Some(attestation) => {
+                encoder
+                    .present(true)
+                    .str(attestation.provider.as_str())
+                    .str(attestation.status.as_str())
+                    .opt_str(attestation.timestamp.as_deref());
+            }
+            None => {
+                encoder.present(false);
+            }
+        }
+
+        encoder
+            .str(&self.action.name)
+            .opt_str(self.action.context.as_deref())
+            .opt_str(self.timestamp.as_deref());
+        encoder
+    }
+
+    fn encode_v3_fields(&self, encoder: &mut CanonicalEncoder) {
+        match &self.risk_breakdown {
+            Some(breakdown) => {
+                encoder
+                    .present(true)
+                    .u32(breakdown.score.value())
+                    .u32(breakdown.contributions.len() as u32);
+                for contribution in &breakdown.contributions {
+                    encoder.str(contribution.factor.as_str());
+                    if let RiskFactor::Finding { category, severity } = &contribution.factor {
+                        encoder.str(category).str(severity.as_str());
+                    }
+                    encoder
+                        .u32(contribution.weight)
+                        .u32(contribution.points)
+                        .bool(contribution.capped);
+                }
+            }
+            None => {
+                encoder.present(false);
+            }
+        }
+
+        encoder.opt_str(
+            self.attestation
+                .as_ref()
+                .and_then(|attestation| attestation.challenge.as_deref()),
+        );
+    }
+}
+
+impl Platform {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Platform::Ios => "ios",
+            Platform::Android => "android",
+        }
+    }
+}
+
+impl AttestationProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttestationProvider::AppAttest => "app_attest",
+            AttestationProvider::PlayIntegrity => "play_integrity",
+            AttestationProvider::None => "none",
+        }
+    }
+}
+
+impl AttestationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttestationStatus::Pass => "pass",
+            AttestationStatus::Fail => "fail",
+            AttestationStatus::Unknown => "unknown",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::risk::{RiskContribution, RiskScore};
+
+    fn event(version: SigningPayloadVersion) -> TelemetryEvent {
+        TelemetryEvent {
+            event_id: "evt_1".to_string(),
+            app_id: "fintech.mobile".to_string(),
+            app_version: "1.0.0".to_string(),
+            env: "prod".to_string(),
+            device: DeviceInfo {
+                platform: Platform::Ios,
+                os_version: "17.0".to_string(),
+                model: "iPhone".to_string(),
+            },
+            session: None,
+            signals: IntegritySignals {
+                jailbreak: false,
+                root: false,
+                debugger: false,
+                hooking: false,
+                proxy_detected: false,
+            },
+            attestation: None,
+            action: ActionContext {
+                name: "login".to_string(),
+                context: None,
+            },
+            timestamp: Some("2026-02-06T21:00:00Z".to_string()),
+            signature: None,
+            payload_version: version,
+            risk_breakdown: None,
+        }
+    }
+
+    #[test]
+    fn legacy_payload_keeps_v1_layout() {
+        let payload = event(SigningPayloadVersion::V1).signing_payload();
+        assert_eq!(payload, b"evt_1:fintech.mobile:1.0.0:prod:login".to_vec());
+    }
+
+    #[test]
+    fn canonical_payload_covers_every_field_but_signature() {
+        let base = event(SigningPayloadVersion::V3);
+        let payload = base.signing_payload();
+
+        let mut signed = base.clone();
+        signed.signature = Some("ed25519:k1:abc".to_string());
+        assert_eq!(signed.signing_payload(), payload);
+
+        let mut tampered = base.clone();
+        tampered.signals.root = true;
+        assert_ne!(tampered.signing_payload(), payload);
+
+        let mut tampered = base.clone();
+        tampered.device.model = "Pixel".to_string();
+        assert_ne!(tampered.signing_payload(), payload);
+
+        let mut tampered = base.clone();
+        tampered.timestamp = Some("2026-02-07T21:00:00Z".to_string());
+        assert_ne!(tampered.signing_payload(), payload);
+
+        let mut tampered = base.clone();
+        tampered.attestation = Some(AttestationResult {
+            provider: AttestationProvider::AppAttest,
+            status: AttestationStatus::Pass,
+            timestamp: None,
+            challenge: None,
+        });
+        assert_ne!(tampered.signing_payload(), payload);
+        let attested_payload = tampered.signing_payload();
+        if let Some(attestation) = tampered.attestation.as_mut() {
+            attestation.challenge = Some("nonce_1".to_string());
+        }
+        assert_ne!(tampered.signing_payload(), attested_payload);
+
+        let mut tampered = base.clone();
+        tampered.session = Some(SessionInfo {
+            session_id: "sess_1".to_string(),
+            user_id_hash: None,
+        });
+        assert_ne!(tampered.signing_payload(), payload);
+
+        let mut scored = base;
+        scored.risk_breakdown = Some(RiskBreakdown {
+            score: RiskScore::new(30),
+            contributions: vec![RiskContribution {
+                factor: RiskFactor::Debugger,
+                weight: 30,
+                points: 30,
+                capped: false,
+            }],
+        });
+        assert_ne!(scored.signing_payload(), payload);
+        let scored_payload = scored.signing_payload();
+        if let Some(breakdown) = scored.risk_breakdown.as_mut() {
+            breakdown.contributions[0].points = 0;
+        }
+        assert_ne!(scored.signing_payload(), scored_payload);
+    }
+
+    #[test]
+    fn v3_marks_absent_breakdown_and_challenge() {
+        let mut challenged = event(SigningPayloadVersion::V3);
+        challenged.attestation = Some(AttestationResult {
+            provider: AttestationProvider::AppAttest,
+            status: AttestationStatus::Pass,
+            timestamp: None,
+            challenge: Some("nonce_1".to_string()),
+        });
+        let mut scored = challenged.clone();
+        scored.risk_breakdown = Some(RiskBreakdown {
+            score: RiskScore::new(0),
+            contributions: Vec::new(),
+        });
+        if let Some(attestation) = scored.attestation.as_mut() {
+            attestation.challenge = None;
+        }
+        assert_ne!(challenged.signing_payload(), scored.signing_payload());
+
+        // V2 keeps the layout SDKs signed before V3 and does not cover either field.
+        let mut v2 = challenged.clone();
+        v2.payload_version = SigningPayloadVersion::V2;
+        let mut v2_plain = v2.clone();
+        if let Some(attestation) = v2_plain.attestation.as_mut() {
+            attestation.challenge = None;
+        }
+        assert_eq!(v2.signing_payload(), v2_plain.signing_payload());
+        assert_ne!(v2.signing_payload(), challenged.signing_payload());
+    }
+
+    #[test]
+    fn canonical_payload_separates_adjacent_fields() {
+        let mut left = event(SigningPayloadVersion::V2);
+        left.app_id = "fintech".to_string();
+        left.app_version = ".mobile1.0.0".to_string();
+        let mut right = event(SigningPayloadVersion::V2);
+        right.app_id = "fintech.mobile".to_string();
+        right.app_version = "1.0.0".to_string();
+
+        assert_ne!(left.signing_payload(), right.signing_payload());
+    }
 }
@@ -0,0 +1,280 @@
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionError {
+    pub message: String,
+}
+
+impl VersionError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+/// `major.minor.patch` app version. Missing components default to 0 and any
+/// pre-release or build suffix (`-beta.1`, `+45`) is ignored when comparing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, VersionError> {
+        let core = value.trim().split(['-', '+']).next().unwrap_or_default();
+        let (version, _) = parse_components(core)?;
+        Ok(version)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Exact,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Caret,
+    Tilde,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Comparator {
+    op: Op,
+    version: Version,
+    /// Number of components written in the range (`1.4` has 2).
+    parts: usize,
+}
+
+impl Comparator {
+    fn matches(&self, version: &Version) -> bool {
+        match self.op {
+            Op::Exact => {
+                let (lower, upper) = self.partial_bounds();
+                *version >= lower && *version < upper
+            }
+            Op::Gt => match self.parts {
+                3 => *version > self.version,
+                _ => *version >= self.partial_bounds().1,
+            },
+            Op::Gte => *version >= self.version,
+            Op::Lt => *version < self.version,
+            Op::Lte => match self.parts {
+                3 => *version <= self.version,
+                _ => *version < self.partial_bounds().1,
+            },
+            Op::Caret => *version >= self.version && *version < self.caret_upper(),
+            Op::Tilde => *version >= self.version && *version < self.tilde_upper(),
+        }
+    }
+
+    /// Half-open interval covered by a partial version: `1.4` is `[1.4.0, 1.5.0)`.
+    fn partial_bounds(&self) -> (Version, Version) {
+        let v = self.version;
+        let upper = match self.parts {
+            1 => Version::new(v.major + 1, 0, 0),
+            2 => Version::new(v.major, v.minor + 1, 0),
+            _ => Version::new(v.major, v.minor, v.patch + 1),
+        };
+        (v, upper)
+    }
+
+    /// `^` allows changes that do not modify the left-most non-zero component.
+    fn caret_upper(&self) -> Version {
+        let v = self.version;
+        if v.major > 0 || self.parts == 1 {
+            Version::new(v.major + 1, 0, 0)
+        } else if v.minor > 0 || self.parts == 2 {
+            Version::new(0, v.minor + 1, 0)
+        } else {
+            Version::new(0, 0, v.patch + 1)
+        }
+    }
+
+    /// `~` allows patch-level changes, or minor-level ones when only the major is given.
+    fn tilde_upper(&self) -> Version {
+        let v = self.version;
+        match self.parts {
+            1 => Version::new(v.major + 1, 0, 0),
+            _ => Version::new(v.major, v.minor + 1, 0),
+        }
+    }
+}
+
+/// Version constraint used by `app_version` conditions.
+///
+/// Comparators are separated by whitespace or commas and must all hold:
+/// `>=1.2.0 <2.0.0`, `^1.4`, `~1.4.2`, `1.0.0` (exact), `*` (any).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionRange {
+    comparators: Vec<Comparator>,
+}
+
+impl VersionRange {
+    pub fn parse(value: &str) -> Result<Self, VersionError> {
+        let tokens = value
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .collect::<Vec<_>>();
+        if tokens.is_empty() {
+            return Err(VersionError::new("version range must not be empty"));
+        }
+
+        if tokens == ["*"] {
+            return Ok(Self {
+                comparators: Vec::new(),
+            });
+        }
+
+        let comparators = tokens
+            .into_iter()
+            .map(parse_comparator)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { comparators })
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        self.comparators
+            .iter()
+            .all(|comparator| comparator.matches(version))
+    }
+
+    /// Whether the range constrains exactly one version (e.g. `1.2.3` or `=1.2.3`).
+    pub fn is_exact(&self) -> bool {
+        matches!(
+            self.comparators.as_slice(),
+            [Comparator {
+                op: Op::Exact,
+                parts: 3,
+                ..
+            }]
+        )
+    }
+}
+
+/// Matches `app_version` against a condition. Versions that are not semver
+/// (e.g. `dev`) only match a condition with the exact same text.
+pub fn app_version_matches(condition: &str, app_version: &str) -> bool {
+    if condition.trim() == app_version.trim() {
+        return true;
+    }
+
+    match (VersionRange::parse(condition), Version::parse(app_version)) {
+        (Ok(range), Ok(version)) => range.matches(&version),
+        _ => false,
+    }
+}
+
+fn parse_comparator(token: &str) -> Result<Comparator, VersionError> {
+    let (op, rest) = [
+        (">=", Op::Gte),
+        ("<=", Op::Lte),
+        (">", Op::Gt),
+        ("<", Op::Lt),
+        ("=", Op::Exact),
+        ("^", Op::Caret),
+        ("~", Op::Tilde),
+    ]
+    .iter()
+    .find_map(|(prefix, op)| token.strip_prefix(prefix).map(|rest| (*op, rest)))
+    .unwrap_or((Op::Exact, token));
+
+    let (version, parts) = parse_components(rest).map_err(|err| {
+        VersionError::new(format!("invalid comparator '{}': {}", token, err.message))
+    })?;
+    Ok(Comparator { op, version, parts })
+}
+
+fn parse_components(value: &str) -> Result<(Version, usize), VersionError> {
+    let parts = value.split('.').collect::<Vec<_>>();
+    if value.is_empty() || parts.len() > 3 {
+        return Err(VersionError::new(format!(
+            "'{}' is not a major[.minor[.patch]] version",
+            value
+        )));
+    }
+
+    let mut numbers = [0u64; 3];
+    for (index, part) in parts.iter().enumerate() {
+        if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+            return Err(VersionError::new(format!(
+                "'{}' is not a major[.minor[.patch]] version",
+                value
+            )));
+        }
+        numbers[index] = part
+            .parse::<u64>()
+            .map_err(|_| VersionError::new(format!("'{}' is out of range", part)))?;
+    }
+
+    Ok((
+        Version::new(numbers[0], numbers[1], numbers[2]),
+        parts.len(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(range: &str, version: &str) -> bool {
+        VersionRange::parse(range)
+            .expect("range")
+            .matches(&Version::parse(version).expect("version"))
+    }
+
+    #[test]
+    fn bare_versions_are_exact() {
+        assert!(matches("1.0.0", "1.0.0"));
+        assert!(!matches("1.0.0", "1.0.1"));
+        assert!(matches("1.4", "1.4.7"));
+        assert!(!matches("1.4", "1.5.0"));
+    }
+
+    #[test]
+    fn comparator_sets_are_intersections() {
+        assert!(matches(">=1.2.0 <2.0.0", "1.2.0"));
+        assert!(matches(">=1.2.0, <2.0.0", "1.9.9"));
+        assert!(!matches(">=1.2.0 <2.0.0", "2.0.0"));
+        assert!(!matches(">=1.2.0 <2.0.0", "1.1.9"));
+        assert!(matches("<2.3.0", "2.2.9"));
+        assert!(!matches("<2.3.0", "2.3.0"));
+        assert!(matches("<=1.4", "1.4.9"));
+        assert!(!matches(">1.4", "1.4.9"));
+    }
+
+    #[test]
+    fn caret_and_tilde_ranges() {
+        assert!(matches("^1.4", "1.9.0"));
+        assert!(!matches("^1.4", "1.3.9"));
+        assert!(!matches("^1.4", "2.0.0"));
+        assert!(matches("^0.4.1", "0.4.9"));
+        assert!(!matches("^0.4.1", "0.5.0"));
+        assert!(matches("~1.4.2", "1.4.9"));
+        assert!(!matches("~1.4.2", "1.5.0"));
+    }
+
+    #[test]
+    fn rejects_malformed_ranges() {
+        assert!(VersionRange::parse("").is_err());
+        assert!(VersionRange::parse(">=1.x").is_err());
+        assert!(VersionRange::parse("1.2.3.4").is_err());
+        assert!(VersionRange::parse("=>1.0.0").is_err());
+    }
+
+    #[test]
+    fn non_semver_app_versions_fall_back_to_text_equality() {
+        assert!(app_version_matches("dev", "dev"));
+        assert!(!app_version_matches(">=1.0.0", "dev"));
+        assert!(app_version_matches("^2", "2.1.0-beta.1"));
+    }
+}
@@ -7,12 +7,25 @@ pub mod adapters;
 pub mod sdk;
 
 pub use app::core_service::CoreService;
-pub use domain::policy::{Decision, PolicyEngine, PolicyRule, PolicySet};
-pub use domain::risk::{Finding, RiskScore};
+pub use domain::policy::{
+    ConditionKind, Decision, Evaluation, PolicyDefaults, PolicyEngine, PolicyOverride, PolicyRule,
+    PolicySet, SignedPolicy,
+};
+pub use domain::policy_diff::{diff_rules, RuleChange, RuleChangeKind};
+pub use domain::policy_lint::{lint_policy, LintKind, LintSeverity, PolicyLint, KNOWN_ACTIONS};
+pub use domain::risk::{
+    Finding, RiskBreakdown, RiskContribution, RiskFactor, RiskObservation, RiskScore, RiskWeights,
+};
 pub use domain::pinning::SpkiPinset;
+pub use adapters::crypto::{Ed25519PublicKey, Ed25519Signer, Ed25519Verifier};
 pub use adapters::http::{HttpConfig, HttpTelemetryClient};
-pub use sdk::{PinningConfig, Sdk, SdkConfig};
+pub use adapters::telemetry_queue::{QueuedTelemetrySink, TelemetryQueueConfig};
+pub use sdk::{
+    PinningConfig, PolicyCacheConfig, PolicyVerificationConfig, PublicKeyConfig, Sdk, SdkConfig,
+    SigningConfig, FALLBACK_POLICY_ID,
+};
 pub use domain::telemetry::{
-    ActionContext, AttestationProvider, AttestationResult, AttestationStatus, DeviceInfo,
-    IntegritySignals, SessionInfo, TelemetryAuth, TelemetryEnvelope, TelemetryEvent,
+    ActionContext, AttestationChallenge, AttestationProvider, AttestationResult,
+    AttestationStatus, DeviceInfo, IntegritySignals, SessionInfo, SigningPayloadVersion,
+    TelemetryAuth, TelemetryEnvelope, TelemetryEvent,
 };
@@ -1,5 +1,5 @@
-use crate::domain::policy::PolicySet;
-use crate::domain::risk::{Finding, RiskScore};
+use crate::domain::policy::SignedPolicy;
+use crate::domain::risk::{Finding, RiskBreakdown, RiskScore};
 use crate::domain::telemetry::{
     AttestationResult, IntegritySignals, Platform, TelemetryEnvelope,
 };
@@ -17,8 +17,30 @@ impl PortError {
     }
 }
 
+/// Per-envelope outcome of `TelemetrySink::send_batch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    Delivered,
+    /// The backend refused the envelope; retrying it cannot succeed.
+    Rejected(String),
+    /// Transient failure; the envelope should be sent again later.
+    Retry(String),
+}
+
 pub trait TelemetrySink {
     fn send(&self, envelope: &TelemetryEnvelope) -> Result<(), PortError>;
+
+    /// Sends several envelopes, returning one status per envelope in order.
+    /// The default sends them one by one and treats every failure as transient.
+    fn send_batch(&self, envelopes: &[TelemetryEnvelope]) -> Result<Vec<DeliveryStatus>, PortError> {
+        Ok(envelopes
+            .iter()
+            .map(|envelope| match self.send(envelope) {
+                Ok(()) => DeliveryStatus::Delivered,
+                Err(err) => DeliveryStatus::Retry(err.message),
+            })
+            .collect())
+    }
 }
 
 pub trait PolicyStore {
@@ -28,7 +50,7 @@ pub trait PolicyStore {
         app_version: &str,
         env: &str,
         platform: Platform,
-    ) -> Result<PolicySet, PortError>;
+    ) -> Result<SignedPolicy, PortError>;
 }
 
 pub trait Clock {
@@ -39,6 +61,10 @@ pub trait CryptoSigner {
     fn sign(&self, payload: &[u8]) -> Result<String, PortError>;
 }
 
+pub trait SignatureVerifier {
+    fn verify(&self, payload: &[u8], signature: &str) -> Result<(), PortError>;
+}
+
 pub trait RiskScorer {
     fn score(
         &self,
@@ -46,4 +72,15 @@ pub trait RiskScorer {
         attestation: Option<&AttestationResult>,
         findings: &[Finding],
     ) -> RiskScore;
+
+    /// The score and what contributed to it. Scorers that cannot explain
+    /// themselves return an opaque breakdown.
+    fn breakdown(
+        &self,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+        findings: &[Finding],
+    ) -> RiskBreakdown {
+        RiskBreakdown::opaque(self.score(signals, attestation, findings))
+    }
 }
@@ -1,14 +1,20 @@
-use chrono::{DateTime, Utc};
+use std::path::PathBuf;
 
+use chrono::{DateTime, Duration, Utc};
+
+use crate::adapters::crypto::{Ed25519PublicKey, Ed25519Signer, Ed25519Verifier};
 use crate::adapters::http::{HttpConfig, HttpTelemetryClient};
+use crate::adapters::policy_cache::CachedPolicyStore;
+use crate::adapters::telemetry_queue::{QueuedTelemetrySink, TelemetryQueueConfig};
 use crate::domain::pinning::SpkiPinset;
-use crate::domain::policy::{Decision, PolicySet};
-use crate::domain::risk::{Finding, RiskScore};
+use crate::domain::policy::{Decision, Evaluation, PolicyDefaults, PolicySet};
+use crate::domain::risk::{Finding, RiskBreakdown, RiskScore, RiskWeights};
 use crate::domain::telemetry::{
-    ActionContext, AttestationResult, DeviceInfo, IntegritySignals, Platform, SessionInfo,
-    TelemetryAuth, TelemetryEnvelope, TelemetryEvent,
+    ActionContext, AttestationChallenge, AttestationResult, DeviceInfo, IntegritySignals, Platform, SessionInfo,
+    SigningPayloadVersion, TelemetryAuth, TelemetryEnvelope, TelemetryEvent,
 };
-use crate::ports::{Clock, CryptoSigner, PortError, RiskScorer};
+use crate::ports::{Clock, PortError, RiskScorer};
+use crate::app::policy_verification::VerifyingPolicyStore;
 use crate::CoreService;
 
 #[derive(Clone, Debug)]
@@ -34,6 +40,64 @@ impl PinningConfig {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct SigningConfig {
+    pub key_id: String,
+    /// Base64-encoded 32-byte Ed25519 seed provisioned for this install.
+    pub private_key: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct PublicKeyConfig {
+    pub key_id: String,
+    /// Base64-encoded 32-byte Ed25519 public key.
+    pub public_key: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct PolicyVerificationConfig {
+    /// Policy-service keys trusted to sign policies (more than one during rotation).
+    pub public_keys: Vec<PublicKeyConfig>,
+    pub max_age_secs: Option<u64>,
+}
+
+impl PolicyVerificationConfig {
+    fn verifier(&self) -> Result<Ed25519Verifier, PortError> {
+        let keys = self
+            .public_keys
+            .iter()
+            .map(|key| Ed25519PublicKey::from_base64(&key.key_id, &key.public_key))
+            .collect::<Result<Vec<_>, _>>()?;
+        if keys.is_empty() {
+            return Err(PortError::new("policy verification requires at least one public key"));
+        }
+        Ok(Ed25519Verifier::new(keys))
+    }
+
+    fn max_age(&self) -> Option<Duration> {
+        self.max_age_secs
+            .map(|secs| Duration::seconds(i64::try_from(secs).unwrap_or(i64::MAX)))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PolicyCacheConfig {
+    /// File holding the last verified policy; `None` disables the on-disk cache.
+    pub path: Option<PathBuf>,
+    /// Oldest `issued_at` a cached policy may have when served offline.
+    pub max_staleness_secs: Option<u64>,
+    /// `PolicyDto` JSON shipped with the app, served when neither the network
+    /// nor the cache can provide a policy. Must be signed by the policy-service.
+    pub bundled_policy: Option<String>,
+}
+
+impl PolicyCacheConfig {
+    fn max_staleness(&self) -> Option<Duration> {
+        self.max_staleness_secs
+            .map(|secs| Duration::seconds(i64::try_from(secs).unwrap_or(i64::MAX)))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SdkConfig {
     pub app_id: String,
@@ -44,11 +108,101 @@ pub struct SdkConfig {
     pub api_token: Option<String>,
     pub device_info: DeviceInfo,
     pub pinning: Option<PinningConfig>,
+    pub signing: SigningConfig,
+    pub policy_verification: PolicyVerificationConfig,
+    pub policy_cache: Option<PolicyCacheConfig>,
+    /// Durable telemetry queue; without it events are sent once, synchronously.
+    pub telemetry_queue: Option<TelemetryQueueConfig>,
+    /// Defaults served by `Sdk::fetch_policy` when no policy can be fetched or
+    /// verified (e.g. `PolicyDefaults::fail_closed()`). When `None` the fetch
+    /// error is returned to the caller.
+    pub no_policy_defaults: Option<PolicyDefaults>,
+    /// Stable per-install identifier the policy-service buckets staged
+    /// rollouts on; without it the device always gets the current policy.
+    pub device_id: Option<String>,
+}
+
+impl SdkConfig {
+    /// Config with the settings `Sdk::new` cannot do without; `platform` is
+    /// taken from `device_info`. The rest start unset and are added with the
+    /// `with_*` setters.
+    pub fn new(
+        app_id: impl Into<String>,
+        app_version: impl Into<String>,
+        env: impl Into<String>,
+        base_url: impl Into<String>,
+        device_info: DeviceInfo,
+        signing: SigningConfig,
+        policy_verification: PolicyVerificationConfig,
+    ) -> Self {
+        Self {
+            app_id: app_id.into(),
+            app_version: app_version.into(),
+            env: env.into(),
+            platform: device_info.platform.clone(),
+            base_url: base_url.into(),
+            api_token: None,
+            device_info,
+            pinning: None,
+            signing,
+            policy_verification,
+            policy_cache: None,
+            telemetry_queue: None,
+            no_policy_defaults: None,
+            device_id: None,
+        }
+    }
+
+    pub fn with_api_token(mut self, api_token: impl Into<String>) -> Self {
+        self.api_token = Some(api_token.into());
+        self
+    }
+
+    pub fn with_pinning(mut self, pinning: PinningConfig) -> Self {
+        self.pinning = Some(pinning);
+        self
+    }
+
+    pub fn with_policy_cache(mut self, policy_cache: PolicyCacheConfig) -> Self {
+        self.policy_cache = Some(policy_cache);
+        self
+    }
+
+    pub fn with_telemetry_queue(mut self, telemetry_queue: TelemetryQueueConfig) -> Self {
+        self.telemetry_queue = Some(telemetry_queue);
+        self
+    }
+
+    pub fn with_no_policy_defaults(mut self, defaults: PolicyDefaults) -> Self {
+        self.no_policy_defaults = Some(defaults);
+        self
+    }
+
+    pub fn with_device_id(mut self, device_id: impl Into<String>) -> Self {
+        self.device_id = Some(device_id.into());
+        self
+    }
 }
 
+/// `policy_id` of the rule-less policy built from `SdkConfig::no_policy_defaults`.
+pub const FALLBACK_POLICY_ID: &str = "sdk_fallback";
+
+type SdkPolicyStore = CachedPolicyStore<
+    VerifyingPolicyStore<HttpTelemetryClient, Ed25519Verifier, SystemClock>,
+    Ed25519Verifier,
+    SystemClock,
+>;
+
 pub struct Sdk {
     config: SdkConfig,
-    core: CoreService<HttpTelemetryClient, HttpTelemetryClient, SystemClock, NoopSigner, SimpleRiskScorer>,
+    http: HttpTelemetryClient,
+    core: CoreService<
+        QueuedTelemetrySink<HttpTelemetryClient>,
+        SdkPolicyStore,
+        SystemClock,
+        Ed25519Signer,
+        WeightedRiskScorer,
+    >,
 }
 
 impl Sdk {
@@ -56,26 +210,67 @@ impl Sdk {
         let http = HttpTelemetryClient::new(HttpConfig {
             base_url: config.base_url.clone(),
             api_token: config.api_token.clone(),
+            device_id: config.device_id.clone(),
         })?;
+        let signer =
+            Ed25519Signer::from_base64_seed(&config.signing.key_id, &config.signing.private_key)?;
+        let verifier = config.policy_verification.verifier()?;
+        let verifying_store = VerifyingPolicyStore::new(
+            http.clone(),
+            verifier.clone(),
+            SystemClock,
+            config.policy_verification.max_age(),
+        );
+        let policy_store = match &config.policy_cache {
+            Some(cache) => CachedPolicyStore::new(
+                verifying_store,
+                verifier,
+                SystemClock,
+                cache.path.clone(),
+                cache.max_staleness(),
+                cache
+                    .bundled_policy
+                    .as_deref()
+                    .map(SdkPolicyStore::parse_bundled)
+                    .transpose()?,
+            ),
+            None => CachedPolicyStore::new(verifying_store, verifier, SystemClock, None, None, None),
+        };
+
+        let telemetry = QueuedTelemetrySink::new(http.clone(), config.telemetry_queue.clone());
 
         let core = CoreService::new(
-            http.clone(),
-            http,
+            telemetry,
+            policy_store,
             SystemClock,
-            NoopSigner,
-            SimpleRiskScorer,
+            signer,
+            WeightedRiskScorer::default(),
         );
 
-        Ok(Self { config, core })
+        Ok(Self { config, http, core })
     }
 
     pub fn fetch_policy(&self) -> Result<PolicySet, PortError> {
-        self.core.fetch_policy(
+        let fetched = self.core.fetch_policy(
             &self.config.app_id,
             &self.config.app_version,
             &self.config.env,
             self.config.platform.clone(),
-        )
+        );
+
+        match (fetched, &self.config.no_policy_defaults) {
+            (Err(_), Some(defaults)) => Ok(PolicySet {
+                policy_id: FALLBACK_POLICY_ID.to_string(),
+                app_id: self.config.app_id.clone(),
+                app_version: self.config.app_version.clone(),
+                env: self.config.env.clone(),
+                rules: Vec::new(),
+                defaults: defaults.clone(),
+                risk_weights: None,
+                overrides: Vec::new(),
+            }),
+            (fetched, _) => fetched,
+        }
     }
 
     pub fn emit_event(
@@ -84,6 +279,19 @@ impl Sdk {
         signals: IntegritySignals,
         attestation: Option<AttestationResult>,
         session: Option<SessionInfo>,
+    ) -> Result<TelemetryEnvelope, PortError> {
+        self.emit_event_with_risk(action, signals, attestation, session, None)
+    }
+
+    /// Like [`Sdk::emit_event`], attaching the breakdown of the risk score
+    /// behind the decision (e.g. `Evaluation::risk_breakdown`).
+    pub fn emit_event_with_risk(
+        &self,
+        action: ActionContext,
+        signals: IntegritySignals,
+        attestation: Option<AttestationResult>,
+        session: Option<SessionInfo>,
+        risk_breakdown: Option<RiskBreakdown>,
     ) -> Result<TelemetryEnvelope, PortError> {
         let event = TelemetryEvent {
             event_id: uuid(),
@@ -97,6 +305,8 @@ impl Sdk {
             action,
             timestamp: None,
             signature: None,
+            payload_version: SigningPayloadVersion::CURRENT,
+            risk_breakdown,
         };
 
         let auth = TelemetryAuth {
@@ -106,6 +316,27 @@ impl Sdk {
         self.core.emit_telemetry(event, auth)
     }
 
+    /// Fetches a fresh challenge for `action` in `session`. Bind the App Attest
+    /// assertion or Play Integrity request to it and set it as
+    /// `AttestationResult::challenge` on the event reporting the action.
+    pub fn request_attestation_challenge(
+        &self,
+        action: &ActionContext,
+        session: &SessionInfo,
+    ) -> Result<AttestationChallenge, PortError> {
+        self.http.request_attestation_challenge(
+            &self.config.app_id,
+            &session.session_id,
+            &action.name,
+        )
+    }
+
+    /// Sends events left in the telemetry queue, e.g. when connectivity returns.
+    /// Returns the number of events still queued.
+    pub fn flush_telemetry(&self) -> Result<usize, PortError> {
+        self.core.telemetry_sink().flush()
+    }
+
     pub fn decide_action(
         &self,
         policy: &PolicySet,
@@ -118,6 +349,18 @@ impl Sdk {
             .decide_action(policy, action, signals, attestation, findings)
     }
 
+    pub fn explain_action(
+        &self,
+        policy: &PolicySet,
+        action: &ActionContext,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+        findings: &[Finding],
+    ) -> Evaluation {
+        self.core
+            .explain_action(policy, action, signals, attestation, findings)
+    }
+
     pub fn baseline_signals() -> IntegritySignals {
         IntegritySignals {
             jailbreak: false,
@@ -146,40 +389,55 @@ impl Clock for SystemClock {
     }
 }
 
-pub struct NoopSigner;
+/// Scores with the default `RiskWeights`, like `WeightedRiskScorer::default()`.
+#[deprecated(note = "use `WeightedRiskScorer::default()`, which also honours policy weights")]
+pub struct SimpleRiskScorer;
 
-impl CryptoSigner for NoopSigner {
-    fn sign(&self, _payload: &[u8]) -> Result<String, PortError> {
-        Ok("stub-signature".to_string())
+#[allow(deprecated)]
+impl RiskScorer for SimpleRiskScorer {
+    fn score(
+        &self,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+        findings: &[Finding],
+    ) -> RiskScore {
+        RiskWeights::default().score(signals, attestation, findings)
+    }
+
+    fn breakdown(
+        &self,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+        findings: &[Finding],
+    ) -> RiskBreakdown {
+        RiskWeights::default().breakdown(signals, attestation, findings)
     }
 }
 
-pub struct SimpleRiskScorer;
+/// Scores with `RiskWeights`, the same model the backend uses. Policies that
+/// carry their own weights override these in `CoreService::explain_action`.
+#[derive(Default)]
+pub struct WeightedRiskScorer {
+    pub weights: RiskWeights,
+}
 
-impl RiskScorer for SimpleRiskScorer {
+impl RiskScorer for WeightedRiskScorer {
     fn score(
         &self,
         signals: &IntegritySignals,
         attestation: Option<&AttestationResult>,
         findings: &[Finding],
     ) -> RiskScore {
-        let mut score = 0u32;
-        if signals.jailbreak || signals.root {
-            score += 40;
-        }
-        if signals.debugger || signals.hooking {
-            score += 30;
-        }
-        if signals.proxy_detected {
-            score += 20;
-        }
-        if let Some(att) = attestation {
-            if matches!(att.status, crate::domain::telemetry::AttestationStatus::Fail) {
-                score += 30;
-            }
-        }
-        score += findings.len().saturating_mul(5) as u32;
-        RiskScore::new(score)
+        self.weights.score(signals, attestation, findings)
+    }
+
+    fn breakdown(
+        &self,
+        signals: &IntegritySignals,
+        attestation: Option<&AttestationResult>,
+        findings: &[Finding],
+    ) -> RiskBreakdown {
+        self.weights.breakdown(signals, attestation, findings)
     }
 }
 
@@ -188,3 +446,90 @@ fn uuid() -> String {
     let ts = Utc::now().timestamp_nanos_opt().unwrap_or(0);
     format!("evt-{}", ts)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::risk::Severity;
+
+    #[test]
+    #[allow(deprecated)]
+    fn simple_risk_scorer_keeps_the_default_weighted_scores() {
+        let clean = Sdk::baseline_signals();
+        let compromised = IntegritySignals {
+            jailbreak: true,
+            root: true,
+            debugger: true,
+            ..Sdk::baseline_signals()
+        };
+        let failed = AttestationResult {
+            provider: crate::domain::telemetry::AttestationProvider::AppAttest,
+            status: crate::domain::telemetry::AttestationStatus::Fail,
+            timestamp: None,
+            challenge: None,
+        };
+        let findings = [Finding {
+            category: "storage".to_string(),
+            severity: Severity::Medium,
+        }];
+
+        let cases = [
+            (&clean, None, &[][..], 0),
+            (&compromised, None, &[][..], 70),
+            (&clean, Some(&failed), &findings[..], 40),
+            (&compromised, Some(&failed), &findings[..], 100),
+        ];
+        for (signals, attestation, findings, expected) in cases {
+            let simple = SimpleRiskScorer.score(signals, attestation, findings);
+            assert_eq!(simple.value(), expected);
+            assert_eq!(
+                WeightedRiskScorer::default().score(signals, attestation, findings),
+                simple
+            );
+            assert_eq!(
+                SimpleRiskScorer.breakdown(signals, attestation, findings),
+                WeightedRiskScorer::default().breakdown(signals, attestation, findings)
+            );
+        }
+    }
+
+    #[test]
+    fn sdk_config_new_leaves_optional_settings_unset_until_added() {
+        let config = SdkConfig::new(
+            "fintech.mobile",
+            "1.0.0",
+            "prod",
+            "https://api.example.com",
+            DeviceInfo {
+                platform: Platform::Android,
+                os_version: "14".to_string(),
+                model: "Pixel".to_string(),
+            },
+            SigningConfig {
+                key_id: "k1".to_string(),
+                private_key: "seed".to_string(),
+            },
+            PolicyVerificationConfig {
+                public_keys: Vec::new(),
+                max_age_secs: None,
+            },
+        );
+        assert_eq!(config.platform, Platform::Android);
+        assert!(config.api_token.is_none());
+        assert!(config.pinning.is_none());
+        assert!(config.policy_cache.is_none());
+        assert!(config.telemetry_queue.is_none());
+        assert!(config.no_policy_defaults.is_none());
+        assert!(config.device_id.is_none());
+
+        let config = config
+            .with_api_token("token")
+            .with_telemetry_queue(TelemetryQueueConfig::new("queue.jsonl"))
+            .with_no_policy_defaults(PolicyDefaults::fail_closed())
+            .with_device_id("install-1");
+        assert_eq!(config.api_token.as_deref(), Some("token"));
+        assert!(config.telemetry_queue.is_some());
+        assert_eq!(config.no_policy_defaults, Some(PolicyDefaults::fail_closed()));
+        assert_eq!(config.device_id.as_deref(), Some("install-1"));
+    }
+}
@@ -26,6 +26,90 @@ final class PantherSecurityCore {
         return mapDecision(decision)
     }
 
+    func explain(
+        policy: PantherSecurityPolicyResponse,
+        action: PantherSecurityActionContext,
+        signals: PantherSecurityIntegritySignals,
+        attestationStatus: String?,
+        riskScore: UInt32
+    ) -> PantherSecurityEvaluation {
+        var out = FfiEvaluation(decision: 3, rule_index: -1, matched_conditions: 0, risk_score: riskScore, default_applied: 0, override_index: -1)
+        withFfiPolicySet(policy: policy) { policyPtr in
+            withFfiStr(action.name) { actionStr in
+                let ffiSignals = FfiIntegritySignals(
+                    jailbreak: signals.jailbreak ? 1 : 0,
+                    root: signals.root ? 1 : 0,
+                    debugger: signals.debugger ? 1 : 0,
+                    hooking: signals.hooking ? 1 : 0,
+                    proxy_detected: signals.proxyDetected ? 1 : 0
+                )
+                let attestationCode = mapAttestationStatus(attestationStatus)
+                _ = ps_explain_policy(policyPtr, actionStr, ffiSignals, attestationCode, riskScore, &out)
+            }
+        }
+
+        return PantherSecurityEvaluation(
+            decision: mapDecision(out.decision),
+            ruleIndex: out.rule_index >= 0 ? Int(out.rule_index) : nil,
+            matchedConditions: mapConditionMask(out.matched_conditions),
+            riskScore: out.risk_score,
+            defaultApplied: out.default_applied == 1,
+            overrideIndex: out.override_index >= 0 ? Int(out.override_index) : nil
+        )
+    }
+
+    func riskBreakdown(
+        policy: PantherSecurityPolicyResponse?,
+        signals: PantherSecurityIntegritySignals,
+        attestationStatus: String?,
+        findings: [PantherSecurityFinding]
+    ) -> PantherSecurityRiskBreakdown? {
+        let ffiSignals = FfiIntegritySignals(
+            jailbreak: signals.jailbreak ? 1 : 0,
+            root: signals.root ? 1 : 0,
+            debugger: signals.debugger ? 1 : 0,
+            hooking: signals.hooking ? 1 : 0,
+            proxy_detected: signals.proxyDetected ? 1 : 0
+        )
+        let attestationCode = mapAttestationStatus(attestationStatus)
+        let capacity = 6 + findings.count
+        var out = [FfiRiskContribution](
+            repeating: FfiRiskContribution(factor: 0, finding_index: -1, weight: 0, points: 0, capped: 0),
+            count: capacity
+        )
+        var outLen = 0
+
+        let score: Int32 = withFfiStrArray(findings.map { $0.category }) { categoryPtrs, count in
+            let cFindings = (0..<count).map { index in
+                FfiFinding(category: categoryPtrs![index], severity: mapSeverityCode(findings[index].severity))
+            }
+            return cFindings.withUnsafeBufferPointer { findingsBuffer in
+                out.withUnsafeMutableBufferPointer { outBuffer in
+                    guard let policy else {
+                        return ps_risk_breakdown(nil, ffiSignals, attestationCode, findingsBuffer.baseAddress, findingsBuffer.count, outBuffer.baseAddress, capacity, &outLen)
+                    }
+                    return withFfiPolicySet(policy: policy) { policyPtr in
+                        ps_risk_breakdown(policyPtr, ffiSignals, attestationCode, findingsBuffer.baseAddress, findingsBuffer.count, outBuffer.baseAddress, capacity, &outLen)
+                    }
+                }
+            }
+        }
+        guard score >= 0 else { return nil }
+
+        let factors = out.prefix(outLen).map { entry -> PantherSecurityRiskContribution in
+            let finding = entry.finding_index >= 0 ? findings[Int(entry.finding_index)] : nil
+            return PantherSecurityRiskContribution(
+                factor: riskFactorNames[Int(entry.factor)] ?? "unknown",
+                category: finding?.category,
+                severity: finding?.severity.lowercased(),
+                weight: entry.weight,
+                points: entry.points,
+                capped: entry.capped == 1
+            )
+        }
+        return PantherSecurityRiskBreakdown(score: UInt32(score), factors: factors)
+    }
+
     func validatePinning(pinning: PantherSecurityPinning, presentedSpkiHash: String) -> Bool {
         return withFfiPinset(pinning: pinning) { pinset in
             withFfiStr(presentedSpkiHash) { presented in
@@ -41,15 +125,97 @@ final class PantherSecurityCore {
                 withFfiStr(policy.appVersion) { appVersion in
                     withFfiStr(policy.env) { env in
                         withFfiPolicyRules(rules: rules) { rulesPtr, rulesLen in
-                            var policySet = FfiPolicySet(
-                                policy_id: policyId,
-                                app_id: appId,
-                                app_version: appVersion,
-                                env: env,
-                                rules_ptr: rulesPtr,
-                                rules_len: rulesLen
-                            )
-                            return body(&policySet)
+                            withFfiActionDefaults(policy.actionDefaults ?? [:]) { defaultsPtr, defaultsLen in
+                                withFfiPolicyOverrides(policy.overrides ?? []) { overridesPtr, overridesLen in
+                                    withFfiRiskWeights(policy.riskWeights) { weightsPtr in
+                                        var policySet = FfiPolicySet(
+                                            policy_id: policyId,
+                                            app_id: appId,
+                                            app_version: appVersion,
+                                            env: env,
+                                            rules_ptr: rulesPtr,
+                                            rules_len: rulesLen,
+                                            default_decision: mapDecisionCode(policy.defaultDecision ?? "ALLOW"),
+                                            action_defaults_ptr: defaultsPtr,
+                                            action_defaults_len: defaultsLen,
+                                            overrides_ptr: overridesPtr,
+                                            overrides_len: overridesLen,
+                                            risk_weights: weightsPtr
+                                        )
+                                        return body(&policySet)
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    private func withFfiRiskWeights<T>(_ weights: PantherSecurityRiskWeights?, _ body: (UnsafePointer<FfiRiskWeights>?) -> T) -> T {
+        guard let weights else {
+            return body(nil)
+        }
+        let categories = (weights.findingCategoryPct ?? [:]).sorted { $0.key < $1.key }
+        return withFfiStrArray(categories.map { $0.key }) { categoryPtrs, count in
+            let cCategories = (0..<count).map { index in
+                FfiFindingCategoryWeight(category: categoryPtrs![index], pct: categories[index].value)
+            }
+            return cCategories.withUnsafeBufferPointer { buffer in
+                var ffiWeights = FfiRiskWeights(
+                    jailbreak: weights.jailbreak ?? UInt32.max,
+                    root: weights.root ?? UInt32.max,
+                    debugger: weights.debugger ?? UInt32.max,
+                    hooking: weights.hooking ?? UInt32.max,
+                    proxy_detected: weights.proxyDetected ?? UInt32.max,
+                    attestation_fail: weights.attestationFail ?? UInt32.max,
+                    attestation_unknown: weights.attestationUnknown ?? UInt32.max,
+                    finding_low: weights.findingLow ?? UInt32.max,
+                    finding_medium: weights.findingMedium ?? UInt32.max,
+                    finding_high: weights.findingHigh ?? UInt32.max,
+                    finding_critical: weights.findingCritical ?? UInt32.max,
+                    finding_categories_ptr: buffer.baseAddress,
+                    finding_categories_len: buffer.count
+                )
+                return body(&ffiWeights)
+            }
+        }
+    }
+
+    private func withFfiActionDefaults<T>(_ defaults: [String: String], _ body: (UnsafePointer<FfiActionDefault>?, Int) -> T) -> T {
+        let entries = defaults.sorted { $0.key < $1.key }
+        return withFfiStrArray(entries.map { $0.key }) { actionPtrs, count in
+            guard let actionPtrs else {
+                return body(nil, 0)
+            }
+            let cDefaults = (0..<count).map { index in
+                FfiActionDefault(action: actionPtrs[index], decision: mapDecisionCode(entries[index].value))
+            }
+            return cDefaults.withUnsafeBufferPointer { buffer in
+                body(buffer.baseAddress, buffer.count)
+            }
+        }
+    }
+
+    private func withFfiPolicyOverrides<T>(_ overrides: [PantherSecurityPolicyOverride], _ body: (UnsafePointer<FfiPolicyOverride>?, Int) -> T) -> T {
+        guard let first = overrides.first else {
+            return body(nil, 0)
+        }
+
+        return withFfiStrArray(first.actions ?? []) { actionsPtr, actionsLen in
+            withFfiStrArray(first.exceptActions ?? []) { exceptPtr, exceptLen in
+                withFfiStr(first.expiresAt) { expiresAt in
+                    let head = FfiPolicyOverride(
+                        actions: FfiStrArray(ptr: actionsPtr, len: actionsLen),
+                        except_actions: FfiStrArray(ptr: exceptPtr, len: exceptLen),
+                        decision: mapDecisionCode(first.decision),
+                        expires_at: expiresAt
+                    )
+                    return withFfiPolicyOverrides(Array(overrides.dropFirst())) { restPtr, restLen in
+                        let cOverrides = [head] + UnsafeBufferPointer(start: restPtr, count: restLen)
+                        return cOverrides.withUnsafeBufferPointer { buffer in
+                            body(buffer.baseAddress, buffer.count)
                         }
                     }
                 }
@@ -98,11 +264,18 @@ final class PantherSecurityCore {
                 let conditions = rule.conditions
                 let ffiConditions = FfiPolicyConditions(
                     attestation_status: mapAttestationStatus(conditions?.attestation),
+                    jailbreak: mapOptionalBool(conditions?.jailbreak),
+                    root: mapOptionalBool(conditions?.root),
                     debugger: mapOptionalBool(conditions?.debugger),
                     hooking: mapOptionalBool(conditions?.hooking),
                     proxy_detected: mapOptionalBool(conditions?.proxyDetected),
                     app_version: appVersionStr,
-                    risk_score_gte: mapRiskScoreGte(conditions?.riskScoreGte)
+                    risk_score_gte: mapRiskScoreGte(conditions?.riskScoreGte),
+                    all_of_ptr: nil,
+                    all_of_len: 0,
+                    any_of_ptr: nil,
+                    any_of_len: 0,
+                    not_ptr: nil
                 )
 
                 let ffiRule = FfiPolicyRule(
@@ -176,6 +349,42 @@ private func mapDecision(_ value: UInt32) -> PantherSecurityDecision {
     }
 }
 
+private let conditionBits: [(UInt32, String)] = [
+    (1 << 0, "attestation"),
+    (1 << 1, "jailbreak"),
+    (1 << 2, "root"),
+    (1 << 3, "debugger"),
+    (1 << 4, "hooking"),
+    (1 << 5, "proxy_detected"),
+    (1 << 6, "app_version"),
+    (1 << 7, "risk_score_gte")
+]
+
+private let riskFactorNames: [Int: String] = [
+    0: "jailbreak",
+    1: "root",
+    2: "debugger",
+    3: "hooking",
+    4: "proxy_detected",
+    5: "attestation_fail",
+    6: "attestation_unknown",
+    7: "finding"
+]
+
+private func mapSeverityCode(_ value: String) -> UInt32 {
+    switch value.lowercased() {
+    case "low": return 0
+    case "medium": return 1
+    case "high": return 2
+    case "critical": return 3
+    default: return UInt32.max
+    }
+}
+
+private func mapConditionMask(_ mask: UInt32) -> [String] {
+    conditionBits.filter { mask & $0.0 != 0 }.map { $0.1 }
+}
+
 private func withFfiStr<T>(_ value: String, _ body: (FfiStr) -> T) -> T {
     return value.withCString { cStr in
         let raw = UnsafeRawPointer(cStr).assumingMemoryBound(to: UInt8.self)
@@ -8,8 +8,10 @@ public struct PantherSecurityConfiguration: Equatable {
     public let apiToken: String?
     public let devicePlatform: String
     public let pinning: PantherSecurityPinning?
+    /// Stable per-install id the policy-service buckets staged rollouts on.
+    public let deviceId: String?
 
-    public init(baseURL: URL, appId: String, appVersion: String, env: String, apiToken: String?, devicePlatform: String, pinning: PantherSecurityPinning? = nil) {
+    public init(baseURL: URL, appId: String, appVersion: String, env: String, apiToken: String?, devicePlatform: String, pinning: PantherSecurityPinning? = nil, deviceId: String? = nil) {
         self.baseURL = baseURL
         self.appId = appId
         self.appVersion = appVersion
@@ -17,6 +19,7 @@ public struct PantherSecurityConfiguration: Equatable {
         self.apiToken = apiToken
         self.devicePlatform = devicePlatform
         self.pinning = pinning
+        self.deviceId = deviceId
     }
 }
 
@@ -40,6 +43,10 @@ public struct PantherSecurityPolicyResponse: Codable, Equatable {
     public let appVersion: String
     public let env: String
     public let rules: [PantherSecurityPolicyRule]
+    public let defaultDecision: String?
+    public let actionDefaults: [String: String]?
+    public let overrides: [PantherSecurityPolicyOverride]?
+    public let riskWeights: PantherSecurityRiskWeights?
     public let signature: String
     public let issuedAt: String
 
@@ -49,21 +56,104 @@ public struct PantherSecurityPolicyResponse: Codable, Equatable {
         case appVersion = "app_version"
         case env
         case rules
+        case defaultDecision = "default_decision"
+        case actionDefaults = "action_defaults"
+        case overrides
+        case riskWeights = "risk_weights"
         case signature
         case issuedAt = "issued_at"
     }
 
-    public init(policyId: String, appId: String, appVersion: String, env: String, rules: [PantherSecurityPolicyRule], signature: String, issuedAt: String) {
+    public init(policyId: String, appId: String, appVersion: String, env: String, rules: [PantherSecurityPolicyRule], defaultDecision: String? = nil, actionDefaults: [String: String]? = nil, overrides: [PantherSecurityPolicyOverride]? = nil, riskWeights: PantherSecurityRiskWeights? = nil, signature: String, issuedAt: String) {
         self.policyId = policyId
         self.appId = appId
         self.appVersion = appVersion
         self.env = env
         self.rules = rules
+        self.defaultDecision = defaultDecision
+        self.actionDefaults = actionDefaults
+        self.overrides = overrides
+        self.riskWeights = riskWeights
         self.signature = signature
         self.issuedAt = issuedAt
     }
 }
 
+/// Per-policy risk weights; nil fields keep the default weight.
+public struct PantherSecurityRiskWeights: Codable, Equatable {
+    public let jailbreak: UInt32?
+    public let root: UInt32?
+    public let debugger: UInt32?
+    public let hooking: UInt32?
+    public let proxyDetected: UInt32?
+    public let attestationFail: UInt32?
+    public let attestationUnknown: UInt32?
+    public let findingLow: UInt32?
+    public let findingMedium: UInt32?
+    public let findingHigh: UInt32?
+    public let findingCritical: UInt32?
+    public let findingCategoryPct: [String: UInt32]?
+    public let decayHalfLifeSecs: UInt32?
+
+    enum CodingKeys: String, CodingKey {
+        case jailbreak
+        case root
+        case debugger
+        case hooking
+        case proxyDetected = "proxy_detected"
+        case attestationFail = "attestation_fail"
+        case attestationUnknown = "attestation_unknown"
+        case findingLow = "finding_low"
+        case findingMedium = "finding_medium"
+        case findingHigh = "finding_high"
+        case findingCritical = "finding_critical"
+        case findingCategoryPct = "finding_category_pct"
+        case decayHalfLifeSecs = "decay_half_life_secs"
+    }
+
+    public init(jailbreak: UInt32? = nil, root: UInt32? = nil, debugger: UInt32? = nil, hooking: UInt32? = nil, proxyDetected: UInt32? = nil, attestationFail: UInt32? = nil, attestationUnknown: UInt32? = nil, findingLow: UInt32? = nil, findingMedium: UInt32? = nil, findingHigh: UInt32? = nil, findingCritical: UInt32? = nil, findingCategoryPct: [String: UInt32]? = nil, decayHalfLifeSecs: UInt32? = nil) {
+        self.jailbreak = jailbreak
+        self.root = root
+        self.debugger = debugger
+        self.hooking = hooking
+        self.proxyDetected = proxyDetected
+        self.attestationFail = attestationFail
+        self.attestationUnknown = attestationUnknown
+        self.findingLow = findingLow
+        self.findingMedium = findingMedium
+        self.findingHigh = findingHigh
+        self.findingCritical = findingCritical
+        self.findingCategoryPct = findingCategoryPct
+        self.decayHalfLifeSecs = decayHalfLifeSecs
+    }
+}
+
+/// Kill switch forcing `decision` ahead of the rules until `expiresAt`;
+/// `actions` nil applies it to every action except `exceptActions`.
+public struct PantherSecurityPolicyOverride: Codable, Equatable {
+    public let actions: [String]?
+    public let exceptActions: [String]?
+    public let decision: String
+    public let expiresAt: String
+    public let reason: String?
+
+    enum CodingKeys: String, CodingKey {
+        case actions
+        case exceptActions = "except_actions"
+        case decision
+        case expiresAt = "expires_at"
+        case reason
+    }
+
+    public init(actions: [String]? = nil, exceptActions: [String]? = nil, decision: String, expiresAt: String, reason: String? = nil) {
+        self.actions = actions
+        self.exceptActions = exceptActions
+        self.decision = decision
+        self.expiresAt = expiresAt
+        self.reason = reason
+    }
+}
+
 public struct PantherSecurityPolicyRule: Codable, Equatable {
     public let action: String
     public let decision: String
@@ -78,6 +168,8 @@ public struct PantherSecurityPolicyRule: Codable, Equatable {
 
 public struct PantherSecurityPolicyConditions: Codable, Equatable {
     public let attestation: String?
+    public let jailbreak: Bool?
+    public let root: Bool?
     public let debugger: Bool?
     public let hooking: Bool?
     public let proxyDetected: Bool?
@@ -86,6 +178,8 @@ public struct PantherSecurityPolicyConditions: Codable, Equatable {
 
     enum CodingKeys: String, CodingKey {
         case attestation
+        case jailbreak
+        case root
         case debugger
         case hooking
         case proxyDetected = "proxy_detected"
@@ -93,8 +187,10 @@ public struct PantherSecurityPolicyConditions: Codable, Equatable {
         case riskScoreGte = "risk_score_gte"
     }
 
-    public init(attestation: String?, debugger: Bool?, hooking: Bool?, proxyDetected: Bool?, appVersion: String?, riskScoreGte: Int?) {
+    public init(attestation: String?, jailbreak: Bool? = nil, root: Bool? = nil, debugger: Bool?, hooking: Bool?, proxyDetected: Bool?, appVersion: String?, riskScoreGte: Int?) {
         self.attestation = attestation
+        self.jailbreak = jailbreak
+        self.root = root
         self.debugger = debugger
         self.hooking = hooking
         self.proxyDetected = proxyDetected
@@ -113,6 +209,8 @@ public struct PantherSecurityTelemetryRequest: Codable, Equatable {
     public let action: PantherSecurityActionContext
     public let timestamp: String
     public let signature: String
+    public let attestation: PantherSecurityAttestationResult?
+    public let riskBreakdown: PantherSecurityRiskBreakdown?
 
     enum CodingKeys: String, CodingKey {
         case eventId = "event_id"
@@ -124,9 +222,11 @@ public struct PantherSecurityTelemetryRequest: Codable, Equatable {
         case action
         case timestamp
         case signature
+        case attestation
+        case riskBreakdown = "risk_breakdown"
     }
 
-    public init(eventId: String, appId: String, appVersion: String, env: String, device: PantherSecurityDeviceInfo, signals: PantherSecurityIntegritySignals, action: PantherSecurityActionContext, timestamp: String, signature: String) {
+    public init(eventId: String, appId: String, appVersion: String, env: String, device: PantherSecurityDeviceInfo, signals: PantherSecurityIntegritySignals, action: PantherSecurityActionContext, timestamp: String, signature: String, attestation: PantherSecurityAttestationResult? = nil, riskBreakdown: PantherSecurityRiskBreakdown? = nil) {
         self.eventId = eventId
         self.appId = appId
         self.appVersion = appVersion
@@ -136,6 +236,8 @@ public struct PantherSecurityTelemetryRequest: Codable, Equatable {
         self.action = action
         self.timestamp = timestamp
         self.signature = signature
+        self.attestation = attestation
+        self.riskBreakdown = riskBreakdown
     }
 }
 
@@ -191,9 +293,97 @@ public struct PantherSecurityActionContext: Codable, Equatable {
     }
 }
 
+public struct PantherSecurityAttestationResult: Codable, Equatable {
+    /// "app_attest", "play_integrity" or "none".
+    public let provider: String
+    /// "pass", "fail" or "unknown".
+    public let result: String
+    public let timestamp: String?
+    /// Server-issued challenge the evidence was bound to.
+    public let challenge: String?
+
+    public init(provider: String, result: String, timestamp: String?, challenge: String? = nil) {
+        self.provider = provider
+        self.result = result
+        self.timestamp = timestamp
+        self.challenge = challenge
+    }
+}
+
+/// Single-use nonce for one action of a session; bind App Attest assertions to it.
+public struct PantherSecurityAttestationChallenge: Codable, Equatable {
+    public let challenge: String
+    public let expiresAt: String
+
+    enum CodingKeys: String, CodingKey {
+        case challenge
+        case expiresAt = "expires_at"
+    }
+
+    public init(challenge: String, expiresAt: String) {
+        self.challenge = challenge
+        self.expiresAt = expiresAt
+    }
+}
+
 public enum PantherSecurityDecision: String {
     case allow = "ALLOW"
     case stepUp = "STEP_UP"
     case degrade = "DEGRADE"
     case deny = "DENY"
 }
+
+public struct PantherSecurityEvaluation {
+    public let decision: PantherSecurityDecision
+    /// Index of the rule that fired, nil when the default decision applied.
+    public let ruleIndex: Int?
+    /// Matched condition names, e.g. "debugger", "risk_score_gte".
+    public let matchedConditions: [String]
+    public let riskScore: UInt32
+    public let defaultApplied: Bool
+    /// Index of the override that forced the decision, nil when none did.
+    public let overrideIndex: Int?
+}
+
+public struct PantherSecurityFinding: Codable, Equatable {
+    public let category: String
+    /// "low", "medium", "high" or "critical".
+    public let severity: String
+
+    public init(category: String, severity: String) {
+        self.category = category
+        self.severity = severity
+    }
+}
+
+/// Why a device got its risk score. Factor points add up to `score`.
+public struct PantherSecurityRiskBreakdown: Codable, Equatable {
+    public let score: UInt32
+    public let factors: [PantherSecurityRiskContribution]
+
+    public init(score: UInt32, factors: [PantherSecurityRiskContribution]) {
+        self.score = score
+        self.factors = factors
+    }
+}
+
+public struct PantherSecurityRiskContribution: Codable, Equatable {
+    /// e.g. "jailbreak", "attestation_fail", "finding".
+    public let factor: String
+    /// Set only for "finding".
+    public let category: String?
+    public let severity: String?
+    public let weight: UInt32
+    public let points: UInt32
+    /// True when the 100-point cap cut this factor's points short.
+    public let capped: Bool
+
+    public init(factor: String, category: String?, severity: String?, weight: UInt32, points: UInt32, capped: Bool) {
+        self.factor = factor
+        self.category = category
+        self.severity = severity
+        self.weight = weight
+        self.points = points
+        self.capped = capped
+    }
+}
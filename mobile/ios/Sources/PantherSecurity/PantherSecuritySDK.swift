@@ -3,6 +3,7 @@ import Foundation
 public protocol PantherSecurityClient {
     func fetchPolicy(config: PantherSecurityConfiguration) async throws -> PantherSecurityPolicyResponse
     func sendTelemetry(_ event: PantherSecurityTelemetryRequest, config: PantherSecurityConfiguration) async throws
+    func requestAttestationChallenge(action: String, sessionId: String, config: PantherSecurityConfiguration) async throws -> PantherSecurityAttestationChallenge
 }
 
 public final class PantherSecuritySDK {
@@ -34,6 +35,14 @@ public final class PantherSecuritySDK {
         try await client.sendTelemetry(event, config: config)
     }
 
+    /// Fetches a fresh challenge for `action`; set it as the event's `attestation.challenge`.
+    public func requestAttestationChallenge(action: String, sessionId: String) async throws -> PantherSecurityAttestationChallenge {
+        guard let config = configuration else {
+            throw PantherSecurityError.notConfigured
+        }
+        return try await client.requestAttestationChallenge(action: action, sessionId: sessionId, config: config)
+    }
+
     public func evaluateDecision(
         policy: PantherSecurityPolicyResponse,
         action: PantherSecurityActionContext,
@@ -44,6 +53,27 @@ public final class PantherSecuritySDK {
         core.evaluate(policy: policy, action: action, signals: signals, attestationStatus: attestationStatus, riskScore: riskScore)
     }
 
+    public func explainDecision(
+        policy: PantherSecurityPolicyResponse,
+        action: PantherSecurityActionContext,
+        signals: PantherSecurityIntegritySignals,
+        attestationStatus: String? = nil,
+        riskScore: UInt32 = 0
+    ) -> PantherSecurityEvaluation {
+        core.explain(policy: policy, action: action, signals: signals, attestationStatus: attestationStatus, riskScore: riskScore)
+    }
+
+    /// Scores with `policy.riskWeights`, or the defaults when the policy is nil
+    /// or carries none; pass `breakdown.score` as `riskScore`.
+    public func riskBreakdown(
+        policy: PantherSecurityPolicyResponse? = nil,
+        signals: PantherSecurityIntegritySignals,
+        attestationStatus: String? = nil,
+        findings: [PantherSecurityFinding] = []
+    ) -> PantherSecurityRiskBreakdown? {
+        core.riskBreakdown(policy: policy, signals: signals, attestationStatus: attestationStatus, findings: findings)
+    }
+
     public func validatePinning(presentedSpkiHash: String) -> Bool {
         guard let config = configuration, let pinning = config.pinning else {
             return true
@@ -76,6 +106,9 @@ public final class HTTPClient: PantherSecurityClient {
             URLQueryItem(name: "env", value: config.env),
             URLQueryItem(name: "device_platform", value: config.devicePlatform)
         ]
+        if let deviceId = config.deviceId {
+            components?.queryItems?.append(URLQueryItem(name: "device_id", value: deviceId))
+        }
         guard let url = components?.url else {
             throw PantherSecurityError.invalidURL
         }
@@ -118,4 +151,29 @@ public final class HTTPClient: PantherSecurityClient {
             throw PantherSecurityError.httpError(http.statusCode)
         }
     }
+
+    public func requestAttestationChallenge(action: String, sessionId: String, config: PantherSecurityConfiguration) async throws -> PantherSecurityAttestationChallenge {
+        let url = config.baseURL.appendingPathComponent("/v1/attestation/challenge")
+        var request = URLRequest(url: url)
+        request.httpMethod = "POST"
+        request.setValue("application/json", forHTTPHeaderField: "Content-Type")
+        if let token = config.apiToken {
+            request.setValue("Bearer \(token)", forHTTPHeaderField: "Authorization")
+        }
+        request.httpBody = try JSONEncoder().encode([
+            "app_id": config.appId,
+            "session_id": sessionId,
+            "action": action
+        ])
+
+        let (data, response) = try await session.data(for: request)
+        guard let http = response as? HTTPURLResponse else {
+            throw PantherSecurityError.decodingError
+        }
+        guard (200..<300).contains(http.statusCode) else {
+            throw PantherSecurityError.httpError(http.statusCode)
+        }
+
+        return try JSONDecoder().decode(PantherSecurityAttestationChallenge.self, from: data)
+    }
 }
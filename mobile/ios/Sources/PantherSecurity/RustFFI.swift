@@ -9,6 +9,28 @@ func ps_evaluate_policy(
     _ riskScore: UInt32
 ) -> UInt32
 
+@_silgen_name("ps_explain_policy")
+func ps_explain_policy(
+    _ policy: UnsafePointer<FfiPolicySet>,
+    _ action: FfiStr,
+    _ signals: FfiIntegritySignals,
+    _ attestationStatus: Int32,
+    _ riskScore: UInt32,
+    _ out: UnsafeMutablePointer<FfiEvaluation>
+) -> Int32
+
+@_silgen_name("ps_risk_breakdown")
+func ps_risk_breakdown(
+    _ policy: UnsafePointer<FfiPolicySet>?,
+    _ signals: FfiIntegritySignals,
+    _ attestationStatus: Int32,
+    _ findings: UnsafePointer<FfiFinding>?,
+    _ findingsLen: Int,
+    _ out: UnsafeMutablePointer<FfiRiskContribution>?,
+    _ outCapacity: Int,
+    _ outLen: UnsafeMutablePointer<Int>
+) -> Int32
+
 @_silgen_name("ps_pinning_is_allowed")
 func ps_pinning_is_allowed(_ pinset: FfiPinset, _ presentedHash: FfiStr) -> UInt8
 
@@ -27,11 +49,18 @@ struct FfiIntegritySignals {
 
 struct FfiPolicyConditions {
     var attestation_status: Int32
+    var jailbreak: Int32
+    var root: Int32
     var debugger: Int32
     var hooking: Int32
     var proxy_detected: Int32
     var app_version: FfiStr
     var risk_score_gte: UInt32
+    var all_of_ptr: UnsafePointer<FfiPolicyConditions>?
+    var all_of_len: Int
+    var any_of_ptr: UnsafePointer<FfiPolicyConditions>?
+    var any_of_len: Int
+    var not_ptr: UnsafePointer<FfiPolicyConditions>?
 }
 
 struct FfiPolicyRule {
@@ -47,6 +76,68 @@ struct FfiPolicySet {
     var env: FfiStr
     var rules_ptr: UnsafePointer<FfiPolicyRule>?
     var rules_len: Int
+    var default_decision: UInt32
+    var action_defaults_ptr: UnsafePointer<FfiActionDefault>?
+    var action_defaults_len: Int
+    var overrides_ptr: UnsafePointer<FfiPolicyOverride>?
+    var overrides_len: Int
+    var risk_weights: UnsafePointer<FfiRiskWeights>?
+}
+
+/// `UInt32.max` keeps the default weight.
+struct FfiRiskWeights {
+    var jailbreak: UInt32
+    var root: UInt32
+    var debugger: UInt32
+    var hooking: UInt32
+    var proxy_detected: UInt32
+    var attestation_fail: UInt32
+    var attestation_unknown: UInt32
+    var finding_low: UInt32
+    var finding_medium: UInt32
+    var finding_high: UInt32
+    var finding_critical: UInt32
+    var finding_categories_ptr: UnsafePointer<FfiFindingCategoryWeight>?
+    var finding_categories_len: Int
+}
+
+struct FfiFindingCategoryWeight {
+    var category: FfiStr
+    var pct: UInt32
+}
+
+struct FfiActionDefault {
+    var action: FfiStr
+    var decision: UInt32
+}
+
+struct FfiPolicyOverride {
+    var actions: FfiStrArray
+    var except_actions: FfiStrArray
+    var decision: UInt32
+    var expires_at: FfiStr
+}
+
+struct FfiEvaluation {
+    var decision: UInt32
+    var rule_index: Int64
+    var matched_conditions: UInt32
+    var risk_score: UInt32
+    var default_applied: UInt8
+    var override_index: Int64
+}
+
+struct FfiFinding {
+    var category: FfiStr
+    var severity: UInt32
+}
+
+struct FfiRiskContribution {
+    var factor: UInt32
+    var finding_index: Int64
+    var weight: UInt32
+    var points: UInt32
+    var capped: UInt8
 }
 
 struct FfiStrArray {
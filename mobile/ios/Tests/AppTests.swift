@@ -41,4 +41,8 @@ private final class MockPantherSecurityClient: PantherSecurityClient {
     func sendTelemetry(_ event: PantherSecurityTelemetryRequest, config: PantherSecurityConfiguration) async throws {
         _ = event
     }
+
+    func requestAttestationChallenge(action: String, sessionId: String, config: PantherSecurityConfiguration) async throws -> PantherSecurityAttestationChallenge {
+        PantherSecurityAttestationChallenge(challenge: "challenge_\(action)", expiresAt: "2026-02-06T00:05:00Z")
+    }
 }
@@ -28,6 +28,7 @@ case "$SERVICE" in
     stop_port 8082
     cargo build --manifest-path backend/policy-service/Cargo.toml
     export POLICY_DB_PATH="${POLICY_DB_PATH:-data/policy.db}"
+    export POLICY_ALLOW_EPHEMERAL_KEY="${POLICY_ALLOW_EPHEMERAL_KEY:-true}"
     "$ROOT_DIR/backend/policy-service/target/debug/policy-service"
     ;;
   all)
@@ -39,6 +40,7 @@ case "$SERVICE" in
 
     export TELEMETRY_DB_PATH="${TELEMETRY_DB_PATH:-data/telemetry.db}"
     export POLICY_DB_PATH="${POLICY_DB_PATH:-data/policy.db}"
+    export POLICY_ALLOW_EPHEMERAL_KEY="${POLICY_ALLOW_EPHEMERAL_KEY:-true}"
 
     "$ROOT_DIR/backend/telemetry-ingestion/target/debug/telemetry-ingestion" &
     TELEMETRY_PID=$!
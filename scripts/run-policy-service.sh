@@ -2,5 +2,7 @@
 set -euo pipefail
 
 export POLICY_DB_PATH="${POLICY_DB_PATH:-data/policy.db}"
+# Local development only: sign with a per-process key when POLICY_SIGNING_KEY is unset.
+export POLICY_ALLOW_EPHEMERAL_KEY="${POLICY_ALLOW_EPHEMERAL_KEY:-true}"
 
 cargo run --manifest-path backend/policy-service/Cargo.toml